@@ -2,11 +2,24 @@ use std::cmp::PartialEq;
 use crate::view::IPolygonDrawer::IPolygonDrawer;
 use std::thread::sleep;
 use eframe::{egui, App};
-use crate::geometry::polygon::{Polygon, ConstraintType};
+use crate::geometry::polygon::{Polygon, ConstraintType, FillRule, SolveResult};
 use crate::geometry::point::{Continuity, Point, PointRole};
-use crate::editor::selection::Selection;
+use crate::geometry::relation::{self, EdgeRef, HoleVertexRef, Relation, RelationKind, VertexRef};
+use crate::geometry::vertex_constraint::{self, Guide, VertexConstraint};
+use crate::geometry::boolean::{self, BooleanOp};
+use crate::editor::selection::{self, SelectedVertex, Selection};
+use crate::editor::snapping::{Snap, SnapContext, SnapKind, SnapSettings};
+use crate::editor::history::{History, Snapshot, DEFAULT_HISTORY_DEPTH};
+use crate::editor::creation::DrawingTool;
+use crate::editor::gizmo::{self, GizmoDrag, GizmoHandle};
+use crate::geometry::transform::{self, Affine};
 use crate::geometry::point::PointRole::Vertex;
-use crate::view::{libPolygonDrawer, PolygonDrawer};
+use crate::view::{libPolygonDrawer, relations, PolygonDrawer};
+use crate::view::viewport::Viewport;
+use crate::io::{svg, Scene};
+
+// jeden "klik" kolka (ok. 50 px przewiniecia) to mniej wiecej 10% powiekszenia
+const ZOOM_SPEED: f32 = 0.002;
 
 #[derive(PartialEq, Eq)]
 pub enum DrawMode {
@@ -18,9 +31,19 @@ pub enum DrawMode {
 #[derive()]
 pub struct PolygonApp {
     polygons: Vec<Polygon>,
+    relations: Vec<Relation>,
+    // relacja czekajaca na wskazanie drugiej krawedzi (lewy klik)
+    pending_relation: Option<(RelationKind, EdgeRef)>,
+    // wierzcholek czekajacy na wskazanie wierzcholka innego wielokata (Coincident)
+    pending_coincident: Option<VertexRef>,
+    // rysowanie nowego wielokata klikaniem (None = zwykla edycja)
+    drawing: Option<DrawingTool>,
+    snap_drawing: bool,
     selection: Selection,
     drawer: Box<dyn IPolygonDrawer>,
     draw_mode: DrawMode,
+    antialiasing: bool,
+    line_thickness: f32,
     show_context_menu: bool,
     context_pos: egui::Pos2,
     clicked_vertex: Option<usize>,
@@ -28,12 +51,38 @@ pub struct PolygonApp {
     show_constraint_submenu: bool,
     length_input: Option<f32>,
     length_edge_idx: Option<usize>,
+    arc_edge_idx: Option<usize>,
+    arc_radius_input: f32,
+    arc_clockwise_input: bool,
+    arc_large_input: bool,
+    angle_relation_idx: Option<usize>,
+    angle_input: f32,
+    // edytowany kontur aktywnego wielokata: 0 - zewnetrzny, k - dziura k - 1
+    active_contour: usize,
+    // drugi wielokat operacji logicznej (pierwszy to aktywny)
+    boolean_other: usize,
     is_dragging_polygon: bool,
+    is_dragging_group: bool,
+    // ramka obrotu / skalowania wokol aktywnego wielokata
+    show_gizmo: bool,
+    // geometria jest w jednostkach swiata, na ekran przelicza ja widok
+    view: Viewport,
+    fit_requested: bool,
+    snapping: SnapSettings,
+    // prowadnice postawione przez uzytkownika (tylko pomoc przy rysowaniu, nie ograniczenia)
+    guides: Vec<Guide>,
+    // do czego przyciagnal sie przeciagany punkt w tej klatce - rysujemy znacznik
+    snap_hint: Option<Snap>,
+    // przesuniecie punktu kontrolnego wzgledem kursora z chwili zlapania - uchwyt nie skacze pod kursor
+    control_grab: Option<Point>,
+    gizmo_drag: Option<GizmoDrag>,
     last_mouse_pos: Option<egui::Pos2>,
 
     show_warning_popup: bool,
     warning_text: String,
     show_help_window: bool,
+    solver_status: SolveResult,
+    history: History,
     pub active_polygon: i32,
 }
 
@@ -82,10 +131,17 @@ impl Default for PolygonApp {
 
         Self {
             polygons: vec![polygon],
+            relations: Vec::new(),
+            pending_relation: None,
+            pending_coincident: None,
+            drawing: None,
+            snap_drawing: true,
             active_polygon: 0,
             selection: Selection::new(),
             drawer: Box::new(PolygonDrawer::new()),
             draw_mode: DrawMode::Library,
+            antialiasing: false,
+            line_thickness: 2.0,
             show_context_menu: false,
             context_pos: egui::pos2(0.0, 0.0),
             clicked_vertex: None,
@@ -93,11 +149,30 @@ impl Default for PolygonApp {
             show_constraint_submenu: false,
             length_input: None,
             length_edge_idx: None,
+            arc_edge_idx: None,
+            arc_radius_input: 0.0,
+            arc_clockwise_input: true,
+            arc_large_input: false,
+            angle_relation_idx: None,
+            angle_input: 90.0,
+            active_contour: 0,
+            boolean_other: 0,
             is_dragging_polygon: false,
+            is_dragging_group: false,
+            show_gizmo: true,
+            view: Viewport::default(),
+            fit_requested: false,
+            snapping: SnapSettings::default(),
+            guides: Vec::new(),
+            snap_hint: None,
+            control_grab: None,
+            gizmo_drag: None,
             last_mouse_pos: None,
             show_warning_popup: false,
             warning_text: String::new(),
             show_help_window: false,
+            solver_status: SolveResult::Converged { iterations: 0 },
+            history: History::new(DEFAULT_HISTORY_DEPTH),
         }
 
     }
@@ -148,6 +223,30 @@ impl PolygonApp{
         self.active_polygon = (self.polygons.len() - 1) as i32;
     }
 
+    // otwarta lamana - np. do rysowania otwartych splajnow
+    fn new_polyline(&mut self) {
+        let shift = self.polygons.len() as f32 * 30.0;
+        let polyline = Polygon::new_open(vec![
+            Point { x: 80.0 + shift, y: 300.0, role: PointRole::Vertex, continuity: Continuity::None },
+            Point { x: 180.0 + shift, y: 240.0, role: PointRole::Vertex, continuity: Continuity::None },
+            Point { x: 280.0 + shift, y: 300.0, role: PointRole::Vertex, continuity: Continuity::None },
+        ]);
+        self.polygons.push(polyline);
+        self.active_polygon = (self.polygons.len() - 1) as i32;
+    }
+
+    // konczy rysowanie; przy za malej liczbie punktow rysujemy dalej
+    fn finish_drawing(&mut self, closed: bool) {
+        let Some(polygon) = self.drawing.as_ref().and_then(|tool| tool.finish(closed)) else {
+            return;
+        };
+        self.drawing = None;
+        self.begin_edit("rysowanie wielokata");
+        self.polygons.push(polygon);
+        self.active_polygon = (self.polygons.len() - 1) as i32;
+        self.solve(&[]);
+    }
+
     pub fn remove_active_polygon(&mut self) {
         if self.polygons.is_empty() {
             self.active_polygon = -1;
@@ -158,6 +257,10 @@ impl PolygonApp{
         let current_index = current_index_i32 as usize;
 
         self.polygons.remove(current_index);
+        relation::on_polygon_removed(&mut self.relations, current_index);
+        vertex_constraint::on_polygon_removed(&mut self.polygons, current_index);
+        self.pending_relation = None;
+        self.pending_coincident = None;
 
         if self.polygons.is_empty() {
             self.active_polygon = -1;
@@ -167,6 +270,360 @@ impl PolygonApp{
         }
     }
 
+    // ustawia ograniczenie tylko jesli nie koliduje z reszta wielokata, inaczej pokazuje ostrzezenie
+    fn try_set_constraint(&mut self, poly_idx: usize, edge_idx: usize, constraint: ConstraintType) -> bool {
+        let contour = self.active_contour;
+        let conflicts = self.polygons[poly_idx].contour(contour).check_constraint_change(edge_idx, Some(constraint.clone()));
+        if !conflicts.is_empty() {
+            let reasons: Vec<String> = conflicts.iter().map(|c| c.message()).collect();
+            self.warning_text = format!("Nie można ustawić ograniczenia:\n{}", reasons.join("\n"));
+            self.show_warning_popup = true;
+            return false;
+        }
+        self.polygons[poly_idx].contour_mut(contour).constraints[edge_idx] = Some(constraint);
+        self.solve(&[]);
+        true
+    }
+
+    // ograniczenia wszystkich wielokatow razem z relacjami miedzy nimi
+    fn solve(&mut self, pinned: &[VertexRef]) {
+        self.solver_status = relation::solve_scene(&mut self.polygons, &self.relations, pinned, &[]);
+    }
+
+    // jak `solve`, ale trzymane wierzcholki moga lezec tez w dziurach
+    fn solve_holding(&mut self, held: &[SelectedVertex]) {
+        let pinned: Vec<VertexRef> = held
+            .iter()
+            .filter(|v| v.contour == 0)
+            .map(|v| VertexRef { polygon: v.polygon, vertex: v.vertex })
+            .collect();
+        let hole_pinned: Vec<HoleVertexRef> = held
+            .iter()
+            .filter(|v| v.contour > 0)
+            .map(|v| HoleVertexRef { polygon: v.polygon, contour: v.contour, vertex: v.vertex })
+            .collect();
+        self.solver_status = relation::solve_scene(&mut self.polygons, &self.relations, &pinned, &hole_pinned);
+    }
+
+    // ograniczenie wierzcholka tez probujemy najpierw na kopii sceny; `contour` > 0 to dziura
+    fn try_set_vertex_constraint(&mut self, at: VertexRef, contour: usize, constraint: Option<VertexConstraint>) -> bool {
+        let mut trial = self.polygons.clone();
+        let target = trial[at.polygon].contour_mut(contour);
+        target.ensure_constraints_len();
+        target.vertex_constraints[at.vertex] = constraint;
+        if !relation::solve_scene(&mut trial, &self.relations, &[], &[]).is_feasible() {
+            self.warning_text = "Nie można ustawić ograniczenia wierzchołka:\nkoliduje z pozostałymi ograniczeniami".to_string();
+            self.show_warning_popup = true;
+            return false;
+        }
+        self.polygons = trial;
+        self.solve(&[]);
+        true
+    }
+
+    // relacje tez najpierw sprawdzamy na kopii sceny
+    fn try_add_relation(&mut self, candidate: Relation) -> bool {
+        if let Err(reason) = relation::check_relation(&self.polygons, &self.relations, &candidate) {
+            self.warning_text = format!("Nie można dodać relacji:\n{}", reason);
+            self.show_warning_popup = true;
+            return false;
+        }
+        self.relations.push(candidate);
+        self.solve(&[]);
+        true
+    }
+
+    // cala grupa przesuwa sie razem; zaznaczone wierzcholki sa przypiete, solver dopasowuje reszte
+    fn move_group(&mut self, dx: f32, dy: f32) {
+        let group = self.selection.group.clone();
+        for v in &group {
+            self.polygons[v.polygon].contour_mut(v.contour).move_vertex(v.vertex, dx, dy);
+        }
+        self.solve_holding(&group);
+    }
+
+    // od konca, zeby indeksy pozostalych zaznaczonych wierzcholkow sie nie przesuwaly
+    fn delete_group(&mut self) {
+        let mut group = self.selection.group.clone();
+        group.sort_by_key(|v| std::cmp::Reverse((v.polygon, v.contour, v.vertex)));
+        let mut kept = 0;
+        for v in group {
+            let contour = self.polygons[v.polygon].contour_mut(v.contour);
+            let old_len = contour.vertices.len();
+            contour.remove_vertex(v.vertex);
+            if contour.vertices.len() == old_len {
+                kept += 1; // za malo wierzcholkow, zeby usunac kolejny
+            } else if v.contour == 0 {
+                relation::on_vertex_removed(&mut self.relations, v.polygon, v.vertex, old_len);
+                vertex_constraint::on_vertex_removed(&mut self.polygons, v.polygon, v.vertex);
+            }
+        }
+        self.selection.group.clear();
+        self.selection.selected_vertex = None;
+        self.solve(&[]);
+        if kept > 0 {
+            self.warning_text = format!("Nie usunieto {} wierzcholkow - w konturze zostalo ich za malo", kept);
+            self.show_warning_popup = true;
+        }
+    }
+
+    // ograniczenie (albo jego brak) na kazdej krawedzi o obu koncach w zaznaczeniu;
+    // krawedzie, na ktorych koliduje z sasiadami, zostaja bez zmian
+    fn constrain_group(&mut self, constraint: Option<ConstraintType>) {
+        let mut rejected = Vec::new();
+        for (p_idx, contour, edge) in self.selection.selected_edges(&self.polygons) {
+            let target = self.polygons[p_idx].contour_mut(contour);
+            let conflicts = target.check_constraint_change(edge, constraint.clone());
+            if conflicts.is_empty() {
+                target.ensure_constraints_len();
+                target.constraints[edge] = constraint.clone();
+            } else {
+                rejected.extend(conflicts.iter().map(|c| c.message()));
+            }
+        }
+        self.solve(&[]);
+        if !rejected.is_empty() {
+            rejected.dedup();
+            self.warning_text = format!("Czesci krawedzi nie ograniczono:\n{}", rejected.join("\n"));
+            self.show_warning_popup = true;
+        }
+    }
+
+    // kwadratowa dziura na srodku wielokata - potem mozna ja dowolnie edytowac
+    fn add_hole(&mut self, poly_idx: usize) {
+        let polygon = &self.polygons[poly_idx];
+        let n = polygon.vertices.len() as f32;
+        let center = polygon.vertices.iter().fold(Point::new(0.0, 0.0), |acc, v| acc + *v) * (1.0 / n);
+        let (min, max) = polygon.vertices.iter().fold(
+            (Point::new(f32::MAX, f32::MAX), Point::new(f32::MIN, f32::MIN)),
+            |(min, max), v| (Point::new(min.x.min(v.x), min.y.min(v.y)), Point::new(max.x.max(v.x), max.y.max(v.y))),
+        );
+        let half = (max.x - min.x).min(max.y - min.y) / 6.0;
+        let square = vec![
+            Point::new(center.x - half, center.y - half),
+            Point::new(center.x + half, center.y - half),
+            Point::new(center.x + half, center.y + half),
+            Point::new(center.x - half, center.y + half),
+        ];
+        if !square.iter().all(|p| polygon.contains_point(*p)) {
+            self.warning_text = "Nie ma miejsca na dziure na srodku wielokata".to_string();
+            self.show_warning_popup = true;
+            return;
+        }
+        self.polygons[poly_idx].add_hole(square);
+        self.active_contour = self.polygons[poly_idx].holes.len();
+    }
+
+    // wynik operacji logicznej dokladamy jako nowe wielokaty, oryginaly zostaja
+    fn apply_boolean(&mut self, op: BooleanOp) {
+        let (a, b) = (self.active_polygon as usize, self.boolean_other);
+        if a == b || a >= self.polygons.len() || b >= self.polygons.len() {
+            return;
+        }
+        if !self.polygons[a].closed || !self.polygons[b].closed {
+            self.warning_text = "Operacje logiczne dzialaja tylko na zamknietych wielokatach".to_string();
+            self.show_warning_popup = true;
+            return;
+        }
+        let (subject, clip) = (&self.polygons[a], &self.polygons[b]);
+        let regions = boolean::boolean(&subject.outlines(), subject.fill_rule, &clip.outlines(), clip.fill_rule, op);
+        if regions.is_empty() {
+            self.warning_text = "Wynik operacji jest pusty".to_string();
+            self.show_warning_popup = true;
+            return;
+        }
+
+        self.begin_edit("operacja logiczna");
+        let fill = self.polygons[a].fill;
+        for region in regions {
+            let mut polygon = Polygon::new(region.outer);
+            polygon.fill = fill;
+            for hole in region.holes {
+                polygon.add_hole(hole);
+            }
+            self.polygons.push(polygon);
+        }
+        self.active_polygon = (self.polygons.len() - 1) as i32;
+        self.selection = Selection::new();
+        self.history.commit(&self.polygons, &self.relations, self.active_polygon);
+    }
+
+    // przypiete wierzcholki blokuja obrot i skalowanie tak samo jak przesuniecie
+    fn can_transform(&mut self, poly_idx: usize) -> bool {
+        let polygon = &self.polygons[poly_idx];
+        let pinned = (0..polygon.contour_count()).any(|k| {
+            let contour = polygon.contour(k);
+            (0..contour.vertices.len()).any(|v| contour.is_vertex_pinned(v))
+        });
+        if pinned {
+            self.warning_text = "Wielokata z przypietymi wierzcholkami nie mozna obracac, skalowac ani odbijac".to_string();
+            self.show_warning_popup = true;
+        }
+        !pinned
+    }
+
+    // przeksztalcamy zawsze kopie `original`, a wspolne wierzcholki innych wielokatow ida za wynikiem
+    fn apply_transform(&mut self, poly_idx: usize, original: &Polygon, t: &Affine) -> Vec<String> {
+        let mut polygon = original.clone();
+        let notes = transform::transform_polygon(&mut polygon, t);
+        self.polygons[poly_idx] = polygon;
+        let pinned: Vec<VertexRef> = (0..self.polygons[poly_idx].vertices.len())
+            .map(|vertex| VertexRef { polygon: poly_idx, vertex })
+            .collect();
+        self.solve(&pinned);
+        notes
+    }
+
+    fn report_transform_notes(&mut self, notes: &[String]) {
+        if !notes.is_empty() {
+            self.warning_text = format!("Czesc ograniczen nie przetrwala transformacji:\n{}", notes.join("\n"));
+            self.show_warning_popup = true;
+        }
+    }
+
+    // odbicie wzgledem srodka ramki aktywnego wielokata
+    fn mirror_active(&mut self, horizontal: bool) {
+        let i = self.active_polygon as usize;
+        if i >= self.polygons.len() || !self.can_transform(i) {
+            return;
+        }
+        // margines ramki nie zmienia jej srodka
+        let Some((min, max)) = gizmo::frame(&self.polygons[i], 0.0) else {
+            return;
+        };
+        let center = (min + max) * 0.5;
+        let t = if horizontal { Affine::mirror_horizontal(center) } else { Affine::mirror_vertical(center) };
+        self.begin_edit("odbicie");
+        let original = self.polygons[i].clone();
+        let notes = self.apply_transform(i, &original, &t);
+        self.report_transform_notes(&notes);
+        self.history.commit(&self.polygons, &self.relations, self.active_polygon);
+    }
+
+    // okienko luku startuje z aktualnymi parametrami krawedzi
+    fn open_arc_dialog(&mut self, poly_idx: usize, edge_idx: usize) {
+        let contour = self.polygons[poly_idx].contour(self.active_contour);
+        if let Some(ConstraintType::Arc { radius, clockwise, large_arc, .. }) = contour.get_constraint(edge_idx) {
+            let (_, actual_radius, _, _) = contour.arc_geometry(edge_idx).unwrap_or((Point::new(0.0, 0.0), radius, 0.0, 0.0));
+            self.arc_radius_input = actual_radius;
+            self.arc_clockwise_input = clockwise;
+            self.arc_large_input = large_arc;
+            self.arc_edge_idx = Some(edge_idx);
+        }
+    }
+
+    fn save_scene(&mut self) {
+        let Some(path) = rfd::FileDialog::new().add_filter("Scena", &["json"]).save_file() else {
+            return;
+        };
+        if let Err(e) = Scene::new(self.polygons.clone(), self.relations.clone()).save(&path) {
+            self.warning_text = format!("Nie udalo sie zapisac sceny: {}", e);
+            self.show_warning_popup = true;
+        }
+    }
+
+    fn open_scene(&mut self) {
+        let Some(path) = rfd::FileDialog::new().add_filter("Scena", &["json"]).pick_file() else {
+            return;
+        };
+        match Scene::load(&path) {
+            Ok(scene) => {
+                self.polygons = scene.polygons;
+                self.relations = scene.relations;
+                self.pending_relation = None;
+                self.pending_coincident = None;
+                self.angle_relation_idx = None;
+                self.active_polygon = if self.polygons.is_empty() { -1 } else { 0 };
+                self.selection = Selection::new();
+                self.show_context_menu = false;
+                self.length_edge_idx = None;
+                self.length_input = None;
+                self.arc_edge_idx = None;
+            }
+            Err(e) => {
+                self.warning_text = format!("Nie udalo sie wczytac sceny: {}", e);
+                self.show_warning_popup = true;
+            }
+        }
+    }
+
+    fn export_svg(&mut self) {
+        let Some(path) = rfd::FileDialog::new().add_filter("SVG", &["svg"]).save_file() else {
+            return;
+        };
+        if let Err(e) = svg::export_svg(&self.polygons, &path) {
+            self.warning_text = format!("Nie udalo sie wyeksportowac SVG: {}", e);
+            self.show_warning_popup = true;
+        }
+    }
+
+    fn import_svg(&mut self) {
+        let Some(path) = rfd::FileDialog::new().add_filter("SVG", &["svg"]).pick_file() else {
+            return;
+        };
+        match svg::import_svg_file(&path, &svg::SvgImportOptions::default()) {
+            Ok(polygons) if !polygons.is_empty() => {
+                self.polygons.extend(polygons);
+                self.active_polygon = (self.polygons.len() - 1) as i32;
+            }
+            Ok(_) => {}
+            Err(e) => {
+                self.warning_text = format!("Nie udalo sie zaimportowac SVG: {}", e);
+                self.show_warning_popup = true;
+            }
+        }
+    }
+
+    // zapamietuje stan przed zmiana; komenda zamyka sie dopiero po puszczeniu myszy
+    fn begin_edit(&mut self, label: &'static str) {
+        self.history.begin(label, &self.polygons, &self.relations, self.active_polygon);
+    }
+
+    fn undo(&mut self) {
+        self.history.commit(&self.polygons, &self.relations, self.active_polygon);
+        if let Some(snapshot) = self.history.undo() {
+            self.restore(snapshot);
+        }
+    }
+
+    fn redo(&mut self) {
+        self.history.commit(&self.polygons, &self.relations, self.active_polygon);
+        if let Some(snapshot) = self.history.redo() {
+            self.restore(snapshot);
+        }
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.polygons = snapshot.polygons;
+        self.relations = snapshot.relations;
+        self.pending_relation = None;
+        self.pending_coincident = None;
+        self.angle_relation_idx = None;
+        self.active_polygon = snapshot.active_polygon;
+        self.selection = Selection::new();
+        self.gizmo_drag = None;
+        self.is_dragging_polygon = false;
+        self.is_dragging_group = false;
+        self.last_mouse_pos = None;
+        self.show_context_menu = false;
+        self.length_edge_idx = None;
+        self.length_input = None;
+        self.arc_edge_idx = None;
+    }
+
+    fn export_png(&mut self) {
+        let Some(framebuffer) = self.drawer.framebuffer() else {
+            return;
+        };
+        let Some(path) = rfd::FileDialog::new().add_filter("PNG", &["png"]).save_file() else {
+            return;
+        };
+        if let Err(e) = framebuffer.save_png(&path) {
+            self.warning_text = format!("Nie udalo sie zapisac PNG: {}", e);
+            self.show_warning_popup = true;
+        }
+    }
+
     pub fn active_polygon_mut(&mut self) -> &mut Polygon {
         &mut self.polygons[self.active_polygon as usize]
     }
@@ -184,7 +641,42 @@ impl App for PolygonApp {
 
     fn update(&mut self,ctx: &egui::Context, _frame: &mut eframe::Frame) {
 
+        // Ctrl+Shift+Z sprawdzamy najpierw, bo consume_key ignoruje dodatkowy shift
+        if ctx.input_mut(|inp| inp.consume_key(egui::Modifiers::COMMAND | egui::Modifiers::SHIFT, egui::Key::Z)) {
+            self.redo();
+        } else if ctx.input_mut(|inp| inp.consume_key(egui::Modifiers::COMMAND, egui::Key::Z)) {
+            self.undo();
+        }
+
+        if (self.pending_relation.is_some() || self.pending_coincident.is_some())
+            && ctx.input(|inp| inp.key_pressed(egui::Key::Escape))
+        {
+            self.pending_relation = None;
+            self.pending_coincident = None;
+        }
+
+        if self.drawing.is_some() {
+            if ctx.input(|inp| inp.key_pressed(egui::Key::Escape)) {
+                self.drawing = None;
+            } else if ctx.input(|inp| inp.key_pressed(egui::Key::Enter)) {
+                self.finish_drawing(true);
+            }
+        }
+
         let mut i = self.active_polygon as usize;
+        self.selection.retain_valid(&self.polygons);
+        if !self.selection.group.is_empty() && !ctx.wants_keyboard_input() {
+            if ctx.input(|inp| inp.key_pressed(egui::Key::Delete)) {
+                self.begin_edit("usun zaznaczone");
+                self.delete_group();
+            } else if ctx.input(|inp| inp.key_pressed(egui::Key::Escape)) {
+                self.selection.group.clear();
+            }
+        }
+        // kontur moglo zabrac cofniecie, usuniecie dziury albo zmiana wielokata
+        if self.polygons.get(i).is_none_or(|polygon| self.active_contour >= polygon.contour_count()) {
+            self.active_contour = 0;
+        }
 
         egui::TopBottomPanel::top("topbar").show(ctx, |ui| {
             ui.horizontal(|ui| {
@@ -195,25 +687,146 @@ impl App for PolygonApp {
                 }
                 if ui.radio(self.draw_mode == DrawMode::Bresenham, "Moja implementacja").clicked() {
                     self.draw_mode = DrawMode::Bresenham;
-                    self.drawer = Box::new(crate::view::myPolygonDrawer::MyPolygonDrawer::new());
+                    let mut drawer = crate::view::myPolygonDrawer::MyPolygonDrawer::new();
+                    drawer.set_antialiasing(self.antialiasing);
+                    drawer.set_line_thickness(self.line_thickness);
+                    self.drawer = Box::new(drawer);
+                }
+                if self.draw_mode == DrawMode::Bresenham {
+                    if ui.button("Eksport PNG").clicked() {
+                        self.export_png();
+                    }
+                    if ui.checkbox(&mut self.antialiasing, "Antyaliasing (Wu)").changed() {
+                        self.drawer.set_antialiasing(self.antialiasing);
+                    }
+                    ui.label("Grubosc:");
+                    if ui.add(egui::DragValue::new(&mut self.line_thickness).range(1.0..=10.0).speed(0.1)).changed() {
+                        self.drawer.set_line_thickness(self.line_thickness);
+                    }
                 }
                 ui.separator();
                 if ui.button("Pomoc").clicked(){
                     self.show_help_window = true;
                 }
                 if ui.button("+").clicked(){
+                    self.begin_edit("nowy wielokat");
                     self.newPolygon();
                 }
+                if ui.button("+ lamana").clicked(){
+                    self.begin_edit("nowa lamana");
+                    self.new_polyline();
+                }
+                if ui.selectable_label(self.drawing.is_some(), "Rysuj").on_hover_text("Klikaj wierzcholki; Enter albo klik w pierwszy zamyka, Esc anuluje").clicked(){
+                    self.drawing = match self.drawing {
+                        Some(_) => None,
+                        None => Some(DrawingTool::new(self.snap_drawing)),
+                    };
+                }
+                if ui.checkbox(&mut self.snap_drawing, "Przyciaganie H/V/45").changed() {
+                    if let Some(tool) = self.drawing.as_mut() {
+                        tool.snapping = self.snap_drawing;
+                    }
+                }
                 if ui.button("-").clicked(){
+                    self.begin_edit("usun wielokat");
                     self.remove_active_polygon();
                 }
+                ui.separator();
+                let undo_hint = self.history.undo_label().unwrap_or("brak");
+                if ui.button("Cofnij").on_hover_text(format!("Ctrl+Z: {}", undo_hint)).clicked(){
+                    self.undo();
+                }
+                let redo_hint = self.history.redo_label().unwrap_or("brak");
+                if ui.button("Ponow").on_hover_text(format!("Ctrl+Shift+Z: {}", redo_hint)).clicked(){
+                    self.redo();
+                }
+                ui.separator();
+                if ui.button("Dopasuj").on_hover_text("Pokaz wszystkie wielokaty (kolko - powiekszenie, srodkowy przycisk - przesuwanie)").clicked(){
+                    self.fit_requested = true;
+                }
+                if ui.button(format!("{:.0}%", self.view.zoom * 100.0)).on_hover_text("Powrot do skali 1:1").clicked(){
+                    self.view = Viewport::default();
+                }
+                ui.separator();
+                if ui.button("Zapisz").clicked(){
+                    self.save_scene();
+                }
+                if ui.button("Otworz").clicked(){
+                    self.begin_edit("otworz scene");
+                    self.open_scene();
+                }
+                if ui.button("Eksport SVG").clicked(){
+                    self.export_svg();
+                }
+                if ui.button("Import SVG").clicked(){
+                    self.begin_edit("import SVG");
+                    self.import_svg();
+                }
             });
         });
 
         egui::CentralPanel::default().show(ctx,|ui| {
                 let canvas_size = egui::Vec2::new(600.0, 400.0);
                 let (rect, response) = ui.allocate_exact_size(canvas_size, egui::Sense::click_and_drag());
-                let painter = ui.painter();
+                // przy powiekszeniu ksztalty moga wyjsc poza plotno - przycinamy do niego
+                let painter = ui.painter_at(rect);
+                if std::mem::take(&mut self.fit_requested) {
+                    self.view.fit(&self.polygons, rect);
+                }
+                // kolko myszy powieksza wokol kursora, srodkowy przycisk przesuwa widok
+                if let Some(hover) = response.hover_pos() {
+                    let scroll = ctx.input(|inp| inp.smooth_scroll_delta.y);
+                    if scroll != 0.0 {
+                        self.view.zoom_at(hover, (scroll * ZOOM_SPEED).exp());
+                    }
+                }
+                if response.dragged_by(egui::PointerButton::Middle) {
+                    self.view.pan_by(response.drag_delta());
+                }
+                self.drawer.set_view(self.view);
+                // promienie trafienia sa w pikselach ekranu, a geometria w jednostkach swiata
+                let pixel = self.view.world_len(1.0);
+                let pick = 10.0 * pixel;
+                let grab = 15.0 * pixel;
+                // Alt chwilowo wylacza przyciaganie
+                let snap_off = ctx.input(|inp| inp.modifiers.alt);
+                self.snap_hint = None;
+
+                // siatka i prowadnice sa pod wszystkim, takze pod buforem pikseli
+                let spacing = self.snapping.grid_spacing;
+                if self.snapping.show_grid && spacing > 0.0 && spacing * self.view.zoom >= 6.0 {
+                    let grid_stroke = egui::Stroke::new(1.0, egui::Color32::from_gray(55));
+                    let (min, max) = (self.view.to_world(rect.min), self.view.to_world(rect.max));
+                    let mut x = (min.x / spacing).ceil() * spacing;
+                    while x <= max.x {
+                        let sx = self.view.to_screen(Point::new(x, 0.0)).x;
+                        painter.line_segment([egui::pos2(sx, rect.min.y), egui::pos2(sx, rect.max.y)], grid_stroke);
+                        x += spacing;
+                    }
+                    let mut y = (min.y / spacing).ceil() * spacing;
+                    while y <= max.y {
+                        let sy = self.view.to_screen(Point::new(0.0, y)).y;
+                        painter.line_segment([egui::pos2(rect.min.x, sy), egui::pos2(rect.max.x, sy)], grid_stroke);
+                        y += spacing;
+                    }
+                }
+                let guide_stroke = egui::Stroke::new(1.0, egui::Color32::from_rgb(90, 200, 220));
+                for guide in &self.guides {
+                    let line = match *guide {
+                        Guide::Horizontal(y) => {
+                            let sy = self.view.to_screen(Point::new(0.0, y)).y;
+                            [egui::pos2(rect.min.x, sy), egui::pos2(rect.max.x, sy)]
+                        }
+                        Guide::Vertical(x) => {
+                            let sx = self.view.to_screen(Point::new(x, 0.0)).x;
+                            [egui::pos2(sx, rect.min.y), egui::pos2(sx, rect.max.y)]
+                        }
+                    };
+                    painter.line_segment(line, guide_stroke);
+                }
+
+                // tlo pod bufor pikseli (tryb "Moja implementacja") musi byc pod obramowaniem
+                self.drawer.begin_frame(&painter, rect);
 
                 painter.rect(
                     rect,
@@ -223,68 +836,216 @@ impl App for PolygonApp {
                     egui::StrokeKind::Inside,
                 );
 
+            // tryb wskazywania drugiej krawedzi relacji - szukamy we wszystkich wielokatach
+            if let Some((kind, first)) = self.pending_relation {
+                if response.clicked_by(egui::PointerButton::Primary) {
+                    if let Some(pos) = response.interact_pointer_pos() {
+                        let mouse_point = self.view.to_world(pos);
+                        let picked = self.polygons.iter().enumerate().find_map(|(p_idx, polygon)| {
+                            self.selection
+                                .select_edge(polygon, &mouse_point, pick)
+                                .map(|edge| EdgeRef { polygon: p_idx, edge })
+                        });
+                        if let Some(second) = picked {
+                            self.begin_edit("relacja");
+                            self.try_add_relation(Relation::between(kind, first, second));
+                            self.pending_relation = None;
+                        }
+                    }
+                }
+            }
+
+            // wskazywanie wierzcholka, z ktorym ma sie pokrywac wczesniej wybrany
+            if let Some(source) = self.pending_coincident {
+                if response.clicked_by(egui::PointerButton::Primary) {
+                    if let Some(pos) = response.interact_pointer_pos() {
+                        let mouse_point = self.view.to_world(pos);
+                        let picked = self.polygons.iter().enumerate().find_map(|(p_idx, polygon)| {
+                            polygon
+                                .vertices
+                                .iter()
+                                .position(|v| v.distance(&mouse_point) < pick)
+                                .map(|vertex| VertexRef { polygon: p_idx, vertex })
+                        });
+                        if let Some(target) = picked {
+                            if target.polygon == source.polygon {
+                                self.warning_text = "Wspolny wierzcholek musi nalezec do innego wielokata".to_string();
+                                self.show_warning_popup = true;
+                            } else {
+                                self.begin_edit("wspolny wierzcholek");
+                                self.try_set_vertex_constraint(source, 0, Some(VertexConstraint::Coincident(target)));
+                            }
+                            self.pending_coincident = None;
+                        }
+                    }
+                }
+            }
+
+            // rysowanie: klik dodaje wierzcholek, klik w pierwszy zamyka wielokat
+            if self.drawing.is_some() && response.clicked_by(egui::PointerButton::Primary) {
+                if let Some(pos) = response.interact_pointer_pos() {
+                    let cursor = self.view.to_world(pos);
+                    if self.drawing.as_ref().is_some_and(|tool| tool.is_near_start(cursor, pixel)) {
+                        self.finish_drawing(true);
+                    } else if let Some(tool) = self.drawing.as_mut() {
+                        tool.add_point(cursor);
+                    }
+                }
+            }
+
             // Obsługa kliknięcia/podciągnięcia wierzchołka
-            if response.dragged_by(egui::PointerButton::Primary) {
+            if self.drawing.is_none() && response.dragged_by(egui::PointerButton::Primary) {
+                // cale przeciagniecie to jeden krok historii
+                self.begin_edit("przesuniecie");
 
                 if let Some(pos) = response.interact_pointer_pos() {
-                    let mouse_point = Point { x: pos.x, y: pos.y, role: Vertex, continuity: Continuity::None };
+                    let mouse_point = self.view.to_world(pos);
+
+                    let shift = ctx.input(|inp| inp.modifiers.shift);
+                    let mut over_shape = false;
 
                     ///HERE CHANGE
+                    // otwarta lamana nie ma wnetrza - lapiemy ja za krawedz
                     for (i, polygon) in self.polygons.iter().enumerate() {
-                        if polygon.contains_point(mouse_point) {
-                            self.active_polygon = i as i32;
+                        let on_polyline = !polygon.closed && self.selection.select_edge(polygon, &mouse_point, pick).is_some();
+                        if polygon.contains_point(mouse_point) || on_polyline {
+                            over_shape = true;
+                            if self.active_polygon != i as i32 && self.selection.band.is_none() {
+                                self.active_polygon = i as i32;
+                                self.active_contour = 0;
+                            }
                         }
                     }
 
-
-                    if self.selection.selected_vertex.is_none() && !self.is_dragging_polygon {
-                        if self.selection.select_vertex(&self.polygons[i], mouse_point, 15.0).is_none() {
-                            self.is_dragging_polygon = true;
+                    let idle = self.selection.selected_vertex.is_none()
+                        && !self.is_dragging_polygon
+                        && !self.is_dragging_group
+                        && self.selection.band.is_none();
+                    if idle {
+                        let grabbed = selection::vertex_at(&self.polygons, mouse_point, grab);
+                        if grabbed.is_some_and(|v| !shift && self.selection.is_selected(v)) {
+                            // zlapany wierzcholek z zaznaczenia - przesuwamy cala grupe
+                            self.is_dragging_group = true;
                             self.last_mouse_pos = Some(pos);
+                        } else if grabbed.is_none() && (shift || !over_shape) {
+                            // prostokat zaznaczania od miejsca wcisniecia; z Shiftem dokladamy do grupy
+                            let origin = ctx.input(|inp| inp.pointer.press_origin()).unwrap_or(pos);
+                            if !shift {
+                                self.selection.group.clear();
+                            }
+                            self.selection.band = Some((self.view.to_world(origin), mouse_point));
+                        } else if i < self.polygons.len() {
+                            // wierzcholka szukamy w konturze zewnetrznym i we wszystkich dziurach
+                            let hit = (0..self.polygons[i].contour_count())
+                                .find(|&k| self.selection.select_vertex(self.polygons[i].contour(k), mouse_point, grab).is_some());
+                            match hit {
+                                Some(k) => self.active_contour = k,
+                                None => {
+                                    self.is_dragging_polygon = true;
+                                    self.last_mouse_pos = Some(pos);
+                                }
+                            }
                         }
                     }
 
-                    if let Some(idx) = self.selection.selected_vertex {
-                        let dx = pos.x - self.polygons[i].vertices[idx].x;
-                        let dy = pos.y - self.polygons[i].vertices[idx].y;
-                        self.polygons[i].move_vertex(self.selection.selected_vertex.unwrap(), dx, dy);
-
-                        self.polygons[i].apply_constraints();
+                    if let Some((start, _)) = self.selection.band {
+                        self.selection.band = Some((start, mouse_point));
+                    }
+                    else if self.is_dragging_group {
+                        if let Some(last_pos) = self.last_mouse_pos {
+                            let (dx, dy) = self.view.world_delta(pos - last_pos);
+                            self.move_group(dx, dy);
+                            self.last_mouse_pos = Some(pos);
+                        }
+                    }
+                    else if let Some(idx) = self.selection.selected_vertex {
+                        let c = self.active_contour;
+                        // przyciaganie; katy liczymy od poprzedniego wierzcholka konturu
+                        let contour = self.polygons[i].contour(c);
+                        let previous = contour.incoming_edge(idx).map(|e| contour.vertices[e]);
+                        let dragged = SelectedVertex { polygon: i, contour: c, vertex: idx };
+                        let snap = if snap_off {
+                            None
+                        } else {
+                            let context = SnapContext {
+                                settings: &self.snapping,
+                                guides: &self.guides,
+                                polygons: &self.polygons,
+                                pixel,
+                                exclude: Some(dragged),
+                                anchor: previous,
+                            };
+                            context.snap(mouse_point)
+                        };
+                        let target = snap.map_or(mouse_point, |snap| snap.point);
+                        let contour = self.polygons[i].contour_mut(c);
+                        let dx = target.x - contour.vertices[idx].x;
+                        let dy = target.y - contour.vertices[idx].y;
+                        contour.move_vertex(idx, dx, dy);
+                        // przypiety wierzcholek albo prowadnica moga nie puscic go do celu - znacznik tylko,
+                        // gdy wierzcholek naprawde tam stoi
+                        let moved_to = contour.vertices[idx];
+                        self.snap_hint = snap.filter(|snap| snap.point.distance(&moved_to) < 0.5 * pixel);
+
+                        // przeciagany wierzcholek jest przypiety - solver rusza tylko pozostale
+                        self.solve_holding(&[dragged]);
                     }
                     else if self.is_dragging_polygon {
                         if let Some(last_pos) = self.last_mouse_pos {
-                            let dx = pos.x - last_pos.x;
-                            let dy = pos.y - last_pos.y;
-                            for v in &mut self.polygons[i].vertices {
-                                v.x += dx;
-                                v.y += dy;
-                            }
-                            for constraint_opt in &mut self.polygons[i].constraints{
-                                if let Some(ConstraintType::Bezier{control1, control2, ..}) = constraint_opt{
-                                    control1.x += dx;
-                                    control1.y += dy;
-                                    control2.x += dx;
-                                    control2.y += dy;
-                                }
-                            }
+                            // przypiete wierzcholki i prowadnice ograniczaja przesuniecie calego wielokata
+                            let (dx, dy) = self.view.world_delta(pos - last_pos);
+                            let (dx, dy) = self.polygons[i].constrain_translation(dx, dy);
+                            self.polygons[i].translate(dx, dy);
+                            // wspolne wierzcholki w innych wielokatach ida za przesuwanym
+                            let pinned: Vec<VertexRef> = (0..self.polygons[i].vertices.len())
+                                .map(|vertex| VertexRef { polygon: i, vertex })
+                                .collect();
+                            self.solve(&pinned);
                             self.last_mouse_pos = Some(pos);
                         }
                     }
                 }
             } else {
+                if let Some((a, b)) = self.selection.band.take() {
+                    self.selection.select_in_rect(&self.polygons, a, b);
+                }
                 self.selection.selected_vertex = None;
                 self.is_dragging_polygon = false;
+                self.is_dragging_group = false;
                 self.last_mouse_pos = None;
             }
 
+            // Shift+klik dodaje wierzcholek do zaznaczenia albo go z niego wyjmuje, klik w puste miejsce czysci
+            let picking = self.pending_relation.is_some() || self.pending_coincident.is_some() || self.drawing.is_some();
+            if !picking && response.clicked_by(egui::PointerButton::Primary) {
+                if let Some(pos) = response.interact_pointer_pos() {
+                    let mouse_point = self.view.to_world(pos);
+                    let shift = ctx.input(|inp| inp.modifiers.shift);
+                    match selection::vertex_at(&self.polygons, mouse_point, pick) {
+                        Some(v) if shift => self.selection.toggle(v),
+                        None if !shift => self.selection.group.clear(),
+                        _ => {}
+                    }
+                }
+            }
+
 
 
-            if response.clicked_by(egui::PointerButton::Secondary){
+            if self.drawing.is_none() && response.clicked_by(egui::PointerButton::Secondary){
                     if let Some(pos) = response.interact_pointer_pos() {
-                        let mouse_point = Point { x: pos.x, y: pos.y, role: Vertex, continuity: Continuity::None };
-
-                        self.clicked_vertex = self.selection.select_vertex(&self.polygons[i], mouse_point, 10.0);
-                        self.clicked_edge = self.selection.select_edge(&self.polygons[i], &mouse_point, 10.0);
+                        let mouse_point = self.view.to_world(pos);
+
+                        // menu dotyczy konturu (zewnetrznego albo dziury), w ktory trafil klik
+                        let count = self.polygons[i].contour_count();
+                        let hit = (0..count).find(|&k| {
+                            let contour = self.polygons[i].contour(k);
+                            contour.vertices.iter().any(|v| v.distance(&mouse_point) < pick)
+                                || self.selection.select_edge(contour, &mouse_point, pick).is_some()
+                        });
+                        self.active_contour = hit.unwrap_or(0);
+                        let contour = self.polygons[i].contour(self.active_contour);
+                        self.clicked_vertex = self.selection.select_vertex(contour, mouse_point, pick);
+                        self.clicked_edge = self.selection.select_edge(contour, &mouse_point, pick);
                         self.context_pos = pos;
                         self.show_context_menu = true;
                         self.show_constraint_submenu = false;
@@ -294,58 +1055,240 @@ impl App for PolygonApp {
             for polygon in self.polygons.iter_mut(){
                 self.drawer.draw(&painter, polygon);
 
+            }
+            self.drawer.end_frame(&painter);
+            relations::draw_relations(&painter, &self.view, &self.polygons, &self.relations);
+
+            // zaznaczenie grupowe: obwodki wokol wierzcholkow i prostokat je obejmujacy
+            let highlight = egui::Color32::from_rgb(255, 230, 90);
+            for v in &self.selection.group {
+                let p = self.view.to_screen(self.polygons[v.polygon].contour(v.contour).vertices[v.vertex]);
+                painter.circle_stroke(p, 8.0, egui::Stroke::new(2.0, highlight));
+            }
+            if let Some((min, max)) = self.selection.bounding_box(&self.polygons).filter(|_| self.selection.group.len() > 1) {
+                let rect = egui::Rect::from_min_max(self.view.to_screen(min), self.view.to_screen(max)).expand(12.0);
+                painter.rect_stroke(rect, 0.0, egui::Stroke::new(1.0, highlight), egui::StrokeKind::Outside);
+            }
+            if let Some((a, b)) = self.selection.band {
+                let rect = egui::Rect::from_two_pos(self.view.to_screen(a), self.view.to_screen(b));
+                painter.rect_filled(rect, 0.0, egui::Color32::from_rgba_unmultiplied(120, 170, 255, 40));
+                painter.rect_stroke(rect, 0.0, egui::Stroke::new(1.0, egui::Color32::LIGHT_BLUE), egui::StrokeKind::Inside);
+            }
+
+            // znacznik przyciagniecia: romb w punkcie, dla kata jeszcze linia od poprzedniego wierzcholka
+            if let Some(snap) = self.snap_hint {
+                let snap_color = egui::Color32::from_rgb(255, 120, 220);
+                let at = self.view.to_screen(snap.point);
+                if let SnapKind::Angle { from, .. } = snap.kind {
+                    let band = [self.view.to_screen(from), at];
+                    self.drawer.draw_dashed_polyline(&painter, &band, egui::Stroke::new(1.0, snap_color));
+                }
+                let diamond = [at + egui::vec2(0.0, -6.0), at + egui::vec2(6.0, 0.0), at + egui::vec2(0.0, 6.0), at + egui::vec2(-6.0, 0.0)];
+                painter.add(egui::Shape::closed_line(diamond.to_vec(), egui::Stroke::new(1.5, snap_color)));
+                painter.text(at + egui::vec2(9.0, -9.0), egui::Align2::LEFT_BOTTOM, snap.kind.label(), egui::FontId::proportional(12.0), snap_color);
+            }
+
+            // podglad rysowanego wielokata z "gumka" do kursora
+            if let Some(tool) = &self.drawing {
+                let stroke = egui::Stroke::new(1.5, egui::Color32::WHITE);
+                for pair in tool.points.windows(2) {
+                    painter.line_segment([self.view.to_screen(pair[0]), self.view.to_screen(pair[1])], stroke);
+                }
+                for p in &tool.points {
+                    painter.circle_filled(self.view.to_screen(*p), 4.0, egui::Color32::RED);
+                }
+                if let (Some(last), Some(hover)) = (tool.points.last(), response.hover_pos()) {
+                    let cursor = self.view.to_world(hover);
+                    let last = self.view.to_screen(*last);
+                    if tool.is_near_start(cursor, pixel) {
+                        let first = self.view.to_screen(tool.points[0]);
+                        painter.circle_stroke(first, 9.0, egui::Stroke::new(2.0, egui::Color32::LIGHT_GREEN));
+                        painter.line_segment([last, first], egui::Stroke::new(1.0, egui::Color32::LIGHT_GREEN));
+                    } else {
+                        let (next, constraint) = tool.preview(cursor);
+                        let next = self.view.to_screen(next);
+                        let band = [last, next];
+                        self.drawer.draw_dashed_polyline(&painter, &band, egui::Stroke::new(1.0, egui::Color32::LIGHT_BLUE));
+                        let label = match constraint {
+                            Some(ConstraintType::Horizontal) => "H",
+                            Some(ConstraintType::Vertical) => "V",
+                            Some(ConstraintType::Diagonal45) => "D",
+                            _ => "",
+                        };
+                        painter.text(
+                            egui::pos2((last.x + next.x) / 2.0, (last.y + next.y) / 2.0 - 10.0),
+                            egui::Align2::CENTER_CENTER,
+                            label,
+                            egui::FontId::monospace(14.0),
+                            egui::Color32::YELLOW,
+                        );
+                    }
+                }
             }
                 //self.drawer.draw(&painter, &mut self.polygons[i]);
 
 
 
-            let mut moved_controls: Vec<(usize, bool, egui::Vec2)> = Vec::new();
+            // uchwyty, menu i okienka dotycza wybranego konturu (zewnetrznego albo dziury)
+            let c = self.active_contour;
+            // (krawedz, numer punktu kontrolnego od 1, nowe polozenie w swiecie)
+            let mut moved_controls: Vec<(usize, u8, Point)> = Vec::new();
 
-            for (e_idx, constraint_opt) in self.polygons[i].constraints.iter().enumerate() {
-                if let Some(ConstraintType::Bezier { control1, control2, .. }) = constraint_opt {
-                    // Pozycje kontrolek
-                    let c1_pos = egui::pos2(control1.x, control1.y);
-                    let c2_pos = egui::pos2(control2.x, control2.y);
+            for (e_idx, constraint_opt) in self.polygons[i].contour(c).constraints.iter().enumerate() {
+                let Some(controls) = constraint_opt.as_ref().and_then(|c| c.bezier_controls()) else {
+                    continue;
+                };
+                for (k, control) in controls.iter().enumerate() {
+                    let control_id = (k + 1) as u8;
+                    let c_pos = self.view.to_screen(*control);
 
-                    // Zrób z nich "uchwyty"
-                    let c1_response = ui.interact(
-                        egui::Rect::from_center_size(c1_pos, egui::vec2(10.0, 10.0)),
-                        ui.id().with(format!("ctrl1_{}", e_idx)),
-                        egui::Sense::drag(),
-                    );
-                    let c2_response = ui.interact(
-                        egui::Rect::from_center_size(c2_pos, egui::vec2(10.0, 10.0)),
-                        ui.id().with(format!("ctrl2_{}", e_idx)),
+                    // Zrób z niego "uchwyt"
+                    let c_response = ui.interact(
+                        egui::Rect::from_center_size(c_pos, egui::vec2(10.0, 10.0)),
+                        ui.id().with(format!("ctrl{}_{}", control_id, e_idx)),
                         egui::Sense::drag(),
                     );
 
-                    // Rysuj uchwyty
-                    painter.circle_filled(c1_pos, 5.0, egui::Color32::from_rgb(180, 180, 180));
-                    painter.circle_filled(c2_pos, 5.0, egui::Color32::from_rgb(180, 180, 180));
+                    // Rysuj uchwyt
+                    painter.circle_filled(c_pos, 5.0, egui::Color32::from_rgb(180, 180, 180));
 
-                    // Jeśli przeciągnięto, zapisz delta
-                    if c1_response.dragged() {
-                        moved_controls.push((e_idx, true, c1_response.drag_delta()));
+                    // Jeśli przeciągnięto, zapisz nowe położenie (przyciąganie potrzebuje położenia, nie przyrostu)
+                    if let (true, Some(pos)) = (c_response.dragged(), c_response.interact_pointer_pos()) {
+                        let pointer = self.view.to_world(pos);
+                        if c_response.drag_started() || self.control_grab.is_none() {
+                            self.control_grab = Some(*control - pointer);
+                        }
+                        moved_controls.push((e_idx, control_id, pointer + self.control_grab.unwrap()));
                     }
-                    if c2_response.dragged() {
-                        moved_controls.push((e_idx, false, c2_response.drag_delta()));
+                    if c_response.drag_stopped() {
+                        self.control_grab = None;
                     }
                 }
             }
 
-            for (e_idx, is_control1, delta) in moved_controls {
-                if let Some(ConstraintType::Bezier { control1, control2, .. }) =
-                    self.polygons[i].constraints.get_mut(e_idx).and_then(|c| c.as_mut())
-                {
-                    if is_control1 {
-                        control1.x += delta.x;
-                        control1.y += delta.y;
-                    } else {
-                        control2.x += delta.x;
-                        control2.y += delta.y;
+            if !moved_controls.is_empty() {
+                self.begin_edit("punkt kontrolny");
+            }
+            for (e_idx, control_id, wanted) in moved_controls {
+                // kat liczymy od wierzcholka, do ktorego punkt kontrolny jest przyczepiony
+                let contour = self.polygons[i].contour(c);
+                let count = contour.constraints[e_idx].as_ref().and_then(|c| c.bezier_controls()).map_or(0, |c| c.len());
+                let anchor = if count > 1 && control_id as usize == count { (e_idx + 1) % contour.vertices.len() } else { e_idx };
+                if !snap_off {
+                    let context = SnapContext {
+                        settings: &self.snapping,
+                        guides: &self.guides,
+                        polygons: &self.polygons,
+                        pixel,
+                        exclude: None,
+                        anchor: Some(contour.vertices[anchor]),
+                    };
+                    self.snap_hint = context.snap(wanted);
+                }
+                let target = self.snap_hint.map_or(wanted, |snap| snap.point);
+                if let Some(constraint) = self.polygons[i].contour_mut(c).constraints.get_mut(e_idx).and_then(|c| c.as_mut()) {
+                    if let Some(control) = constraint.bezier_controls_mut().into_iter().nth(control_id as usize - 1) {
+                        control.x = target.x;
+                        control.y = target.y;
                     }
 
-                    self.polygons[i].enforce_continuity_after_control_move(e_idx, if is_control1 { 1 } else { 2 });
+                    self.polygons[i].contour_mut(c).enforce_continuity_after_control_move(e_idx, control_id);
+                }
+            }
+
+            // uchwyt w polowie kazdego luku - przeciagniecie ustawia luk przez ten punkt
+            let mut moved_arcs: Vec<(usize, Point)> = Vec::new();
+            for e_idx in 0..self.polygons[i].contour(c).constraints.len() {
+                let Some(mid) = self.polygons[i].contour(c).arc_midpoint(e_idx) else {
+                    continue;
+                };
+                let mid_pos = self.view.to_screen(mid);
+                let arc_response = ui.interact(
+                    egui::Rect::from_center_size(mid_pos, egui::vec2(10.0, 10.0)),
+                    ui.id().with(format!("arc_mid_{}", e_idx)),
+                    egui::Sense::drag(),
+                );
+                painter.circle_stroke(mid_pos, 5.0, egui::Stroke::new(1.5, egui::Color32::LIGHT_BLUE));
+                if arc_response.dragged() {
+                    let (dx, dy) = self.view.world_delta(arc_response.drag_delta());
+                    moved_arcs.push((e_idx, Point::new(mid.x + dx, mid.y + dy)));
+                }
+            }
+            if !moved_arcs.is_empty() {
+                self.begin_edit("luk");
+            }
+            for (e_idx, through) in moved_arcs {
+                if self.polygons[i].contour_mut(c).set_arc_through(e_idx, through) {
+                    self.solve(&[]);
+                }
+            }
+
+            // ramka transformacji: narozniki skaluja jednakowo, srodki bokow w jednej osi, kolko na gorze obraca
+            let gizmo_visible = self.show_gizmo
+                && i < self.polygons.len()
+                && self.drawing.is_none()
+                && self.selection.group.is_empty();
+            let mut gizmo_started: Option<(GizmoHandle, Point)> = None;
+            let mut gizmo_pointer: Option<egui::Pos2> = None;
+            let mut gizmo_stopped = false;
+            if let Some((min, max)) = gizmo_visible.then(|| gizmo::frame(&self.polygons[i], pixel)).flatten() {
+                let gizmo_stroke = egui::Stroke::new(1.0, egui::Color32::from_rgb(255, 170, 60));
+                let frame = [
+                    Point::new(min.x, min.y),
+                    Point::new(max.x, min.y),
+                    Point::new(max.x, max.y),
+                    Point::new(min.x, max.y),
+                    Point::new(min.x, min.y),
+                ]
+                .map(|corner| self.view.to_screen(corner));
+                self.drawer.draw_dashed_polyline(&painter, &frame, gizmo_stroke);
+                for (handle, at) in gizmo::handles(min, max, pixel) {
+                    let handle_pos = self.view.to_screen(at);
+                    let handle_response = ui.interact(
+                        egui::Rect::from_center_size(handle_pos, egui::vec2(12.0, 12.0)),
+                        ui.id().with(format!("gizmo_{:?}", handle)),
+                        egui::Sense::drag(),
+                    );
+                    match handle {
+                        GizmoHandle::Rotate => {
+                            painter.line_segment([self.view.to_screen(Point::new(at.x, min.y)), handle_pos], gizmo_stroke);
+                            painter.circle(handle_pos, 6.0, egui::Color32::DARK_GRAY, gizmo_stroke);
+                        }
+                        GizmoHandle::Scale { .. } => {
+                            let square = egui::Rect::from_center_size(handle_pos, egui::vec2(8.0, 8.0));
+                            painter.rect(square, 0.0, egui::Color32::DARK_GRAY, gizmo_stroke, egui::StrokeKind::Middle);
+                        }
+                    }
+                    if handle_response.drag_started() {
+                        // kat i skala liczone od miejsca wcisniecia, a nie od progu przeciagania
+                        let origin = ctx.input(|inp| inp.pointer.press_origin()).map(|pos| self.view.to_world(pos));
+                        gizmo_started = Some((handle, origin.unwrap_or(at)));
+                    }
+                    if handle_response.dragged() {
+                        gizmo_pointer = handle_response.interact_pointer_pos();
+                    }
+                    if handle_response.drag_stopped() {
+                        gizmo_stopped = true;
+                    }
+                }
+            }
+
+            if let Some((handle, start)) = gizmo_started {
+                if self.can_transform(i) {
+                    self.begin_edit("transformacja");
+                    self.gizmo_drag = GizmoDrag::new(handle, self.polygons[i].clone(), start, pixel);
+                }
+            }
+            if let (Some(pos), Some(drag)) = (gizmo_pointer, self.gizmo_drag.take()) {
+                // Shift przyciaga obrot do pelnych 15 stopni
+                let snap = ctx.input(|inp| inp.modifiers.shift);
+                let t = drag.transform(self.view.to_world(pos), snap);
+                let notes = self.apply_transform(i, &drag.original, &t);
+                self.gizmo_drag = Some(GizmoDrag { notes, ..drag });
+            }
+            if gizmo_stopped {
+                if let Some(drag) = self.gizmo_drag.take() {
+                    self.report_transform_notes(&drag.notes);
                 }
             }
 
@@ -366,49 +1309,191 @@ impl App for PolygonApp {
 
                                 if let Some(v_idx) = self.clicked_vertex {
                                     if ui.button("usun wierzcholek").clicked(){
-                                        self.polygons[i].remove_vertex(v_idx);
+                                        self.begin_edit("usun wierzcholek");
+                                        let old_len = self.polygons[i].contour(c).vertices.len();
+                                        self.polygons[i].contour_mut(c).remove_vertex(v_idx);
+                                        // relacje i wspolne wierzcholki wskazuja tylko kontury zewnetrzne
+                                        if c == 0 && self.polygons[i].vertices.len() < old_len {
+                                            relation::on_vertex_removed(&mut self.relations, i, v_idx, old_len);
+                                            vertex_constraint::on_vertex_removed(&mut self.polygons, i, v_idx);
+                                        }
                                         self.show_context_menu = false;
                                     }
                                     if ui.button("Ustaw G0").clicked() {
+                                        self.begin_edit("Ustaw G0");
                                         if let Some(v_idx) = self.clicked_vertex {
-                                            self.polygons[i].vertices[v_idx].continuity = Continuity::G0;
-                                            self.polygons[i].apply_constraints();
+                                            self.polygons[i].contour_mut(c).vertices[v_idx].continuity = Continuity::G0;
+                                            self.solve(&[]);
                                         }
                                     }
                                     if ui.button("Ustaw C1").clicked() {
+                                        self.begin_edit("Ustaw C1");
                                         if let Some(v_idx) = self.clicked_vertex {
-                                            self.polygons[i].vertices[v_idx].continuity = Continuity::C1;
-                                            self.polygons[i].apply_constraints();
+                                            self.polygons[i].contour_mut(c).vertices[v_idx].continuity = Continuity::C1;
+                                            self.solve(&[]);
                                         }
                                     }
                                     if ui.button("Ustaw G1").clicked() {
+                                        self.begin_edit("Ustaw G1");
+                                        if let Some(v_idx) = self.clicked_vertex {
+                                            self.polygons[i].contour_mut(c).vertices[v_idx].continuity = Continuity::G1;
+                                            self.solve(&[]);
+                                        }
+                                    }
+                                    if ui.button("Ustaw G2").clicked() {
+                                        self.begin_edit("Ustaw G2");
                                         if let Some(v_idx) = self.clicked_vertex {
-                                            self.polygons[i].vertices[v_idx].continuity = Continuity::G1;
-                                            self.polygons[i].apply_constraints();
+                                            self.polygons[i].contour_mut(c).vertices[v_idx].continuity = Continuity::G2;
+                                            self.solve(&[]);
+                                        }
+                                    }
+                                    ui.separator();
+                                    let at = VertexRef { polygon: i, vertex: v_idx };
+                                    let v = self.polygons[i].contour(c).vertices[v_idx];
+                                    let pin_label = if self.polygons[i].contour(c).is_vertex_pinned(v_idx) { "Odepnij" } else { "Przypnij" };
+                                    if ui.button(pin_label).clicked() {
+                                        self.begin_edit(pin_label);
+                                        let constraint = (!self.polygons[i].contour(c).is_vertex_pinned(v_idx)).then_some(VertexConstraint::Pinned { x: v.x, y: v.y });
+                                        self.try_set_vertex_constraint(at, c, constraint);
+                                        self.show_context_menu = false;
+                                    }
+                                    if ui.button("Na prowadnicy poziomej").clicked() {
+                                        self.begin_edit("prowadnica pozioma");
+                                        self.try_set_vertex_constraint(at, c, Some(VertexConstraint::OnGuide(Guide::Horizontal(v.y))));
+                                        self.show_context_menu = false;
+                                    }
+                                    if ui.button("Na prowadnicy pionowej").clicked() {
+                                        self.begin_edit("prowadnica pionowa");
+                                        self.try_set_vertex_constraint(at, c, Some(VertexConstraint::OnGuide(Guide::Vertical(v.x))));
+                                        self.show_context_menu = false;
+                                    }
+                                    if c == 0 && ui.button("Wspolny z wierzcholkiem...").clicked() {
+                                        self.pending_coincident = Some(at);
+                                        self.pending_relation = None;
+                                        self.show_context_menu = false;
+                                    }
+                                    if self.polygons[i].contour(c).vertex_constraint(v_idx).is_some() && ui.button("usun ograniczenie wierzcholka").clicked() {
+                                        self.begin_edit("usun ograniczenie wierzcholka");
+                                        self.try_set_vertex_constraint(at, c, None);
+                                        self.show_context_menu = false;
+                                    }
+                                    ui.separator();
+                                    let existing = self.relations.iter().position(|r| matches!(r, Relation::FixedAngle { at: a, .. } if *a == at));
+                                    if c == 0 && ui.button("Staly kat").clicked() {
+                                        match existing {
+                                            Some(rel_idx) => self.angle_relation_idx = Some(rel_idx),
+                                            None => {
+                                                // startujemy z obecnym katem, wartosc zmienia sie w okienku
+                                                self.begin_edit("Staly kat");
+                                                let degrees = relation::current_angle(&self.polygons, at).unwrap_or(90.0);
+                                                if self.try_add_relation(Relation::FixedAngle { at, degrees }) {
+                                                    self.angle_relation_idx = Some(self.relations.len() - 1);
+                                                }
+                                            }
+                                        }
+                                        if let Some(Relation::FixedAngle { degrees, .. }) = self.angle_relation_idx.and_then(|k| self.relations.get(k)) {
+                                            self.angle_input = *degrees;
+                                        }
+                                        self.show_context_menu = false;
+                                    }
+                                    if let Some(rel_idx) = existing.filter(|_| c == 0) {
+                                        if ui.button("usun kat").clicked() {
+                                            self.begin_edit("usun kat");
+                                            self.relations.remove(rel_idx);
+                                            self.angle_relation_idx = None;
+                                            self.show_context_menu = false;
                                         }
                                     }
                                 }else if let Some(e_idx) = self.clicked_edge {
                                     if ui.button("dodaj wierzcholek").clicked(){
-                                        self.polygons[i].add_vertex_mid_edge(e_idx, e_idx+1);
+                                        self.begin_edit("dodaj wierzcholek");
+                                        self.polygons[i].contour_mut(c).add_vertex_mid_edge(e_idx, e_idx+1);
+                                        if c == 0 {
+                                            relation::on_vertex_inserted(&mut self.relations, i, e_idx);
+                                            vertex_constraint::on_vertex_inserted(&mut self.polygons, i, e_idx);
+                                        }
                                         self.show_context_menu = false;
-                                        self.polygons[i].apply_constraints();
+                                        self.solve(&[]);
                                     }
                                     if ui.button("dodaj ograniczenie").clicked(){
                                         self.show_constraint_submenu = !self.show_constraint_submenu;
-                                        self.polygons[i].apply_constraints();
+                                        self.solve(&[]);
                                         //self.show_context_menu = false;
                                     }
                                     if ui.button("usun ograniczenie").clicked(){
-                                        if e_idx < self.polygons[i].constraints.len(){
-                                            self.polygons[i].constraints[e_idx] = None;
+                                        self.begin_edit("usun ograniczenie");
+                                        if e_idx < self.polygons[i].contour(c).constraints.len(){
+                                            self.polygons[i].contour_mut(c).constraints[e_idx] = None;
                                         }
                                         self.show_context_menu = false;
-                                        self.polygons[i].apply_constraints();
+                                        self.solve(&[]);
                                     }
-                                    if ui.button("uzyj antyaliasingu").clicked(){
-                                        //todo!();
-                                        eprintln!("egui robi to automatycznie!");
-                                        self.polygons[i].apply_constraints();
+                                    // dziura zawsze jest zamknieta, a wielokat z dziurami tez
+                                    if c == 0 && self.polygons[i].holes.is_empty() && self.polygons[i].closed && ui.button("rozetnij tutaj (otworz)").clicked() {
+                                        self.begin_edit("otworz wielokat");
+                                        let len = self.polygons[i].vertices.len();
+                                        self.polygons[i].open_at(e_idx);
+                                        relation::on_chain_opened(&mut self.relations, i, e_idx, len);
+                                        vertex_constraint::on_chain_opened(&mut self.polygons, i, e_idx, len);
+                                        self.solve(&[]);
+                                        self.show_context_menu = false;
+                                    }
+                                    // relacje laczą tylko krawedzie konturow zewnetrznych
+                                    if c == 0 {
+                                        ui.separator();
+                                        let edge = EdgeRef { polygon: i, edge: e_idx };
+                                        // druga krawedz wskazuje sie lewym kliknieciem, takze w innym wielokacie
+                                        for (label, kind) in [
+                                            ("Rownolegla do...", RelationKind::Parallel),
+                                            ("Prostopadla do...", RelationKind::Perpendicular),
+                                            ("Rowna dlugosc jak...", RelationKind::EqualLength),
+                                        ] {
+                                            if ui.button(label).clicked() {
+                                                self.pending_relation = Some((kind, edge));
+                                                self.pending_coincident = None;
+                                                self.show_context_menu = false;
+                                            }
+                                        }
+                                        if self.relations.iter().any(|r| r.involves_edge(edge)) && ui.button("usun relacje krawedzi").clicked() {
+                                            self.begin_edit("usun relacje");
+                                            self.relations.retain(|r| !r.involves_edge(edge));
+                                            self.show_context_menu = false;
+                                        }
+                                    }
+                                    ui.separator();
+                                    let aa_label = if self.antialiasing { "wylacz antyaliasing" } else { "uzyj antyaliasingu" };
+                                    if ui.button(aa_label).clicked(){
+                                        // w trybie bibliotecznym egui i tak antyaliasuje
+                                        self.antialiasing = !self.antialiasing;
+                                        self.drawer.set_antialiasing(self.antialiasing);
+                                        self.show_context_menu = false;
+                                    }
+                                }
+                                if !self.polygons[i].contour(c).closed && self.polygons[i].contour(c).vertices.len() >= 3 && ui.button("zamknij lancuch").clicked() {
+                                    self.begin_edit("zamknij lancuch");
+                                    self.polygons[i].contour_mut(c).close();
+                                    self.solve(&[]);
+                                    self.show_context_menu = false;
+                                }
+                                if c == 0 && self.polygons[i].closed && ui.button("dodaj dziure").clicked() {
+                                    self.begin_edit("dodaj dziure");
+                                    self.add_hole(i);
+                                    self.show_context_menu = false;
+                                }
+                                if c > 0 && ui.button("usun dziure").clicked() {
+                                    self.begin_edit("usun dziure");
+                                    self.polygons[i].holes.remove(c - 1);
+                                    self.active_contour = 0;
+                                    self.show_context_menu = false;
+                                }
+                                if self.clicked_vertex.is_none() && self.clicked_edge.is_none() {
+                                    let at = self.view.to_world(self.context_pos);
+                                    if ui.button("prowadnica pozioma tutaj").clicked() {
+                                        self.guides.push(Guide::Horizontal(at.y));
+                                        self.show_context_menu = false;
+                                    }
+                                    if ui.button("prowadnica pionowa tutaj").clicked() {
+                                        self.guides.push(Guide::Vertical(at.x));
                                         self.show_context_menu = false;
                                     }
                                 }
@@ -422,73 +1507,87 @@ impl App for PolygonApp {
                                 ui.label("Dodaj ograniczenie:");
                                 if let Some(e_idx) = self.clicked_edge {
                                     if ui.button("Pozioma (H)").clicked(){
-                                        let constraint = ConstraintType::Horizontal;
-                                        if self.polygons[i].is_constraint_legal(e_idx, &constraint) {
-                                            self.polygons[i].constraints[e_idx] = Some(ConstraintType::Horizontal);
-                                            self.polygons[i].apply_constraints();
-                                        }else{
-                                            self.warning_text = "Nie można ustawić: sasiednia krawedz jest juz pozioma".to_string();
-                                            self.show_warning_popup = true;
-                                        }
+                                        self.begin_edit("Pozioma (H)");
+                                        self.try_set_constraint(i, e_idx, ConstraintType::Horizontal);
+                                        self.show_context_menu = false;
+                                    }
+                                    if ui.button("Pionowa (V)").clicked(){
+                                        self.begin_edit("Pionowa (V)");
+                                        self.try_set_constraint(i, e_idx, ConstraintType::Vertical);
                                         self.show_context_menu = false;
                                     }
                                     if ui.button("Skosna (D)").clicked(){
-                                        self.polygons[i].constraints[e_idx] = Some(ConstraintType::Diagonal45);
+                                        self.begin_edit("Skosna (D)");
+                                        self.try_set_constraint(i, e_idx, ConstraintType::Diagonal45);
                                         self.show_context_menu = false;
-                                        self.polygons[i].apply_constraints();
                                     }
                                     if ui.button("Dlugosc stala").clicked(){
-                                        let start = &self.polygons[i].vertices[e_idx];
-                                        let end = &self.polygons[i].vertices[(e_idx+1) % self.polygons[i].vertices.len()];
+                                        self.begin_edit("Dlugosc stala");
+                                        let start = &self.polygons[i].contour(c).vertices[e_idx];
+                                        let end = &self.polygons[i].contour(c).vertices[(e_idx+1) % self.polygons[i].contour(c).vertices.len()];
                                         let dx = end.x - start.x;
                                         let dy = end.y - start.y;
                                         let length = (dx * dx + dy * dy).sqrt();
-                                        self.polygons[i].constraints[e_idx] = Some(ConstraintType::FixedLength(length as f64)); // tutaj mega jest ten jezyk!
-
-                                        self.length_input = Some(length);//okienko
-                                        self.length_edge_idx = Some(e_idx); //index do okienka
+                                        if self.try_set_constraint(i, e_idx, ConstraintType::FixedLength(length as f64)) {
+                                            self.length_input = Some(length);//okienko
+                                            self.length_edge_idx = Some(e_idx); //index do okienka
+                                        }
 
                                         self.show_context_menu = false;
-                                        self.polygons[i].apply_constraints();
                                     }
                                     if ui.button("Łuk").clicked(){
+                                        self.begin_edit("Łuk");
+                                        // domyslnie polokrag, promien i kierunek mozna potem zmienic w okienku
+                                        let start = self.polygons[i].contour(c).vertices[e_idx];
+                                        let end = self.polygons[i].contour(c).vertices[(e_idx + 1) % self.polygons[i].contour(c).vertices.len()];
                                         let constraint = ConstraintType::Arc{
                                             g1_start: false,
                                             g1_end: false,
+                                            radius: start.distance(&end) / 2.0,
+                                            clockwise: true,
+                                            large_arc: false,
                                         };
-                                        self.polygons[i].constraints[e_idx] = Some(constraint);
-                                        self.polygons[i].apply_constraints();
+                                        if self.try_set_constraint(i, e_idx, constraint) {
+                                            self.open_arc_dialog(i, e_idx);
+                                        }
                                     }
-                                    if let Some(ConstraintType::Arc { ref mut g1_start, ref mut g1_end})=
-                                        self.polygons[i].constraints[e_idx]
+                                    if let Some(ConstraintType::Arc { g1_start, g1_end, radius, clockwise, large_arc })=
+                                        self.polygons[i].contour(c).constraints[e_idx].clone()
                                     {
                                         ui.separator();
                                         ui.label("Ustaw ciaglosc luku:");
 
-                                        if ui.selectable_label(*g1_start, "G1 start").clicked() {
-                                            *g1_start = true;
-                                            *g1_end = false;
+                                        // sasiednie krawedzie obracaja sie tak, zeby byly styczne do luku
+                                        if ui.selectable_label(g1_start, "G1 start").clicked() {
+                                            self.begin_edit("G1 start");
+                                            let constraint = ConstraintType::Arc { g1_start: !g1_start, g1_end, radius, clockwise, large_arc };
+                                            self.try_set_constraint(i, e_idx, constraint);
                                             self.show_context_menu = false;
                                         }
 
-                                        if ui.selectable_label(*g1_end, "G1 end").clicked() {
-                                            *g1_end = true;
-                                            *g1_start = false;
+                                        if ui.selectable_label(g1_end, "G1 end").clicked() {
+                                            self.begin_edit("G1 end");
+                                            let constraint = ConstraintType::Arc { g1_start, g1_end: !g1_end, radius, clockwise, large_arc };
+                                            self.try_set_constraint(i, e_idx, constraint);
+                                            self.show_context_menu = false;
+                                        }
+
+                                        if ui.button("Edytuj luk").clicked() {
+                                            self.open_arc_dialog(i, e_idx);
                                             self.show_context_menu = false;
                                         }
                                     }
 
                                     if ui.button("Przełącz Bezier").clicked() {
-                                        match &mut self.polygons[i].constraints[e_idx] {
+                                        self.begin_edit("Przełącz Bezier");
+                                        match &mut self.polygons[i].contour_mut(c).constraints[e_idx] {
                                             Some(ConstraintType::Bezier { .. }) => {
-                                                self.polygons[i].constraints[e_idx] = None;
+                                                self.polygons[i].contour_mut(c).constraints[e_idx] = None;
                                             }
                                             _ => {
-                                                let n = self.polygons[i].vertices.len();
-                                                let start = self.polygons[i].vertices[e_idx];
-                                                let end = self.polygons[i].vertices[(e_idx + 1) % self.polygons[i].vertices.len()];
-                                                self.polygons[i].vertices[e_idx].continuity = Continuity::G1;
-                                                self.polygons[i].vertices[(e_idx+1)%n].continuity = Continuity::C1;
+                                                let n = self.polygons[i].contour(c).vertices.len();
+                                                let start = self.polygons[i].contour(c).vertices[e_idx];
+                                                let end = self.polygons[i].contour(c).vertices[(e_idx + 1) % self.polygons[i].contour(c).vertices.len()];
 
                                                 let control1 = Point {
                                                     x: start.x + (end.x - start.x) / 3.0,
@@ -502,31 +1601,33 @@ impl App for PolygonApp {
                                                     role: Vertex,
                                                     continuity: Continuity::None,
                                                 };
-                                                self.polygons[i].constraints[e_idx] = Some(ConstraintType::Bezier {
+                                                let constraint = ConstraintType::Bezier {
                                                     control1,
                                                     control2,
                                                     g1_start:true,
                                                     g1_end:true,
                                                     c1_start: false,
                                                     c1_end: false,
-                                                });
+                                                };
+                                                if self.try_set_constraint(i, e_idx, constraint) {
+                                                    self.polygons[i].contour_mut(c).vertices[e_idx].continuity = Continuity::G1;
+                                                    self.polygons[i].contour_mut(c).vertices[(e_idx+1)%n].continuity = Continuity::C1;
+                                                }
                                             }
                                         }
-                                        self.polygons[i].apply_constraints();
+                                        self.solve(&[]);
                                     }
 
                                     if ui.button("Przełącz BezierSharp").clicked() {
-                                        match &mut self.polygons[i].constraints[e_idx] {
+                                        self.begin_edit("Przełącz BezierSharp");
+                                        match &mut self.polygons[i].contour_mut(c).constraints[e_idx] {
                                             Some(ConstraintType::Bezier { .. }) => {
-                                                self.polygons[i].constraints[e_idx] = None;
+                                                self.polygons[i].contour_mut(c).constraints[e_idx] = None;
                                             }
                                             _ => {
-                                                let n = self.polygons[i].vertices.len();
-                                                let start = self.polygons[i].vertices[e_idx];
-                                                let end = self.polygons[i].vertices[(e_idx + 1) % n];
-
-                                                self.polygons[i].vertices[e_idx].continuity = Continuity::G0;
-                                                self.polygons[i].vertices[(e_idx + 1) % n].continuity = Continuity::G0;
+                                                let n = self.polygons[i].contour(c).vertices.len();
+                                                let start = self.polygons[i].contour(c).vertices[e_idx];
+                                                let end = self.polygons[i].contour(c).vertices[(e_idx + 1) % n];
 
                                                 let dx = end.x - start.x;
                                                 let dy = end.y - start.y;
@@ -555,21 +1656,70 @@ impl App for PolygonApp {
                                                     continuity: Continuity::None,
                                                 };
 
-                                                self.polygons[i].constraints[e_idx] = Some(ConstraintType::Bezier {
+                                                let constraint = ConstraintType::Bezier {
                                                     control1,
                                                     control2,
                                                     g1_start: true,
                                                     g1_end: true,
                                                     c1_start: false,
                                                     c1_end: false,
-                                                });
+                                                };
+                                                if self.try_set_constraint(i, e_idx, constraint) {
+                                                    self.polygons[i].contour_mut(c).vertices[e_idx].continuity = Continuity::G0;
+                                                    self.polygons[i].contour_mut(c).vertices[(e_idx + 1) % n].continuity = Continuity::G0;
+                                                }
                                             }
                                         }
-                                        self.polygons[i].apply_constraints();
+                                        self.solve(&[]);
                                     }
 
 
 
+                                    if ui.button("Przełącz Bezier 2. stopnia").clicked() {
+                                        self.begin_edit("Przełącz Bezier 2. stopnia");
+                                        if self.polygons[i].contour(c).constraints[e_idx].as_ref().is_some_and(|c| c.is_bezier()) {
+                                            self.polygons[i].contour_mut(c).constraints[e_idx] = None;
+                                        } else {
+                                            let mut trial = self.polygons[i].contour(c).clone();
+                                            trial.constraints[e_idx] = None;
+                                            trial.elevate_bezier_degree(e_idx);
+                                            if let Some(constraint) = trial.constraints[e_idx].clone() {
+                                                self.try_set_constraint(i, e_idx, constraint);
+                                            }
+                                        }
+                                        self.solve(&[]);
+                                    }
+
+                                    if self.polygons[i].contour(c).constraints[e_idx].as_ref().is_some_and(|c| c.is_bezier()) {
+                                        let degree = self.polygons[i].contour(c).constraints[e_idx].as_ref().map_or(0, |c| c.bezier_degree());
+                                        ui.separator();
+                                        ui.label(format!("Stopien krzywej: {}", degree));
+                                        if ui.button("Podnies stopien").clicked() {
+                                            self.begin_edit("Podnies stopien");
+                                            let mut trial = self.polygons[i].contour(c).clone();
+                                            if trial.elevate_bezier_degree(e_idx)
+                                                && let Some(constraint) = trial.constraints[e_idx].clone()
+                                            {
+                                                self.try_set_constraint(i, e_idx, constraint);
+                                            }
+                                        }
+                                        if ui.button("Obniz stopien").clicked() {
+                                            // kwadratowa po obnizeniu staje sie zwykla krawedzia
+                                            self.begin_edit("Obniz stopien");
+                                            let mut trial = self.polygons[i].contour(c).clone();
+                                            if trial.reduce_bezier_degree(e_idx) {
+                                                match trial.constraints[e_idx].clone() {
+                                                    Some(constraint) => {
+                                                        self.try_set_constraint(i, e_idx, constraint);
+                                                    }
+                                                    None => {
+                                                        self.polygons[i].contour_mut(c).constraints[e_idx] = None;
+                                                        self.solve(&[]);
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
                                 }
                             }
                         });
@@ -590,16 +1740,95 @@ impl App for PolygonApp {
                         }
 
                         if ui.button("Zastosuj").clicked(){
-                            self.polygons[i].constraints[edge_idx] = Some(ConstraintType::FixedLength((self.length_input.unwrap()) as f64));
+                            self.begin_edit("dlugosc krawedzi");
+                            let constraint = ConstraintType::FixedLength((self.length_input.unwrap()) as f64);
+                            if self.try_set_constraint(i, edge_idx, constraint) {
+                                self.length_input = None;
+                                self.length_edge_idx = None;
+                            }
+                        }
+
+                        if ui.button("Anuluj").clicked(){
                             self.length_input = None;
                             self.length_edge_idx = None;
+                        }
+                    });
+            }
+
+            if let Some(edge_idx) = self.arc_edge_idx {
+                egui::Window::new("Ustaw luk")
+                    .collapsible(false)
+                    .resizable(false)
+                    .show(ctx, |ui| {
+                        let n = self.polygons[i].contour(c).vertices.len();
+                        let half_chord = self.polygons[i].contour(c).vertices[edge_idx % n].distance(&self.polygons[i].contour(c).vertices[(edge_idx + 1) % n]) / 2.0;
+                        ui.label(format!("Najmniejszy promien (polokrag): {:.2}", half_chord));
+
+                        ui.horizontal(|ui| {
+                            ui.label("Promien");
+                            ui.add(egui::DragValue::new(&mut self.arc_radius_input).speed(1.0).range(half_chord..=f32::MAX));
+                        });
+                        ui.checkbox(&mut self.arc_clockwise_input, "Zgodnie z ruchem wskazowek");
+                        ui.checkbox(&mut self.arc_large_input, "Dluzszy luk");
 
-                            self.polygons[i].apply_constraints();
+                        if ui.button("Zastosuj").clicked(){
+                            self.begin_edit("luk");
+                            let (g1_start, g1_end) = match self.polygons[i].contour(c).get_constraint(edge_idx) {
+                                Some(ConstraintType::Arc { g1_start, g1_end, .. }) => (g1_start, g1_end),
+                                _ => (false, false),
+                            };
+                            let constraint = ConstraintType::Arc {
+                                g1_start,
+                                g1_end,
+                                radius: self.arc_radius_input,
+                                clockwise: self.arc_clockwise_input,
+                                large_arc: self.arc_large_input,
+                            };
+                            if self.try_set_constraint(i, edge_idx, constraint) {
+                                self.arc_edge_idx = None;
+                            }
                         }
 
                         if ui.button("Anuluj").clicked(){
-                            self.length_input = None;
-                            self.length_edge_idx = None;
+                            self.arc_edge_idx = None;
+                        }
+                    });
+            }
+
+            if let Some(rel_idx) = self.angle_relation_idx {
+                egui::Window::new("Ustaw kat")
+                    .collapsible(false)
+                    .resizable(false)
+                    .show(ctx, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label("Kat wewnetrzny [°]");
+                            ui.add(egui::DragValue::new(&mut self.angle_input).speed(1.0).range(1.0..=359.0));
+                        });
+
+                        if ui.button("Zastosuj").clicked() {
+                            if let Some(Relation::FixedAngle { at, .. }) = self.relations.get(rel_idx).copied() {
+                                self.begin_edit("kat");
+                                let candidate = Relation::FixedAngle { at, degrees: self.angle_input };
+                                let mut others = self.relations.clone();
+                                others.remove(rel_idx);
+                                match relation::check_relation(&self.polygons, &others, &candidate) {
+                                    Ok(()) => {
+                                        self.relations[rel_idx] = candidate;
+                                        self.solve(&[]);
+                                        self.angle_relation_idx = None;
+                                    }
+                                    Err(reason) => {
+                                        self.warning_text = format!("Nie można ustawić kąta:\n{}", reason);
+                                        self.show_warning_popup = true;
+                                    }
+                                }
+                            } else {
+                                self.angle_relation_idx = None;
+                            }
+                        }
+
+                        if ui.button("Anuluj").clicked() {
+                            self.angle_relation_idx = None;
                         }
                     });
             }
@@ -639,18 +1868,28 @@ impl App for PolygonApp {
                             .auto_shrink([false; 2])
                             .max_height(300.0) // możesz zmienić wysokość np. na 400
                             .show(ui, |ui| {
-                                ui.label("Program umożliwia tworzenie i edycję wielokątów z ograniczeniami geometrycznymi (H, V, 45°, długość). Kliknij wierzchołek, aby go przesunąć, lub w krawędź, aby dodać nowy punkt. Kliknięcie prawym przyciskiem myszy otwiera menu z opcjami (dodaj, usuń, nadaj ograniczenie itp.). Dwie sąsiednie krawędzie nie mogą być jednocześnie poziome lub pionowe. Krawędzie mogą być także krzywymi Beziera trzeciego stopnia z punktami kontrolnymi. W wierzchołkach można ustawiać klasę ciągłości (G0, G1, C1) między segmentami. Cały wielokąt można przesuwać przeciągając tło. Po każdej zmianie program automatycznie wymusza zgodność z ograniczeniami.");
+                                ui.label("Program umożliwia tworzenie i edycję wielokątów z ograniczeniami geometrycznymi (H, V, 45°, długość). Kliknij wierzchołek, aby go przesunąć, lub w krawędź, aby dodać nowy punkt. Kliknięcie prawym przyciskiem myszy otwiera menu z opcjami (dodaj, usuń, nadaj ograniczenie itp.). Dwie sąsiednie krawędzie nie mogą być jednocześnie poziome lub pionowe. Krawędzie mogą być także krzywymi Beziera (kwadratowymi, trzeciego lub wyższego stopnia) z punktami kontrolnymi; stopień krzywej można podnosić i obniżać z menu kontekstowego. W wierzchołkach można ustawiać klasę ciągłości (G0, G1, C1, G2) między segmentami - liczoną względem faktycznego sąsiedniego segmentu (krzywa, odcinek albo łuk). Krawędzie - także z różnych wielokątów - można powiązać relacją równoległości, prostopadłości albo równej długości, a w wierzchołku ustalić kąt wewnętrzny. Wierzchołek można przypiąć, ograniczyć do poziomej lub pionowej prowadnicy albo skleić z wierzchołkiem innego wielokąta; przypiętych wierzchołków nie rusza ani przeciąganie, ani solver. Wielokąt może mieć dziury (menu: dodaj dziurę) - każda dziura to osobny kontur z własnymi ograniczeniami krawędzi, edytowany tak samo jak kontur zewnętrzny; wypełnienie omija dziury. Cały wielokąt można przesuwać przeciągając tło. Po każdej zmianie program automatycznie wymusza zgodność z ograniczeniami.");
 
                                 ui.add_space(10.0);
                                 ui.label("Klawiszologia:");
                                 ui.label(" • Lewy przycisk myszy – zaznacz lub przeciągnij wierzchołek");
                                 ui.label(" • Prawy przycisk myszy na wiezcholku – otwiera menu kontekstowe (dodaj, usuń, ograniczenia)");
-                                ui.label(" • Przeciągnięcie tła – przesuwa cały wielokąt");
+                                ui.label(" • Rysuj – klikaj kolejne wierzchołki; Enter lub klik w pierwszy wierzchołek zamyka wielokąt, Esc anuluje. Z przyciąganiem krawędzie bliskie poziomu, pionu i 45° dostają ograniczenie H/V/D");
+                                ui.label(" • Shift+klik na wierzcholku – dodaje go do zaznaczenia (albo wyjmuje); przeciagniecie od pustego miejsca (albo z Shiftem) zaznacza prostokatem, takze w wielu wielokatach");
+                                ui.label(" • Przeciagniecie zaznaczonego wierzcholka przesuwa cala grupe; Del usuwa zaznaczone, Esc czysci zaznaczenie, a panel boczny nadaje ograniczenie wszystkim zaznaczonym krawedziom");
+                                ui.label(" • Przeciągnięcie tła – przesuwa cały wielokąt (otwartą łamaną łapiemy za krawędź)");
+                                ui.label(" • Siatka i przyciaganie (panel boczny) – przeciagany wierzcholek albo punkt kontrolny skacze do wierzcholkow, srodkow krawedzi, prowadnic, katow co 15° od poprzedniego wierzcholka i do siatki; Alt chwilowo wylacza przyciaganie. Prowadnice stawia prawy klik w puste miejsce plotna");
+                                ui.label(" • Kolko myszy – powieksza wokol kursora, srodkowy przycisk – przesuwa widok, \"Dopasuj\" pokazuje wszystkie wielokaty; wspolrzedne i dlugosci sa w jednostkach swiata, niezaleznie od powiekszenia");
+                                ui.label(" • \"+ lamana\" dodaje otwartą łamaną; w menu można ją zamknąć, a wielokąt rozciąć w wybranej krawędzi");
                                 ui.label(" • Prawy przycisk myszy na krawedzi otwiera menu kontekstowe ( ograniczenie poziome/pionowe");
+                                ui.label(" • Menu wierzcholka – przypnij (kwadrat), prowadnica pozioma/pionowa, wspolny wierzcholek z innym wielokatem (kliknij lewym drugi wierzcholek)");
+                                ui.label(" • Ramka wokol aktywnego wielokata – narozniki skaluja jednakowo, srodki bokow w jednej osi, kolko na gorze obraca (z Shiftem co 15°); odbicia sa w panelu bocznym. Krawedzie H/V/45° po obrocie dostaja nowy kierunek albo traca ograniczenie, stale dlugosci i promienie lukow skaluja sie razem z ksztaltem");
+                                ui.label(" • Operacje logiczne (panel boczny) – suma, iloczyn, roznica albo XOR aktywnego wielokata z wybranym; wynik dochodzi jako nowe wielokaty, krzywe sa wczesniej splaszczane");
+                                ui.label(" • Relacja krawedzi (rownoleglosc, prostopadlosc, rowna dlugosc) – wybierz ja z menu krawedzi i kliknij lewym druga krawedz, takze w innym wielokacie; Esc anuluje");
 
                                 ui.add_space(10.0);
                                 ui.label("Algorytm relacji:");
-                                ui.label("Program po każdej zmianie wymusza zgodność z zadanymi ograniczeniami. Relacje geometryczne są stosowane w kolejności ich dodania. Ograniczenia długości są traktowane jako nadrzędne wobec kierunkowych (H, V, 45°). Przy przesuwaniu wierzchołków program automatycznie przelicza położenia innych punktów, aby zachować zadane relacje. W przypadku krzywych Beziera, wierzchołki kontrolne są przesuwane zgodnie z wybraną klasą ciągłości (C0, C1, G1).");
+                                ui.label("Program po każdej zmianie wymusza zgodność z zadanymi ograniczeniami. Relacje geometryczne są stosowane w kolejności ich dodania. Ograniczenia długości są traktowane jako nadrzędne wobec kierunkowych (H, V, 45°). Przy przesuwaniu wierzchołków program automatycznie przelicza położenia innych punktów, aby zachować zadane relacje. W przypadku krzywych Beziera, wierzchołki kontrolne są przesuwane zgodnie z wybraną klasą ciągłości (C0, C1, G1, G2); przeciągany uchwyt zostaje na miejscu, a dopasowuje się druga strona wierzchołka.");
                             });
 
                         ui.add_space(10.0);
@@ -669,16 +1908,205 @@ impl App for PolygonApp {
         }
     );
 
+        // przeciaganie trwa dopoki przycisk myszy jest wcisniety
+        if !ctx.input(|inp| inp.pointer.any_down()) {
+            self.history.commit(&self.polygons, &self.relations, self.active_polygon);
+        }
+
         // Panel boczny z informacją o wybranym wierzchołku
         egui::SidePanel::right("sidebar").show(ctx, |ui| {
+            if self.pending_relation.is_some() {
+                ui.colored_label(egui::Color32::from_rgb(255, 170, 80), "Wskaz druga krawedz relacji (Esc - anuluj)");
+                ui.separator();
+            }
+            if self.drawing.is_some() {
+                ui.colored_label(egui::Color32::LIGHT_BLUE, "Rysowanie: klikaj kolejne wierzcholki");
+                ui.label("Enter albo klik w pierwszy wierzcholek zamyka wielokat, Esc anuluje");
+                ui.horizontal(|ui| {
+                    if ui.button("Zakoncz jako lamana").clicked() {
+                        self.finish_drawing(false);
+                    }
+                    if ui.button("Anuluj").clicked() {
+                        self.drawing = None;
+                    }
+                });
+                ui.separator();
+            }
+            if self.pending_coincident.is_some() {
+                ui.colored_label(egui::Color32::from_rgb(255, 170, 80), "Wskaz wierzcholek innego wielokata (Esc - anuluj)");
+                ui.separator();
+            }
+            if !self.selection.group.is_empty() {
+                ui.heading(format!("Zaznaczone wierzchołki: {}", self.selection.group.len()));
+                let edges = self.selection.selected_edges(&self.polygons).len();
+                ui.label(format!("Krawędzie między nimi: {}", edges));
+                ui.horizontal_wrapped(|ui| {
+                    for (label, constraint) in [
+                        ("H", Some(ConstraintType::Horizontal)),
+                        ("V", Some(ConstraintType::Vertical)),
+                        ("45°", Some(ConstraintType::Diagonal45)),
+                        ("bez ograniczen", None),
+                    ] {
+                        if ui.add_enabled(edges > 0, egui::Button::new(label)).clicked() {
+                            self.begin_edit("ograniczenie grupy");
+                            self.constrain_group(constraint);
+                        }
+                    }
+                });
+                ui.horizontal(|ui| {
+                    if ui.button("Usun (Del)").clicked() {
+                        self.begin_edit("usun zaznaczone");
+                        self.delete_group();
+                    }
+                    if ui.button("Odznacz (Esc)").clicked() {
+                        self.selection.group.clear();
+                    }
+                });
+                ui.separator();
+            }
             ui.heading("Wybrany wierzchołek");
             if let Some(idx) = self.selection.selected_vertex {
-                let v = &self.polygons[i].vertices[idx];
+                let v = &self.polygons[i].contour(self.active_contour).vertices[idx];
                 ui.label(format!("Index: {}", idx));
+                if self.active_contour > 0 {
+                    ui.label(format!("Kontur: dziura {}", self.active_contour));
+                }
                 ui.label(format!("Pozycja: ({:.1}, {:.1})", v.x, v.y));
             } else {
                 ui.label("Brak wybranego wierzchołka");
             }
+
+            ui.separator();
+            ui.heading("Wypełnienie");
+            let active = self.active_polygon as usize;
+            if let Some(polygon) = self.polygons.get(active) {
+                let mut filled = polygon.fill.is_some();
+                let mut color = polygon
+                    .fill
+                    .map(|[r, g, b, a]| egui::Color32::from_rgba_unmultiplied(r, g, b, a))
+                    .unwrap_or(egui::Color32::from_rgba_unmultiplied(80, 140, 220, 160));
+                let mut rule = polygon.fill_rule;
+
+                let mut changed = ui.checkbox(&mut filled, "Wypełnij wielokąt").changed();
+                ui.add_enabled_ui(filled, |ui| {
+                    changed |= ui.color_edit_button_srgba(&mut color).changed();
+                    changed |= ui.radio_value(&mut rule, FillRule::EvenOdd, "Even-odd").changed();
+                    changed |= ui.radio_value(&mut rule, FillRule::NonZero, "Non-zero").changed();
+                });
+                if changed {
+                    self.begin_edit("wypelnienie");
+                    let polygon = &mut self.polygons[active];
+                    polygon.fill = filled.then(|| color.to_srgba_unmultiplied());
+                    polygon.fill_rule = rule;
+                }
+            }
+
+            ui.separator();
+            ui.heading("Siatka i przyciąganie");
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut self.snapping.show_grid, "Siatka");
+                ui.label("oczko:");
+                ui.add(egui::DragValue::new(&mut self.snapping.grid_spacing).range(1.0..=500.0).speed(1.0));
+            });
+            ui.checkbox(&mut self.snapping.enabled, "Przyciąganie").on_hover_text("Alt - chwilowo wylacza");
+            ui.add_enabled_ui(self.snapping.enabled, |ui| {
+                ui.horizontal_wrapped(|ui| {
+                    ui.checkbox(&mut self.snapping.to_grid, "siatka");
+                    ui.checkbox(&mut self.snapping.to_guides, "prowadnice");
+                    ui.checkbox(&mut self.snapping.to_vertices, "wierzcholki");
+                    ui.checkbox(&mut self.snapping.to_midpoints, "srodki krawedzi");
+                    ui.checkbox(&mut self.snapping.to_angles, "katy co 15°");
+                });
+            });
+            let mut removed_guide = None;
+            for (k, guide) in self.guides.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    let (label, value) = match guide {
+                        Guide::Horizontal(y) => ("pozioma y =", y),
+                        Guide::Vertical(x) => ("pionowa x =", x),
+                    };
+                    ui.label(label);
+                    ui.add(egui::DragValue::new(value).speed(1.0));
+                    if ui.small_button("x").clicked() {
+                        removed_guide = Some(k);
+                    }
+                });
+            }
+            if let Some(k) = removed_guide {
+                self.guides.remove(k);
+            }
+            if self.guides.is_empty() {
+                ui.label("Prowadnice: prawy klik w puste miejsce plotna");
+            }
+
+            ui.separator();
+            ui.heading("Transformacja");
+            ui.checkbox(&mut self.show_gizmo, "Uchwyty transformacji")
+                .on_hover_text("Narozniki skaluja jednakowo, boki w jednej osi, kolko obraca (Shift - co 15°)");
+            ui.horizontal(|ui| {
+                let enabled = self.polygons.get(self.active_polygon as usize).is_some();
+                if ui.add_enabled(enabled, egui::Button::new("Odbij poziomo")).clicked() {
+                    self.mirror_active(true);
+                }
+                if ui.add_enabled(enabled, egui::Button::new("Odbij pionowo")).clicked() {
+                    self.mirror_active(false);
+                }
+            });
+
+            ui.separator();
+            ui.heading("Operacje logiczne");
+            if self.polygons.len() < 2 || self.active_polygon < 0 {
+                ui.label("Potrzebne sa co najmniej dwa wielokaty");
+            } else {
+                let active = self.active_polygon as usize;
+                if self.boolean_other >= self.polygons.len() || self.boolean_other == active {
+                    self.boolean_other = if active == 0 { 1 } else { 0 };
+                }
+                ui.horizontal(|ui| {
+                    ui.label(format!("Wielokat {} oraz", active));
+                    egui::ComboBox::from_id_salt("boolean_other")
+                        .selected_text(format!("wielokat {}", self.boolean_other))
+                        .show_ui(ui, |ui| {
+                            for idx in (0..self.polygons.len()).filter(|&idx| idx != active) {
+                                ui.selectable_value(&mut self.boolean_other, idx, format!("wielokat {}", idx));
+                            }
+                        });
+                });
+                ui.horizontal(|ui| {
+                    for (label, op) in [
+                        ("Suma", BooleanOp::Union),
+                        ("Iloczyn", BooleanOp::Intersection),
+                        ("Roznica", BooleanOp::Difference),
+                        ("XOR", BooleanOp::Xor),
+                    ] {
+                        if ui.button(label).clicked() {
+                            self.apply_boolean(op);
+                        }
+                    }
+                });
+            }
+
+            ui.separator();
+            let mut depth = self.history.max_depth;
+            ui.horizontal(|ui| {
+                ui.label("Historia (kroki):");
+                if ui.add(egui::DragValue::new(&mut depth).range(1..=1000)).changed() {
+                    self.history.set_max_depth(depth);
+                }
+            });
+
+            ui.separator();
+            match self.solver_status {
+                SolveResult::Converged { iterations } => {
+                    ui.label(format!("Ograniczenia spełnione ({} iteracji)", iterations));
+                }
+                SolveResult::NotConverged { residual } => {
+                    ui.colored_label(
+                        egui::Color32::from_rgb(255, 120, 120),
+                        format!("Solver nie zbiegł się (odchyłka {:.2})", residual),
+                    );
+                }
+            }
         });
 
 
@@ -687,5 +2115,53 @@ impl App for PolygonApp {
 
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(x: f32, y: f32, size: f32) -> Vec<Point> {
+        vec![Point::new(x, y), Point::new(x + size, y), Point::new(x + size, y + size), Point::new(x, y + size)]
+    }
+
+    fn select(app: &mut PolygonApp, vertices: &[(usize, usize, usize)]) {
+        app.selection.group = vertices
+            .iter()
+            .map(|&(polygon, contour, vertex)| SelectedVertex { polygon, contour, vertex })
+            .collect();
+    }
 
+    #[test]
+    fn delete_group_removes_vertices_across_polygons() {
+        let mut first = Polygon::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(50.0, 0.0),
+            Point::new(100.0, 0.0),
+            Point::new(100.0, 100.0),
+            Point::new(0.0, 100.0),
+        ]);
+        first.add_hole(square(20.0, 20.0, 20.0));
+        first.holes[0].vertices.push(Point::new(30.0, 45.0));
+        first.holes[0].ensure_constraints_len();
+        let mut app = PolygonApp { polygons: vec![first, Polygon::new(square(200.0, 0.0, 50.0))], ..PolygonApp::default() };
+        // wierzcholki w dowolnej kolejnosci - usuwanie idzie od konca
+        select(&mut app, &[(0, 0, 1), (1, 0, 0), (0, 1, 4), (0, 0, 3)]);
+        app.delete_group();
+
+        let coords = |polygon: &Polygon| polygon.vertices.iter().map(|v| (v.x, v.y)).collect::<Vec<_>>();
+        assert_eq!(coords(&app.polygons[0]), vec![(0.0, 0.0), (100.0, 0.0), (0.0, 100.0)]);
+        assert_eq!(app.polygons[0].holes[0].vertices.len(), 4);
+        assert_eq!(coords(&app.polygons[1]), vec![(250.0, 0.0), (250.0, 50.0), (200.0, 50.0)]);
+        assert!(app.selection.group.is_empty());
+        assert!(!app.show_warning_popup);
+    }
 
+    #[test]
+    fn delete_group_keeps_minimal_contour() {
+        let triangle = Polygon::new(vec![Point::new(0.0, 0.0), Point::new(10.0, 0.0), Point::new(0.0, 10.0)]);
+        let mut app = PolygonApp { polygons: vec![triangle], ..PolygonApp::default() };
+        select(&mut app, &[(0, 0, 0), (0, 0, 1), (0, 0, 2)]);
+        app.delete_group();
+        assert_eq!(app.polygons[0].vertices.len(), 3);
+        assert!(app.show_warning_popup);
+    }
+}
@@ -0,0 +1,184 @@
+use std::f32::consts::FRAC_PI_4;
+use crate::geometry::point::Point;
+use crate::geometry::polygon::{ConstraintType, Polygon};
+
+/// Kliknięcie bliżej niż tyle pikseli ekranu od pierwszego wierzchołka zamyka wielokąt.
+pub const CLOSE_RADIUS: f32 = 10.0;
+/// Krawędź odchylona od poziomu, pionu albo 45° o mniej niż tyle stopni jest do nich przyciągana.
+pub const SNAP_ANGLE_DEG: f32 = 6.0;
+
+/// Narzędzie rysowania nowego wielokąta: kolejne kliknięcia dodają wierzchołki,
+/// a od ostatniego z nich do kursora rysujemy "gumkę" z podglądem następnej krawędzi.
+pub struct DrawingTool {
+    pub points: Vec<Point>,
+    /// ograniczenie wykryte przy przyciąganiu, osobno dla każdej narysowanej krawędzi
+    constraints: Vec<Option<ConstraintType>>,
+    pub snapping: bool,
+}
+
+impl DrawingTool {
+    pub fn new(snapping: bool) -> Self {
+        Self { points: Vec::new(), constraints: Vec::new(), snapping }
+    }
+
+    /// Czy kursor jest nad pierwszym wierzchołkiem (i da się już zamknąć wielokąt);
+    /// `pixel` to długość piksela ekranu w jednostkach świata.
+    pub fn is_near_start(&self, cursor: Point, pixel: f32) -> bool {
+        self.points.len() >= 3 && self.points[0].distance(&cursor) < CLOSE_RADIUS * pixel
+    }
+
+    /// Gdzie wyląduje następny wierzchołek: kursor, albo jego rzut na przyciągnięty kierunek.
+    pub fn preview(&self, cursor: Point) -> (Point, Option<ConstraintType>) {
+        match self.points.last() {
+            Some(last) if self.snapping => snap_direction(*last, cursor),
+            _ => (cursor, None),
+        }
+    }
+
+    pub fn add_point(&mut self, cursor: Point) {
+        let (point, constraint) = self.preview(cursor);
+        if self.points.last().is_some_and(|last| last.distance(&point) < 1.0) {
+            return; // podwojne klikniecie w to samo miejsce
+        }
+        if !self.points.is_empty() {
+            self.constraints.push(constraint);
+        }
+        self.points.push(point);
+    }
+
+    /// Gotowy wielokąt (zamknięty) albo otwarta łamana; `None`, gdy punktów jest za mało.
+    pub fn finish(&self, closed: bool) -> Option<Polygon> {
+        let needed = if closed { 3 } else { 2 };
+        if self.points.len() < needed {
+            return None;
+        }
+        let mut polygon = if closed {
+            Polygon::new(self.points.clone())
+        } else {
+            Polygon::new_open(self.points.clone())
+        };
+
+        let mut constraints = self.constraints.clone();
+        if closed {
+            // krawedz zamykajaca nie byla rysowana gumka - sprawdzamy tylko jej kierunek
+            let closing = if self.snapping {
+                snap_direction(self.points[self.points.len() - 1], self.points[0]).1
+            } else {
+                None
+            };
+            constraints.push(closing);
+        }
+
+        // dwie sasiednie poziome (pionowe) krawedzie to konflikt - zostawiamy tylko pierwsza
+        let edges = constraints.len();
+        for i in 0..edges {
+            let prev = if i > 0 { Some(i - 1) } else if closed { Some(edges - 1) } else { None };
+            let same_axis = prev.is_some_and(|p| {
+                p != i
+                    && matches!(
+                        (&constraints[p], &constraints[i]),
+                        (Some(ConstraintType::Horizontal), Some(ConstraintType::Horizontal))
+                            | (Some(ConstraintType::Vertical), Some(ConstraintType::Vertical))
+                    )
+            });
+            if same_axis {
+                constraints[i] = None;
+            }
+        }
+        polygon.constraints = constraints;
+        polygon.ensure_constraints_len();
+        Some(polygon)
+    }
+}
+
+/// Rzutuje `to` na najbliższy z kierunków H/V/45° wychodzących z `from`, jeśli odchylenie
+/// jest mniejsze niż `SNAP_ANGLE_DEG`; zwraca też odpowiadające ograniczenie krawędzi.
+pub fn snap_direction(from: Point, to: Point) -> (Point, Option<ConstraintType>) {
+    let d = to - from;
+    if d.length() < CLOSE_RADIUS {
+        return (to, None);
+    }
+    let angle = d.y.atan2(d.x);
+    let k = (angle / FRAC_PI_4).round();
+    if (angle - k * FRAC_PI_4).abs() > SNAP_ANGLE_DEG.to_radians() {
+        return (to, None);
+    }
+
+    let dir = Point::new((k * FRAC_PI_4).cos(), (k * FRAC_PI_4).sin());
+    let snapped = from + dir * d.dot(dir);
+    let constraint = match (k as i32).rem_euclid(4) {
+        0 => ConstraintType::Horizontal,
+        2 => ConstraintType::Vertical,
+        _ => ConstraintType::Diagonal45,
+    };
+    (Point { x: snapped.x, y: snapped.y, ..to }, Some(constraint))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snaps_to_horizontal_vertical_and_diagonal() {
+        let from = Point::new(0.0, 0.0);
+        let (p, c) = snap_direction(from, Point::new(100.0, 3.0));
+        assert!(matches!(c, Some(ConstraintType::Horizontal)));
+        assert!(p.y.abs() < 1e-4 && (p.x - 100.0).abs() < 0.1);
+
+        let (p, c) = snap_direction(from, Point::new(-4.0, -80.0));
+        assert!(matches!(c, Some(ConstraintType::Vertical)));
+        assert!(p.x.abs() < 1e-4);
+
+        let (p, c) = snap_direction(from, Point::new(50.0, -53.0));
+        assert!(matches!(c, Some(ConstraintType::Diagonal45)));
+        assert!((p.x + p.y).abs() < 1e-3);
+
+        // za daleko od kazdego z kierunkow albo za blisko poczatku - bez przyciagania
+        let (p, c) = snap_direction(from, Point::new(100.0, 30.0));
+        assert!(c.is_none() && p == Point::new(100.0, 30.0));
+        assert!(snap_direction(from, Point::new(5.0, 0.5)).1.is_none());
+    }
+
+    #[test]
+    fn finish_drops_second_of_adjacent_same_axis_constraints() {
+        let mut tool = DrawingTool::new(true);
+        for (x, y) in [(0.0, 0.0), (50.0, 1.0), (100.0, -1.0), (100.0, 60.0)] {
+            tool.add_point(Point::new(x, y));
+        }
+        let polygon = tool.finish(false).unwrap();
+        assert!(!polygon.closed);
+        assert_eq!(polygon.constraints.len(), 3);
+        // obie pierwsze krawedzie przyciagnely sie do poziomu, ale ograniczenie dostaje tylko pierwsza
+        assert!(matches!(polygon.constraints[0], Some(ConstraintType::Horizontal)));
+        assert!(polygon.constraints[1].is_none());
+        assert!(matches!(polygon.constraints[2], Some(ConstraintType::Vertical)));
+    }
+
+    #[test]
+    fn finish_checks_closing_edge() {
+        let mut tool = DrawingTool::new(true);
+        for (x, y) in [(0.0, 0.0), (100.0, 0.0), (100.0, 60.0), (2.0, 60.0)] {
+            tool.add_point(Point::new(x, y));
+        }
+        let polygon = tool.finish(true).unwrap();
+        assert_eq!(polygon.constraints.len(), 4);
+        // krawedz zamykajaca (0, 60) -> (0, 0) jest pionowa, a jej sasiadka pozioma
+        assert!(matches!(polygon.constraints[2], Some(ConstraintType::Horizontal)));
+        assert!(matches!(polygon.constraints[3], Some(ConstraintType::Vertical)));
+    }
+
+    #[test]
+    fn finish_needs_enough_points() {
+        let mut tool = DrawingTool::new(false);
+        assert!(tool.finish(false).is_none());
+        tool.add_point(Point::new(0.0, 0.0));
+        // drugie klikniecie w to samo miejsce sie nie liczy
+        tool.add_point(Point::new(0.5, 0.0));
+        assert!(tool.finish(false).is_none());
+        tool.add_point(Point::new(30.0, 40.0));
+        assert!(tool.finish(true).is_none());
+        assert!(tool.finish(false).is_some());
+        tool.add_point(Point::new(60.0, 0.0));
+        assert!(tool.finish(true).is_some());
+    }
+}
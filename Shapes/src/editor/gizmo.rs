@@ -0,0 +1,112 @@
+use std::f32::consts::PI;
+use crate::geometry::point::Point;
+use crate::geometry::polygon::Polygon;
+use crate::geometry::transform::Affine;
+
+/// Odstęp ramki uchwytów od obrysu wielokąta (w pikselach ekranu), żeby uchwyty nie zasłaniały wierzchołków.
+pub const GIZMO_MARGIN: f32 = 12.0;
+/// Jak wysoko nad ramką wisi uchwyt obrotu (też w pikselach).
+pub const ROTATE_OFFSET: f32 = 28.0;
+/// Z Shiftem obrót przeskakuje co tyle stopni - H/V przeżywają wtedy obrót o 90°.
+pub const ROTATE_SNAP_DEG: f32 = 15.0;
+// nie pozwalamy zgniesc ksztaltu do zera
+const MIN_SCALE: f32 = 0.05;
+
+/// Uchwyt ramki transformacji.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GizmoHandle {
+    Rotate,
+    /// skalowanie; (fx, fy) to strona ramki: -1 lewo/góra, 1 prawo/dół, 0 środek.
+    /// Narożniki skalują jednakowo, środki boków tylko w jednej osi.
+    Scale { fx: i8, fy: i8 },
+}
+
+/// Trwające przeciąganie uchwytu: wszystko liczymy od stanu z początku, a nie przyrostowo,
+/// więc ograniczenia zdjęte w trakcie obrotu wracają, gdy kształt wróci na miejsce.
+pub struct GizmoDrag {
+    pub handle: GizmoHandle,
+    pub original: Polygon,
+    pub start: Point,
+    pub min: Point,
+    pub max: Point,
+    /// ograniczenia, których nie dało się zachować przy ostatnim przeliczeniu
+    pub notes: Vec<String>,
+}
+
+impl GizmoDrag {
+    /// `pixel` - ile jednostek świata ma jeden piksel ekranu przy obecnym powiększeniu.
+    pub fn new(handle: GizmoHandle, original: Polygon, start: Point, pixel: f32) -> Option<Self> {
+        let (min, max) = frame(&original, pixel)?;
+        Some(Self { handle, original, start, min, max, notes: Vec::new() })
+    }
+
+    /// Przekształcenie od początku przeciągania do kursora `current`.
+    pub fn transform(&self, current: Point, snap: bool) -> Affine {
+        let center = (self.min + self.max) * 0.5;
+        match self.handle {
+            GizmoHandle::Rotate => {
+                let angle = |p: Point| (p.y - center.y).atan2(p.x - center.x);
+                let mut delta = angle(current) - angle(self.start);
+                if snap {
+                    let step = ROTATE_SNAP_DEG * PI / 180.0;
+                    delta = (delta / step).round() * step;
+                }
+                Affine::rotation(center, delta)
+            }
+            GizmoHandle::Scale { fx, fy } => {
+                // skalujemy wzgledem przeciwleglego boku albo naroznika
+                let anchor = Point::new(
+                    if fx > 0 { self.min.x } else if fx < 0 { self.max.x } else { center.x },
+                    if fy > 0 { self.min.y } else if fy < 0 { self.max.y } else { center.y },
+                );
+                let ratio = |now: f32, then: f32, at: f32| {
+                    let then = then - at;
+                    if then.abs() < f32::EPSILON { 1.0 } else { ((now - at) / then).max(MIN_SCALE) }
+                };
+                if fx != 0 && fy != 0 {
+                    let then = self.start.distance(&anchor);
+                    let s = if then < f32::EPSILON { 1.0 } else { (current.distance(&anchor) / then).max(MIN_SCALE) };
+                    Affine::scale(anchor, s, s)
+                } else if fx != 0 {
+                    Affine::scale(anchor, ratio(current.x, self.start.x, anchor.x), 1.0)
+                } else {
+                    Affine::scale(anchor, 1.0, ratio(current.y, self.start.y, anchor.y))
+                }
+            }
+        }
+    }
+}
+
+/// Ramka uchwytów we współrzędnych świata: obrys kształtu (z krzywymi i dziurami)
+/// powiększony o `GIZMO_MARGIN` pikseli, gdzie piksel ma długość `pixel`.
+pub fn frame(polygon: &Polygon, pixel: f32) -> Option<(Point, Point)> {
+    let outline = polygon.outline();
+    let first = *outline.first()?;
+    let (min, max) = outline.iter().fold((first, first), |(min, max), p| {
+        (Point::new(min.x.min(p.x), min.y.min(p.y)), Point::new(max.x.max(p.x), max.y.max(p.y)))
+    });
+    let margin = Point::new(GIZMO_MARGIN * pixel, GIZMO_MARGIN * pixel);
+    Some((min - margin, max + margin))
+}
+
+/// Uchwyty ramki z ich położeniem: osiem do skalowania i jeden do obrotu.
+pub fn handles(min: Point, max: Point, pixel: f32) -> Vec<(GizmoHandle, Point)> {
+    let center = (min + max) * 0.5;
+    let coord = |f: i8, lo: f32, mid: f32, hi: f32| match f {
+        -1 => lo,
+        0 => mid,
+        _ => hi,
+    };
+    let mut result = Vec::with_capacity(9);
+    for fy in -1..=1 {
+        for fx in -1..=1 {
+            if fx == 0 && fy == 0 {
+                continue;
+            }
+            let at = Point::new(coord(fx, min.x, center.x, max.x), coord(fy, min.y, center.y, max.y));
+            result.push((GizmoHandle::Scale { fx, fy }, at));
+        }
+    }
+    result.push((GizmoHandle::Rotate, Point::new(center.x, min.y - ROTATE_OFFSET * pixel)));
+    result
+}
@@ -0,0 +1,161 @@
+//! Historia cofania. Komendy trzymają całe migawki sceny zamiast odwrotnych operacji:
+//! jeden krok solvera przesuwa naraz wiele wielokątów, ich relacje i sprzężone wierzchołki
+//! (`Coincident`), więc odwracanie pojedynczych pól łatwo rozjechałoby się ze stanem sceny.
+
+use std::collections::VecDeque;
+use crate::geometry::polygon::Polygon;
+use crate::geometry::relation::Relation;
+
+pub const DEFAULT_HISTORY_DEPTH: usize = 100;
+
+/// Stan edytora zapamiętywany przed i po komendzie.
+#[derive(Clone, PartialEq)]
+pub struct Snapshot {
+    pub polygons: Vec<Polygon>,
+    pub relations: Vec<Relation>,
+    pub active_polygon: i32,
+}
+
+/// Jedna cofalna komenda - np. całe przeciągnięcie wierzchołka albo dodanie ograniczenia.
+pub struct EditCommand {
+    pub label: &'static str,
+    before: Snapshot,
+    after: Snapshot,
+}
+
+pub struct History {
+    undo_stack: VecDeque<EditCommand>,
+    redo_stack: Vec<EditCommand>,
+    pending: Option<(&'static str, Snapshot)>,
+    pub max_depth: usize,
+}
+
+impl History {
+    pub fn new(max_depth: usize) -> Self {
+        Self {
+            undo_stack: VecDeque::new(),
+            redo_stack: Vec::new(),
+            pending: None,
+            max_depth,
+        }
+    }
+
+    /// Zaczyna komendę. Jeśli jakaś już trwa (np. przeciąganie), kolejne zmiany do niej dołączają.
+    pub fn begin(&mut self, label: &'static str, polygons: &[Polygon], relations: &[Relation], active_polygon: i32) {
+        if self.pending.is_none() {
+            let before = Snapshot { polygons: polygons.to_vec(), relations: relations.to_vec(), active_polygon };
+            self.pending = Some((label, before));
+        }
+    }
+
+    /// Zamyka trwającą komendę; nic nie zapisuje, jeśli stan się nie zmienił.
+    pub fn commit(&mut self, polygons: &[Polygon], relations: &[Relation], active_polygon: i32) {
+        let Some((label, before)) = self.pending.take() else {
+            return;
+        };
+        let after = Snapshot { polygons: polygons.to_vec(), relations: relations.to_vec(), active_polygon };
+        if before == after {
+            return;
+        }
+        self.undo_stack.push_back(EditCommand { label, before, after });
+        self.redo_stack.clear();
+        self.trim();
+    }
+
+    pub fn undo(&mut self) -> Option<Snapshot> {
+        let command = self.undo_stack.pop_back()?;
+        let snapshot = command.before.clone();
+        self.redo_stack.push(command);
+        Some(snapshot)
+    }
+
+    pub fn redo(&mut self) -> Option<Snapshot> {
+        let command = self.redo_stack.pop()?;
+        let snapshot = command.after.clone();
+        self.undo_stack.push_back(command);
+        Some(snapshot)
+    }
+
+    pub fn undo_label(&self) -> Option<&'static str> {
+        self.undo_stack.back().map(|c| c.label)
+    }
+
+    pub fn redo_label(&self) -> Option<&'static str> {
+        self.redo_stack.last().map(|c| c.label)
+    }
+
+    pub fn set_max_depth(&mut self, max_depth: usize) {
+        self.max_depth = max_depth;
+        self.trim();
+    }
+
+    fn trim(&mut self) {
+        while self.undo_stack.len() > self.max_depth {
+            self.undo_stack.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::point::Point;
+
+    fn triangle(x: f32) -> Polygon {
+        Polygon::new(vec![Point::new(x, 0.0), Point::new(x + 10.0, 0.0), Point::new(x, 10.0)])
+    }
+
+    #[test]
+    fn undo_redo_undo_restores_snapshots() {
+        let mut history = History::new(DEFAULT_HISTORY_DEPTH);
+        let before = vec![triangle(0.0)];
+        let after = vec![triangle(0.0), triangle(50.0)];
+        history.begin("Dodanie wielokata", &before, &[], 0);
+        history.commit(&after, &[], 1);
+        assert_eq!(history.undo_label(), Some("Dodanie wielokata"));
+
+        let undone = history.undo().unwrap();
+        assert!(undone.polygons == before && undone.active_polygon == 0);
+        assert_eq!(history.redo_label(), Some("Dodanie wielokata"));
+        let redone = history.redo().unwrap();
+        assert!(redone.polygons == after && redone.active_polygon == 1);
+        let undone = history.undo().unwrap();
+        assert!(undone.polygons == before && undone.active_polygon == 0);
+        assert!(history.undo().is_none());
+    }
+
+    #[test]
+    fn unchanged_command_is_not_recorded() {
+        let mut history = History::new(DEFAULT_HISTORY_DEPTH);
+        let polygons = vec![triangle(0.0)];
+        history.begin("Przeciagniecie", &polygons, &[], 0);
+        history.commit(&polygons, &[], 0);
+        assert!(history.undo().is_none());
+    }
+
+    #[test]
+    fn depth_limit_drops_oldest_and_new_edit_clears_redo() {
+        let mut history = History::new(3);
+        let states: Vec<Vec<Polygon>> = (0..6).map(|k| vec![triangle(k as f32 * 10.0)]).collect();
+        for k in 0..5 {
+            history.begin("Przesuniecie", &states[k], &[], 0);
+            history.commit(&states[k + 1], &[], 0);
+        }
+        // zostaly trzy ostatnie komendy; najstarsza dostepna cofa do stanu 2
+        let mut oldest = None;
+        while let Some(snapshot) = history.undo() {
+            oldest = Some(snapshot);
+        }
+        assert!(oldest.unwrap().polygons == states[2]);
+
+        assert!(history.redo().is_some());
+        history.begin("Przesuniecie", &states[3], &[], 0);
+        history.commit(&states[0], &[], 0);
+        assert!(history.redo().is_none());
+        assert!(history.undo().unwrap().polygons == states[3]);
+
+        // zmniejszenie limitu tez przycina historie
+        history.set_max_depth(0);
+        assert!(history.undo().is_none());
+    }
+}
@@ -1,3 +1,7 @@
 pub mod selection;
+pub mod history;
+pub mod creation;
+pub mod gizmo;
+pub mod snapping;
 
 pub use selection::*;
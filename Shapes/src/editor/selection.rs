@@ -1,10 +1,21 @@
-use crate::geometry::polygon::{ConstraintType, Polygon};
-use crate::geometry::point::{Continuity, Point};
-use crate::geometry::point::PointRole::Vertex;
+use crate::geometry::polygon::Polygon;
+use crate::geometry::point::Point;
+
+/// Wierzchołek w zaznaczeniu grupowym - także z dziury (`contour` jak w `Polygon::contour`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SelectedVertex {
+    pub polygon: usize,
+    pub contour: usize,
+    pub vertex: usize,
+}
 
 pub struct Selection {
     pub selected_vertex: Option<usize>,
     pub selected_control: Option<(usize, u8)>,
+    /// zaznaczenie wielu wierzchołków (Shift+klik, prostokąt), także w różnych wielokątach
+    pub group: Vec<SelectedVertex>,
+    /// prostokąt zaznaczania: punkt początkowy i aktualna pozycja kursora
+    pub band: Option<(Point, Point)>,
 }
 
 impl Selection {
@@ -13,69 +24,183 @@ impl Selection {
         {
             selected_vertex: None,
             selected_control: None,
+            group: Vec::new(),
+            band: None,
         }
     }
 
-    pub fn select_vertex(&mut self, polygon: &Polygon, mouse_pos: Point, radius: f32) -> Option<usize> {
-        if let Some(idx) = polygon.vertices.iter().position(|v| v.distance(&mouse_pos) < radius) {
-            self.selected_vertex = Some(idx);
-            return Some(idx);
+    pub fn is_selected(&self, v: SelectedVertex) -> bool {
+        self.group.contains(&v)
+    }
+
+    pub fn toggle(&mut self, v: SelectedVertex) {
+        match self.group.iter().position(|g| *g == v) {
+            Some(k) => {
+                self.group.remove(k);
+            }
+            None => self.group.push(v),
         }
+    }
 
-        for (i, constraint_opt) in polygon.constraints.iter().enumerate() {
-            if let Some(ConstraintType::Bezier { control1, control2, .. }) = constraint_opt {
-                if control1.distance(&mouse_pos) < radius {
-                    self.selected_vertex = None;
-                    self.selected_control = Some((i, 1));
-                    return None;
-                }
-                if control2.distance(&mouse_pos) < radius {
-                    self.selected_vertex = None;
-                    self.selected_control = Some((i, 2));
-                    return None;
+    /// Dokłada do grupy wszystkie wierzchołki (ze wszystkich konturów) leżące w prostokącie a-b.
+    pub fn select_in_rect(&mut self, polygons: &[Polygon], a: Point, b: Point) {
+        let (min_x, max_x) = (a.x.min(b.x), a.x.max(b.x));
+        let (min_y, max_y) = (a.y.min(b.y), a.y.max(b.y));
+        for (p_idx, polygon) in polygons.iter().enumerate() {
+            for contour in 0..polygon.contour_count() {
+                for (vertex, v) in polygon.contour(contour).vertices.iter().enumerate() {
+                    let key = SelectedVertex { polygon: p_idx, contour, vertex };
+                    let inside = v.x >= min_x && v.x <= max_x && v.y >= min_y && v.y <= max_y;
+                    if inside && !self.is_selected(key) {
+                        self.group.push(key);
+                    }
                 }
             }
         }
-
-        self.selected_vertex = None;
-        self.selected_control = None;
-        None
     }
 
+    /// Wyrzuca z grupy wierzchołki, których już nie ma (usunięty wielokąt, cofnięcie itp.).
+    pub fn retain_valid(&mut self, polygons: &[Polygon]) {
+        self.group.retain(|v| {
+            polygons
+                .get(v.polygon)
+                .filter(|p| v.contour < p.contour_count())
+                .is_some_and(|p| v.vertex < p.contour(v.contour).vertices.len())
+        });
+    }
 
+    /// Krawędzie, których oba końce są zaznaczone: (wielokąt, kontur, krawędź).
+    pub fn selected_edges(&self, polygons: &[Polygon]) -> Vec<(usize, usize, usize)> {
+        let mut edges = Vec::new();
+        for v in &self.group {
+            let contour = polygons[v.polygon].contour(v.contour);
+            let Some(edge) = contour.outgoing_edge(v.vertex) else {
+                continue;
+            };
+            let end = SelectedVertex { vertex: contour.edge_end(edge), ..*v };
+            if self.is_selected(end) {
+                edges.push((v.polygon, v.contour, edge));
+            }
+        }
+        edges
+    }
 
-    pub fn select_edge(&self, polygon: &Polygon, mouse: &Point, radius: f32) -> Option<usize> {
-        let mut closest = None;
-        let mut min_dist = radius;
+    /// Prostokąt obejmujący zaznaczone wierzchołki (min, max).
+    pub fn bounding_box(&self, polygons: &[Polygon]) -> Option<(Point, Point)> {
+        let mut points = self.group.iter().map(|v| polygons[v.polygon].contour(v.contour).vertices[v.vertex]);
+        let first = points.next()?;
+        Some(points.fold((first, first), |(min, max), p| {
+            (Point::new(min.x.min(p.x), min.y.min(p.y)), Point::new(max.x.max(p.x), max.y.max(p.y)))
+        }))
+    }
 
-        let n = polygon.vertices.len();
-        if n < 2 {
-            return None;
-        }
+    // wygrywa najblizszy z wierzcholkow i uchwytow krzywych, a nie pierwszy w zasiegu -
+    // inaczej uchwyt lezacy przy wierzcholku nie dalby sie zlapac
+    pub fn select_vertex(&mut self, polygon: &Polygon, mouse_pos: Point, radius: f32) -> Option<usize> {
+        let vertices = polygon
+            .vertices
+            .iter()
+            .enumerate()
+            .map(|(idx, v)| (v.distance(&mouse_pos), Some(idx), None));
+        // punkty kontrolne numerujemy od 1, tak jak control1/control2
+        let controls = polygon.constraints.iter().enumerate().flat_map(|(i, constraint_opt)| {
+            let controls = constraint_opt.as_ref().and_then(|c| c.bezier_controls()).unwrap_or_default();
+            controls
+                .into_iter()
+                .enumerate()
+                .map(move |(k, c)| (c.distance(&mouse_pos), None, Some((i, (k + 1) as u8))))
+        });
+        let nearest = vertices
+            .chain(controls)
+            .filter(|(dist, _, _)| *dist < radius)
+            .min_by(|a, b| a.0.total_cmp(&b.0));
 
-        for i in 0..n {
-            let start = &polygon.vertices[i];
-            let end = &polygon.vertices[(i + 1) % n]; // <--- UWAGA TU: % n robi wrap-around
-            let dist = distance_point_to_segment(mouse, start, end);
-            if dist < min_dist {
-                min_dist = dist;
-                closest = Some(i);
+        match nearest {
+            Some((_, Some(idx), _)) => {
+                self.selected_vertex = Some(idx);
+                Some(idx)
+            }
+            Some((_, None, control)) => {
+                self.selected_vertex = None;
+                self.selected_control = control;
+                None
+            }
+            None => {
+                self.selected_vertex = None;
+                self.selected_control = None;
+                None
             }
         }
-        closest
+    }
+
+    // krawedz liczona po prawdziwym ksztalcie (krzywa, luk), nie po cieciwie
+    pub fn select_edge(&self, polygon: &Polygon, mouse: &Point, radius: f32) -> Option<usize> {
+        (0..polygon.edge_count())
+            .map(|i| (i, polygon.distance_to_edge(i, *mouse)))
+            .filter(|(_, dist)| *dist < radius)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(i, _)| i)
     }
 }
-fn distance_point_to_segment(p: &Point, a: &Point, b: &Point) -> f32 {
-    let ap = Point { x: p.x - a.x, y: p.y - a.y , role: Vertex, continuity: Continuity::None };
-    let ab = Point { x: b.x - a.x, y: b.y - a.y , role: Vertex, continuity: Continuity::None };
-    let ab2 = ab.x * ab.x + ab.y * ab.y;
-    let dot = ap.x * ab.x + ap.y * ab.y;
-    let t = (dot / ab2).clamp(0.0, 1.0);
-    let closest = Point {
-        x: a.x + ab.x * t,
-        y: a.y + ab.y * t,
-        role: Vertex,
-        continuity: Continuity::None,
-    };
-    closest.distance(&p)
+
+/// Wierzchołek pod kursorem w dowolnym wielokącie i konturze.
+pub fn vertex_at(polygons: &[Polygon], mouse: Point, radius: f32) -> Option<SelectedVertex> {
+    polygons.iter().enumerate().find_map(|(p_idx, polygon)| {
+        (0..polygon.contour_count()).find_map(|contour| {
+            polygon
+                .contour(contour)
+                .vertices
+                .iter()
+                .position(|v| v.distance(&mouse) < radius)
+                .map(|vertex| SelectedVertex { polygon: p_idx, contour, vertex })
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(x: f32, y: f32, size: f32) -> Vec<Point> {
+        vec![Point::new(x, y), Point::new(x + size, y), Point::new(x + size, y + size), Point::new(x, y + size)]
+    }
+
+    fn key(polygon: usize, contour: usize, vertex: usize) -> SelectedVertex {
+        SelectedVertex { polygon, contour, vertex }
+    }
+
+    #[test]
+    fn rect_selects_vertices_of_all_contours_once() {
+        let mut outer = Polygon::new(square(0.0, 0.0, 100.0));
+        outer.add_hole(square(40.0, 40.0, 20.0));
+        let polygons = vec![outer, Polygon::new(square(200.0, 0.0, 10.0))];
+        let mut selection = Selection::new();
+        // prostokat podany od prawego dolnego rogu
+        selection.select_in_rect(&polygons, Point::new(205.0, 50.0), Point::new(50.0, -5.0));
+        assert_eq!(selection.group, vec![key(0, 0, 1), key(0, 1, 1), key(1, 0, 0), key(1, 0, 3)]);
+        selection.select_in_rect(&polygons, Point::new(90.0, -5.0), Point::new(110.0, 5.0));
+        assert_eq!(selection.group.len(), 4);
+    }
+
+    #[test]
+    fn selected_edges_need_both_ends() {
+        let closed = Polygon::new(square(0.0, 0.0, 10.0));
+        let open = Polygon::new_open(square(20.0, 0.0, 10.0));
+        let polygons = vec![closed, open];
+        let mut selection = Selection::new();
+        selection.group = vec![key(0, 0, 3), key(0, 0, 0), key(0, 0, 2), key(1, 0, 3), key(1, 0, 0)];
+        // w zamknietym 2-3 i 3-0 (zamykajaca); w otwartej 3 i 0 nie sa polaczone
+        assert_eq!(selection.selected_edges(&polygons), vec![(0, 0, 3), (0, 0, 2)]);
+    }
+
+    #[test]
+    fn retain_valid_drops_missing_vertices() {
+        let mut polygon = Polygon::new(square(0.0, 0.0, 10.0));
+        polygon.add_hole(square(2.0, 2.0, 2.0));
+        let polygons = vec![polygon];
+        let mut selection = Selection::new();
+        selection.group = vec![key(0, 0, 3), key(0, 0, 4), key(0, 1, 0), key(0, 2, 0), key(1, 0, 0)];
+        selection.retain_valid(&polygons);
+        assert_eq!(selection.group, vec![key(0, 0, 3), key(0, 1, 0)]);
+    }
 }
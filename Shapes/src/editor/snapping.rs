@@ -0,0 +1,240 @@
+use crate::editor::selection::SelectedVertex;
+use crate::geometry::point::Point;
+use crate::geometry::polygon::Polygon;
+use crate::geometry::vertex_constraint::Guide;
+
+/// Kąty przyciągania względem poprzedniego wierzchołka idą co tyle stopni.
+pub const SNAP_ANGLE_STEP_DEG: f32 = 15.0;
+/// Promień przyciągania w pikselach ekranu (w świecie zależy od powiększenia).
+pub const SNAP_RADIUS: f32 = 8.0;
+
+/// Co przyciąga przeciągane wierzchołki i punkty kontrolne. Do siatki przyciągamy
+/// tylko wtedy, gdy jest widoczna.
+pub struct SnapSettings {
+    pub enabled: bool,
+    pub show_grid: bool,
+    pub grid_spacing: f32,
+    pub to_grid: bool,
+    pub to_guides: bool,
+    pub to_vertices: bool,
+    pub to_midpoints: bool,
+    pub to_angles: bool,
+}
+
+impl Default for SnapSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            show_grid: false,
+            grid_spacing: 20.0,
+            to_grid: true,
+            to_guides: true,
+            to_vertices: true,
+            to_midpoints: true,
+            to_angles: true,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum SnapKind {
+    Vertex,
+    Midpoint,
+    Guide,
+    /// kierunek od poprzedniego wierzchołka, `degrees` to wielokrotność 15°
+    Angle { from: Point, degrees: f32 },
+    Grid,
+}
+
+/// Wynik przyciągania - punkt, do którego skoczył kursor, i powód (do narysowania znacznika).
+#[derive(Clone, Copy)]
+pub struct Snap {
+    pub point: Point,
+    pub kind: SnapKind,
+}
+
+impl SnapKind {
+    pub fn label(&self) -> String {
+        match self {
+            SnapKind::Vertex => "wierzcholek".to_string(),
+            SnapKind::Midpoint => "srodek krawedzi".to_string(),
+            SnapKind::Guide => "prowadnica".to_string(),
+            SnapKind::Angle { degrees, .. } => format!("{:.0}°", degrees),
+            SnapKind::Grid => "siatka".to_string(),
+        }
+    }
+}
+
+/// Wszystko, czego potrzebuje przyciąganie jednego przeciąganego punktu.
+pub struct SnapContext<'a> {
+    pub settings: &'a SnapSettings,
+    pub guides: &'a [Guide],
+    pub polygons: &'a [Polygon],
+    /// długość piksela ekranu w jednostkach świata - progi przyciągania są w pikselach
+    pub pixel: f32,
+    /// przeciągany wierzchołek - nie przyciąga sam siebie ani środków swoich krawędzi
+    pub exclude: Option<SelectedVertex>,
+    /// od tego punktu liczymy kąty co 15° (poprzedni wierzchołek albo wierzchołek punktu kontrolnego)
+    pub anchor: Option<Point>,
+}
+
+impl SnapContext<'_> {
+    /// Przyciąga `p` do najważniejszego celu bliżej niż `SNAP_RADIUS` pikseli.
+    /// Kolejność: wierzchołki i środki krawędzi, prowadnice, kąty od `anchor`, na końcu węzły siatki.
+    pub fn snap(&self, p: Point) -> Option<Snap> {
+        let settings = self.settings;
+        if !settings.enabled {
+            return None;
+        }
+        let radius = SNAP_RADIUS * self.pixel;
+
+        let mut best: Option<(f32, Snap)> = None;
+        let mut consider = |point: Point, kind: SnapKind| {
+            let d = point.distance(&p);
+            if d < radius && best.is_none_or(|(best_d, _)| d < best_d) {
+                best = Some((d, Snap { point, kind }));
+            }
+        };
+        for (p_idx, polygon) in self.polygons.iter().enumerate() {
+            for c in 0..polygon.contour_count() {
+                let contour = polygon.contour(c);
+                let n = contour.vertices.len();
+                let moving = |v: usize| self.exclude == Some(SelectedVertex { polygon: p_idx, contour: c, vertex: v });
+                if settings.to_vertices {
+                    for (_, vertex) in contour.vertices.iter().enumerate().filter(|(v, _)| !moving(*v)) {
+                        consider(*vertex, SnapKind::Vertex);
+                    }
+                }
+                if settings.to_midpoints {
+                    for e in (0..contour.edge_count()).filter(|e| !moving(*e) && !moving((e + 1) % n)) {
+                        consider((contour.vertices[e] + contour.vertices[(e + 1) % n]) * 0.5, SnapKind::Midpoint);
+                    }
+                }
+            }
+        }
+        if let Some((_, snap)) = best {
+            return Some(snap);
+        }
+
+        // prowadnice przyciagaja kazda os osobno, wiec na skrzyzowaniu lapia obie
+        if settings.to_guides {
+            let mut snapped = p;
+            let mut hit = false;
+            let nearest = |values: &mut dyn Iterator<Item = f32>, at: f32| {
+                values.map(|v| (v, (v - at).abs())).filter(|(_, d)| *d < radius).min_by(|a, b| a.1.total_cmp(&b.1))
+            };
+            let mut xs = self.guides.iter().filter_map(|g| if let Guide::Vertical(x) = g { Some(*x) } else { None });
+            if let Some((x, _)) = nearest(&mut xs, p.x) {
+                snapped.x = x;
+                hit = true;
+            }
+            let mut ys = self.guides.iter().filter_map(|g| if let Guide::Horizontal(y) = g { Some(*y) } else { None });
+            if let Some((y, _)) = nearest(&mut ys, p.y) {
+                snapped.y = y;
+                hit = true;
+            }
+            if hit {
+                return Some(Snap { point: snapped, kind: SnapKind::Guide });
+            }
+        }
+
+        if let (true, Some(from)) = (settings.to_angles, self.anchor) {
+            let d = p - from;
+            if d.length() > radius {
+                let step = SNAP_ANGLE_STEP_DEG.to_radians();
+                let angle = (d.y.atan2(d.x) / step).round() * step;
+                let dir = Point::new(angle.cos(), angle.sin());
+                let projected = from + dir * d.dot(dir);
+                if projected.distance(&p) < radius {
+                    let degrees = (-angle.to_degrees()).rem_euclid(360.0);
+                    return Some(Snap { point: projected, kind: SnapKind::Angle { from, degrees } });
+                }
+            }
+        }
+
+        // siatka tylko wtedy, gdy jest widoczna, i tez tylko z bliska
+        if settings.show_grid && settings.to_grid && settings.grid_spacing > 0.0 {
+            let s = settings.grid_spacing;
+            let point = Point::new((p.x / s).round() * s, (p.y / s).round() * s);
+            if point.distance(&p) < radius {
+                return Some(Snap { point, kind: SnapKind::Grid });
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context<'a>(settings: &'a SnapSettings, guides: &'a [Guide], polygons: &'a [Polygon], anchor: Option<Point>) -> SnapContext<'a> {
+        SnapContext { settings, guides, polygons, pixel: 1.0, exclude: None, anchor }
+    }
+
+    fn grid_settings() -> SnapSettings {
+        SnapSettings { show_grid: true, ..SnapSettings::default() }
+    }
+
+    #[test]
+    fn vertex_beats_guide_angle_and_grid() {
+        let settings = grid_settings();
+        let polygons = vec![Polygon::new(vec![Point::new(103.0, 0.0), Point::new(200.0, 0.0), Point::new(200.0, 50.0)])];
+        let guides = [Guide::Vertical(101.0)];
+        // wszystkie cztery cele sa w promieniu, wygrywa wierzcholek
+        let snap = context(&settings, &guides, &polygons, Some(Point::new(0.0, 0.0))).snap(Point::new(100.0, 1.0)).unwrap();
+        assert!(matches!(snap.kind, SnapKind::Vertex));
+        assert_eq!((snap.point.x, snap.point.y), (103.0, 0.0));
+    }
+
+    #[test]
+    fn guide_beats_angle_and_grid() {
+        let settings = grid_settings();
+        let guides = [Guide::Vertical(101.0)];
+        let snap = context(&settings, &guides, &[], Some(Point::new(0.0, 0.0))).snap(Point::new(100.0, 1.0)).unwrap();
+        assert!(matches!(snap.kind, SnapKind::Guide));
+        assert_eq!((snap.point.x, snap.point.y), (101.0, 1.0));
+    }
+
+    #[test]
+    fn angle_beats_grid() {
+        let settings = grid_settings();
+        let snap = context(&settings, &[], &[], Some(Point::new(0.0, 0.0))).snap(Point::new(100.0, 1.0)).unwrap();
+        match snap.kind {
+            SnapKind::Angle { degrees, .. } => assert_eq!(degrees, 0.0),
+            _ => panic!("oczekiwano przyciagniecia do kata"),
+        }
+        assert!(snap.point.y.abs() < 1e-4);
+
+        let snap = context(&settings, &[], &[], None).snap(Point::new(101.0, 1.0)).unwrap();
+        assert!(matches!(snap.kind, SnapKind::Grid));
+        assert_eq!((snap.point.x, snap.point.y), (100.0, 0.0));
+    }
+
+    #[test]
+    fn targets_outside_radius_are_ignored() {
+        let settings = grid_settings();
+        let polygons = vec![Polygon::new(vec![Point::new(0.0, 0.0), Point::new(40.0, 0.0), Point::new(40.0, 40.0)])];
+        let guides = [Guide::Horizontal(150.0)];
+        // srodek oczka siatki: do wezla ~14 px, do wszystkiego innego dalej niz SNAP_RADIUS
+        let far = Point::new(70.0, 30.0);
+        assert!(context(&settings, &guides, &polygons, None).snap(far).is_none());
+
+        // ten sam uklad przy powiekszeniu 1/4: piksel to 4 jednostki swiata, wiec wezel siatki juz lapie
+        let zoomed_out = SnapContext { pixel: 4.0, ..context(&settings, &guides, &polygons, None) };
+        let snap = zoomed_out.snap(Point::new(90.0, 30.0)).unwrap();
+        assert!(matches!(snap.kind, SnapKind::Grid));
+
+        let hidden = SnapSettings::default();
+        assert!(context(&hidden, &[], &[], None).snap(Point::new(101.0, 1.0)).is_none());
+    }
+
+    #[test]
+    fn dragged_vertex_does_not_snap_to_itself() {
+        let settings = SnapSettings::default();
+        let polygons = vec![Polygon::new(vec![Point::new(0.0, 0.0), Point::new(50.0, 0.0), Point::new(50.0, 50.0)])];
+        let exclude = Some(SelectedVertex { polygon: 0, contour: 0, vertex: 1 });
+        let drag = SnapContext { exclude, ..context(&settings, &[], &polygons, None) };
+        assert!(drag.snap(Point::new(51.0, 1.0)).is_none());
+    }
+}
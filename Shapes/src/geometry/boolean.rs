@@ -0,0 +1,598 @@
+use super::point::Point;
+use super::polygon::FillRule;
+
+/// Operacja logiczna na dwóch zbiorach konturów (A - podmiot, B - drugi wielokąt).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BooleanOp {
+    Union,
+    Intersection,
+    /// A bez B
+    Difference,
+    Xor,
+}
+
+/// Jeden spójny kawałek wyniku: kontur zewnętrzny i dziury w nim.
+#[derive(Clone)]
+pub struct Region {
+    pub outer: Vec<Point>,
+    pub holes: Vec<Vec<Point>>,
+}
+
+// punkty blizej niz tyle pikseli uznajemy za ten sam punkt
+const DIST_EPS: f64 = 1e-4;
+// jak daleko od fragmentu probkujemy, po ktorej stronie jest wnetrze
+const PROBE: f64 = 1e-3;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct P {
+    x: f64,
+    y: f64,
+}
+
+// odcinek od punktu do punktu; kierunek ma znaczenie przy sklejaniu konturow
+type Edge = (P, P);
+
+impl P {
+    fn from(p: &Point) -> Self {
+        P { x: p.x as f64, y: p.y as f64 }
+    }
+    fn sub(self, o: P) -> P {
+        P { x: self.x - o.x, y: self.y - o.y }
+    }
+    fn add(self, o: P) -> P {
+        P { x: self.x + o.x, y: self.y + o.y }
+    }
+    fn mul(self, s: f64) -> P {
+        P { x: self.x * s, y: self.y * s }
+    }
+    fn cross(self, o: P) -> f64 {
+        self.x * o.y - self.y * o.x
+    }
+    fn dot(self, o: P) -> f64 {
+        self.x * o.x + self.y * o.y
+    }
+    fn len(self) -> f64 {
+        self.dot(self).sqrt()
+    }
+    fn near(self, o: P) -> bool {
+        self.sub(o).len() < DIST_EPS
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Class {
+    Inside,
+    Outside,
+    /// wspolny odcinek brzegu, wnetrza obu po tej samej stronie
+    SharedSame,
+    /// wspolny odcinek brzegu, wnetrza po przeciwnych stronach
+    SharedOpposite,
+}
+
+/// Operacja logiczna w stylu Weilera-Athertona: wszystkie krawędzie A i B są dzielone
+/// w punktach wzajemnych przecięć (także na odcinkach wspólnych i w stykających się
+/// wierzchołkach), każdy fragment jest klasyfikowany jako wewnątrz / na zewnątrz drugiego
+/// zbioru albo wspólny, a wybrane fragmenty są z powrotem sklejane w kontury.
+/// Kontury wejścia interpretujemy regułą wypełniania danego zbioru, więc mogą już
+/// zawierać dziury i samoprzecięcia. Krzywe trzeba wcześniej spłaszczyć (`Polygon::outlines`).
+pub fn boolean(
+    subject: &[Vec<Point>],
+    subject_rule: FillRule,
+    clip: &[Vec<Point>],
+    clip_rule: FillRule,
+    op: BooleanOp,
+) -> Vec<Region> {
+    let a = prepare(subject);
+    let b = prepare(clip);
+    let (frags_a, frags_b) = split_edges(&a, &b);
+    let frags_a = orient(frags_a, &a, subject_rule);
+    let frags_b = orient(frags_b, &b, clip_rule);
+
+    let mut kept: Vec<Edge> = Vec::new();
+    for &(s, e) in &frags_a {
+        let class = classify(s, e, &b, clip_rule, &frags_b);
+        let keep = match (op, class) {
+            (BooleanOp::Union, Class::Outside | Class::SharedSame) => Some(false),
+            (BooleanOp::Intersection, Class::Inside | Class::SharedSame) => Some(false),
+            (BooleanOp::Difference, Class::Outside | Class::SharedOpposite) => Some(false),
+            (BooleanOp::Xor, Class::Outside) => Some(false),
+            (BooleanOp::Xor, Class::Inside) => Some(true),
+            _ => None,
+        };
+        if let Some(reverse) = keep {
+            kept.push(if reverse { (e, s) } else { (s, e) });
+        }
+    }
+    for &(s, e) in &frags_b {
+        // wspolne odcinki rozstrzyga juz strona A
+        let class = classify(s, e, &a, subject_rule, &frags_a);
+        let keep = match (op, class) {
+            (BooleanOp::Union, Class::Outside) => Some(false),
+            (BooleanOp::Intersection, Class::Inside) => Some(false),
+            (BooleanOp::Difference, Class::Inside) => Some(true),
+            (BooleanOp::Xor, Class::Outside) => Some(false),
+            (BooleanOp::Xor, Class::Inside) => Some(true),
+            _ => None,
+        };
+        if let Some(reverse) = keep {
+            kept.push(if reverse { (e, s) } else { (s, e) });
+        }
+    }
+
+    let rings: Vec<Vec<P>> = link(&kept).into_iter().filter_map(simplify).collect();
+    group(rings)
+}
+
+// bez powtorzonych punktow i konturow, ktore nie maja pola
+fn prepare(contours: &[Vec<Point>]) -> Vec<Vec<P>> {
+    contours
+        .iter()
+        .filter_map(|contour| {
+            let mut ring: Vec<P> = Vec::with_capacity(contour.len());
+            for p in contour.iter().map(P::from) {
+                if !ring.last().is_some_and(|last| last.near(p)) {
+                    ring.push(p);
+                }
+            }
+            while ring.len() > 1 && ring[0].near(ring[ring.len() - 1]) {
+                ring.pop();
+            }
+            (ring.len() >= 3 && signed_area(&ring).abs() > DIST_EPS).then_some(ring)
+        })
+        .collect()
+}
+
+fn edges(contours: &[Vec<P>]) -> Vec<Edge> {
+    contours
+        .iter()
+        .flat_map(|ring| (0..ring.len()).map(move |i| (ring[i], ring[(i + 1) % ring.len()])))
+        .collect()
+}
+
+// dzieli krawedzie A i B we wszystkich punktach wspolnych; oba zbiory dostaja te same punkty
+fn split_edges(a: &[Vec<P>], b: &[Vec<P>]) -> (Vec<Edge>, Vec<Edge>) {
+    let edges_a = edges(a);
+    let edges_b = edges(b);
+    let mut cuts_a: Vec<Vec<(f64, P)>> = edges_a.iter().map(|&(s, e)| vec![(0.0, s), (1.0, e)]).collect();
+    let mut cuts_b: Vec<Vec<(f64, P)>> = edges_b.iter().map(|&(s, e)| vec![(0.0, s), (1.0, e)]).collect();
+
+    for (i, &(p0, p1)) in edges_a.iter().enumerate() {
+        for (j, &(q0, q1)) in edges_b.iter().enumerate() {
+            for (t, u, point) in intersect(p0, p1, q0, q1) {
+                cuts_a[i].push((t, point));
+                cuts_b[j].push((u, point));
+            }
+        }
+    }
+    (fragments(cuts_a), fragments(cuts_b))
+}
+
+fn fragments(cuts: Vec<Vec<(f64, P)>>) -> Vec<Edge> {
+    let mut result = Vec::new();
+    for mut edge_cuts in cuts {
+        let end = edge_cuts[1].1;
+        // sortowanie jest stabilne, wiec poczatek krawedzi zostaje na pierwszym miejscu
+        edge_cuts.sort_by(|x, y| x.0.total_cmp(&y.0));
+        let mut points: Vec<P> = Vec::with_capacity(edge_cuts.len());
+        for (_, p) in edge_cuts {
+            if !points.last().is_some_and(|last| last.near(p)) {
+                points.push(p);
+            }
+        }
+        // koniec krawedzi ma pierwszenstwo przed przecieciem policzonym tuz obok niego
+        if let Some(last) = points.last_mut() {
+            *last = end;
+        }
+        result.extend(points.windows(2).map(|w| (w[0], w[1])));
+    }
+    result
+}
+
+// punkty wspolne odcinkow p0-p1 i q0-q1: (parametr na p, parametr na q, punkt)
+fn intersect(p0: P, p1: P, q0: P, q1: P) -> Vec<(f64, f64, P)> {
+    let r = p1.sub(p0);
+    let s = q1.sub(q0);
+    let (r_len, s_len) = (r.len(), s.len());
+    if r_len < DIST_EPS || s_len < DIST_EPS {
+        return Vec::new();
+    }
+    let denom = r.cross(s);
+    let mut result = Vec::new();
+
+    if denom.abs() <= 1e-10 * r_len * s_len {
+        // rownolegle - wspolny kawalek istnieje tylko dla odcinkow na jednej prostej
+        if (q0.sub(p0).cross(r) / r_len).abs() > DIST_EPS {
+            return result;
+        }
+        for q in [q0, q1] {
+            let t = q.sub(p0).dot(r) / (r_len * r_len);
+            if t * r_len > -DIST_EPS && (t - 1.0) * r_len < DIST_EPS {
+                let u = if q == q0 { 0.0 } else { 1.0 };
+                result.push((t.clamp(0.0, 1.0), u, q));
+            }
+        }
+        for p in [p0, p1] {
+            let u = p.sub(q0).dot(s) / (s_len * s_len);
+            if u * s_len > -DIST_EPS && (u - 1.0) * s_len < DIST_EPS {
+                let t = if p == p0 { 0.0 } else { 1.0 };
+                result.push((t, u.clamp(0.0, 1.0), p));
+            }
+        }
+        return result;
+    }
+
+    let d = q0.sub(p0);
+    let t = d.cross(s) / denom;
+    let u = d.cross(r) / denom;
+    let t_tol = DIST_EPS / r_len;
+    let u_tol = DIST_EPS / s_len;
+    if t < -t_tol || t > 1.0 + t_tol || u < -u_tol || u > 1.0 + u_tol {
+        return result;
+    }
+    // przeciecie w wierzcholku bierze jego dokladne wspolrzedne, zeby konce fragmentow sie zgadzaly
+    let point = if t.abs() <= t_tol {
+        p0
+    } else if (t - 1.0).abs() <= t_tol {
+        p1
+    } else if u.abs() <= u_tol {
+        q0
+    } else if (u - 1.0).abs() <= u_tol {
+        q1
+    } else {
+        p0.add(r.mul(t))
+    };
+    result.push((t.clamp(0.0, 1.0), u.clamp(0.0, 1.0), point));
+    result
+}
+
+// odwraca fragmenty tak, by wnetrze wlasnego zbioru zawsze lezalo po lewej; fragmenty
+// z wnetrzem (albo zewnetrzem) po obu stronach nie sa brzegiem - przy non-zero tak
+// wyglada krawedz zakladki, ktora lezy wewnatrz innej czesci tego samego wielokata
+fn orient(frags: Vec<Edge>, own: &[Vec<P>], rule: FillRule) -> Vec<Edge> {
+    frags
+        .into_iter()
+        .filter(|(s, e)| !s.near(*e))
+        .filter_map(|(s, e)| {
+            let d = e.sub(s);
+            let left = P { x: -d.y, y: d.x }.mul(PROBE / d.len());
+            let mid = s.add(e).mul(0.5);
+            match (inside(own, rule, mid.add(left)), inside(own, rule, mid.sub(left))) {
+                (true, false) => Some((s, e)),
+                (false, true) => Some((e, s)),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+fn classify(s: P, e: P, other: &[Vec<P>], rule: FillRule, other_frags: &[Edge]) -> Class {
+    for &(os, oe) in other_frags {
+        if os.near(s) && oe.near(e) {
+            return Class::SharedSame;
+        }
+        if os.near(e) && oe.near(s) {
+            return Class::SharedOpposite;
+        }
+    }
+    if inside(other, rule, s.add(e).mul(0.5)) {
+        Class::Inside
+    } else {
+        Class::Outside
+    }
+}
+
+// numer obiegu liczony po wszystkich konturach naraz, o wnetrzu decyduje regula wypelniania
+fn inside(contours: &[Vec<P>], rule: FillRule, p: P) -> bool {
+    let mut winding = 0;
+    for ring in contours {
+        let n = ring.len();
+        let mut j = n - 1;
+        for i in 0..n {
+            let (a, b) = (ring[j], ring[i]);
+            if (a.y > p.y) != (b.y > p.y) && p.x < (b.x - a.x) * (p.y - a.y) / (b.y - a.y) + a.x {
+                winding += if b.y > a.y { 1 } else { -1 };
+            }
+            j = i;
+        }
+    }
+    rule.contains(winding)
+}
+
+// skleja fragmenty w zamkniete kontury; w punkcie z kilkoma wyjsciami skrecamy najbardziej
+// w lewo, wiec stykajace sie w wierzcholku obszary zostaja osobnymi konturami
+fn link(kept: &[Edge]) -> Vec<Vec<P>> {
+    let mut used = vec![false; kept.len()];
+    let mut rings = Vec::new();
+
+    for start in 0..kept.len() {
+        if used[start] {
+            continue;
+        }
+        used[start] = true;
+        let mut ring = vec![kept[start].0];
+        let mut current = kept[start];
+
+        loop {
+            let incoming = current.1.sub(current.0);
+            let candidates = (0..kept.len())
+                .filter(|&k| (!used[k] || k == start) && kept[k].0.near(current.1));
+            let next = candidates.max_by(|&x, &y| {
+                let turn = |k: usize| {
+                    let out = kept[k].1.sub(kept[k].0);
+                    incoming.cross(out).atan2(incoming.dot(out))
+                };
+                turn(x).total_cmp(&turn(y))
+            });
+            match next {
+                Some(k) if k == start => {
+                    rings.push(ring);
+                    break;
+                }
+                Some(k) => {
+                    used[k] = true;
+                    ring.push(kept[k].0);
+                    current = kept[k];
+                }
+                // nie da sie zamknac (numeryka) - porzucamy lancuch
+                None => break,
+            }
+        }
+    }
+    rings
+}
+
+// usuwa punkty posrodku prostych odcinkow; kontur bez pola znika
+fn simplify(ring: Vec<P>) -> Option<Vec<P>> {
+    let mut ring = ring;
+    let mut changed = true;
+    while changed && ring.len() >= 3 {
+        changed = false;
+        let n = ring.len();
+        for i in 0..n {
+            let prev = ring[(i + n - 1) % n];
+            let next = ring[(i + 1) % n];
+            let a = ring[i].sub(prev);
+            let b = next.sub(ring[i]);
+            let straight = a.len() < DIST_EPS || (a.cross(b).abs() / (a.len() * b.len().max(DIST_EPS)) < 1e-9 && a.dot(b) > 0.0);
+            if straight {
+                ring.remove(i);
+                changed = true;
+                break;
+            }
+        }
+    }
+    (ring.len() >= 3 && signed_area(&ring).abs() > DIST_EPS).then_some(ring)
+}
+
+fn signed_area(ring: &[P]) -> f64 {
+    let n = ring.len();
+    (0..n).map(|i| ring[i].cross(ring[(i + 1) % n])).sum::<f64>() / 2.0
+}
+
+// kontury z wnetrzem po lewej maja dodatnie pole (zewnetrzne), ujemne to dziury;
+// dziura trafia do najmniejszego konturu zewnetrznego, ktory ja zawiera. Dziura bez
+// wlasciciela (moze sie zdarzyc przy bledach zaokraglen) nie ginie - odwracamy ja
+// i oddajemy jako osobny obszar
+fn group(rings: Vec<Vec<P>>) -> Vec<Region> {
+    let (outers, holes): (Vec<Vec<P>>, Vec<Vec<P>>) = rings.into_iter().partition(|r| signed_area(r) > 0.0);
+    let mut regions: Vec<(f64, Vec<P>, Vec<Vec<P>>)> =
+        outers.into_iter().map(|r| (signed_area(&r), r, Vec::new())).collect();
+    let mut orphans = Vec::new();
+
+    for hole in holes {
+        // punkt tuz wewnatrz dziury (po prawej stronie jej pierwszej krawedzi)
+        let d = hole[1].sub(hole[0]);
+        let right = P { x: d.y, y: -d.x }.mul(PROBE / d.len());
+        let probe = hole[0].add(hole[1]).mul(0.5).add(right);
+        let owner = regions
+            .iter_mut()
+            .filter(|(_, outer, _)| inside(std::slice::from_ref(outer), FillRule::EvenOdd, probe))
+            .min_by(|x, y| x.0.total_cmp(&y.0));
+        match owner {
+            Some((_, _, owner_holes)) => owner_holes.push(hole),
+            None => orphans.push(hole),
+        }
+    }
+    for mut orphan in orphans {
+        orphan.reverse();
+        regions.push((signed_area(&orphan), orphan, Vec::new()));
+    }
+
+    let to_points = |ring: &[P]| ring.iter().map(|p| Point::new(p.x as f32, p.y as f32)).collect::<Vec<Point>>();
+    regions
+        .into_iter()
+        .map(|(_, outer, holes)| Region { outer: to_points(&outer), holes: holes.iter().map(|h| to_points(h)).collect() })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x0: f32, y0: f32, x1: f32, y1: f32) -> Vec<Point> {
+        vec![Point::new(x0, y0), Point::new(x1, y0), Point::new(x1, y1), Point::new(x0, y1)]
+    }
+
+    fn area(ring: &[Point]) -> f32 {
+        let n = ring.len();
+        ((0..n).map(|i| ring[i].x * ring[(i + 1) % n].y - ring[(i + 1) % n].x * ring[i].y).sum::<f32>() / 2.0).abs()
+    }
+
+    fn total_area(regions: &[Region]) -> f32 {
+        regions
+            .iter()
+            .map(|r| area(&r.outer) - r.holes.iter().map(|h| area(h)).sum::<f32>())
+            .sum()
+    }
+
+    fn run(a: Vec<Point>, b: Vec<Point>, op: BooleanOp) -> Vec<Region> {
+        boolean(&[a], FillRule::EvenOdd, &[b], FillRule::EvenOdd, op)
+    }
+
+    #[test]
+    fn overlapping_squares() {
+        let a = rect(0.0, 0.0, 10.0, 10.0);
+        let b = rect(5.0, 5.0, 15.0, 15.0);
+        assert!((total_area(&run(a.clone(), b.clone(), BooleanOp::Union)) - 175.0).abs() < 1e-3);
+        assert!((total_area(&run(a.clone(), b.clone(), BooleanOp::Intersection)) - 25.0).abs() < 1e-3);
+        assert!((total_area(&run(a.clone(), b.clone(), BooleanOp::Difference)) - 75.0).abs() < 1e-3);
+        assert!((total_area(&run(b.clone(), a.clone(), BooleanOp::Difference)) - 75.0).abs() < 1e-3);
+        let xor = run(a, b, BooleanOp::Xor);
+        assert_eq!(xor.len(), 2);
+        assert!((total_area(&xor) - 150.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn orientation_does_not_matter() {
+        let a = rect(0.0, 0.0, 10.0, 10.0);
+        let mut b = rect(5.0, 5.0, 15.0, 15.0);
+        b.reverse();
+        let union = run(a, b, BooleanOp::Union);
+        assert_eq!(union.len(), 1);
+        assert_eq!(union[0].outer.len(), 8);
+    }
+
+    #[test]
+    fn disjoint() {
+        let a = rect(0.0, 0.0, 10.0, 10.0);
+        let b = rect(20.0, 0.0, 30.0, 10.0);
+        assert_eq!(run(a.clone(), b.clone(), BooleanOp::Union).len(), 2);
+        assert!(run(a.clone(), b.clone(), BooleanOp::Intersection).is_empty());
+        let diff = run(a, b, BooleanOp::Difference);
+        assert_eq!(diff.len(), 1);
+        assert!((total_area(&diff) - 100.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn identical_polygons() {
+        let a = rect(0.0, 0.0, 10.0, 10.0);
+        let union = run(a.clone(), a.clone(), BooleanOp::Union);
+        assert_eq!(union.len(), 1);
+        assert!((total_area(&union) - 100.0).abs() < 1e-3);
+        assert!((total_area(&run(a.clone(), a.clone(), BooleanOp::Intersection)) - 100.0).abs() < 1e-3);
+        assert!(run(a.clone(), a.clone(), BooleanOp::Difference).is_empty());
+        assert!(run(a.clone(), a, BooleanOp::Xor).is_empty());
+    }
+
+    #[test]
+    fn shared_edge() {
+        let a = rect(0.0, 0.0, 10.0, 10.0);
+        let b = rect(10.0, 0.0, 20.0, 10.0);
+        let union = run(a.clone(), b.clone(), BooleanOp::Union);
+        assert_eq!(union.len(), 1);
+        // wspolna krawedz znika, zostaje prostokat bez punktow posrodku bokow
+        assert_eq!(union[0].outer.len(), 4);
+        assert!((total_area(&union) - 200.0).abs() < 1e-3);
+        assert!(run(a.clone(), b.clone(), BooleanOp::Intersection).is_empty());
+        let diff = run(a, b, BooleanOp::Difference);
+        assert!((total_area(&diff) - 100.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn partially_shared_edge() {
+        let a = rect(0.0, 0.0, 10.0, 10.0);
+        let b = rect(10.0, 5.0, 20.0, 15.0);
+        let union = run(a.clone(), b.clone(), BooleanOp::Union);
+        assert_eq!(union.len(), 1);
+        assert!((total_area(&union) - 200.0).abs() < 1e-3);
+        assert!(run(a, b, BooleanOp::Intersection).is_empty());
+    }
+
+    #[test]
+    fn shared_edge_inside() {
+        // B lezy w A i dzieli z nim czesc lewego boku
+        let a = rect(0.0, 0.0, 10.0, 10.0);
+        let b = rect(0.0, 2.0, 4.0, 6.0);
+        assert!((total_area(&run(a.clone(), b.clone(), BooleanOp::Union)) - 100.0).abs() < 1e-3);
+        assert!((total_area(&run(a.clone(), b.clone(), BooleanOp::Intersection)) - 16.0).abs() < 1e-3);
+        let diff = run(a.clone(), b.clone(), BooleanOp::Difference);
+        assert_eq!(diff.len(), 1);
+        assert!(diff[0].holes.is_empty());
+        assert!((total_area(&diff) - 84.0).abs() < 1e-3);
+        assert!(run(b, a, BooleanOp::Difference).is_empty());
+    }
+
+    #[test]
+    fn touching_vertices() {
+        let a = rect(0.0, 0.0, 10.0, 10.0);
+        let b = rect(10.0, 10.0, 20.0, 20.0);
+        let union = run(a.clone(), b.clone(), BooleanOp::Union);
+        // stykaja sie tylko rogiem - zostaja dwa osobne obszary
+        assert_eq!(union.len(), 2);
+        assert!((total_area(&union) - 200.0).abs() < 1e-3);
+        assert!(run(a.clone(), b.clone(), BooleanOp::Intersection).is_empty());
+        assert!((total_area(&run(a, b, BooleanOp::Difference)) - 100.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn vertex_on_edge() {
+        // wierzcholek trojkata dotyka boku kwadratu od zewnatrz
+        let a = rect(0.0, 0.0, 10.0, 10.0);
+        let b = vec![Point::new(5.0, 10.0), Point::new(10.0, 20.0), Point::new(0.0, 20.0)];
+        let union = run(a.clone(), b.clone(), BooleanOp::Union);
+        assert!((total_area(&union) - 150.0).abs() < 1e-3);
+        assert!(run(a, b, BooleanOp::Intersection).is_empty());
+    }
+
+    #[test]
+    fn difference_makes_hole() {
+        let a = rect(0.0, 0.0, 10.0, 10.0);
+        let b = rect(3.0, 3.0, 6.0, 6.0);
+        let diff = run(a.clone(), b.clone(), BooleanOp::Difference);
+        assert_eq!(diff.len(), 1);
+        assert_eq!(diff[0].holes.len(), 1);
+        assert!((total_area(&diff) - 91.0).abs() < 1e-3);
+        let xor = run(a, b, BooleanOp::Xor);
+        assert!((total_area(&xor) - 91.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn union_splits_into_many() {
+        // krzyz z dwoch prostokatow - jeden kontur z 12 wierzcholkami
+        let a = rect(0.0, 4.0, 12.0, 8.0);
+        let b = rect(4.0, 0.0, 8.0, 12.0);
+        let union = run(a.clone(), b.clone(), BooleanOp::Union);
+        assert_eq!(union.len(), 1);
+        assert_eq!(union[0].outer.len(), 12);
+        // A bez B rozpada sie na dwa kawalki
+        assert_eq!(run(a, b, BooleanOp::Difference).len(), 2);
+    }
+
+    #[test]
+    fn collinear_overlap_and_degenerate_input() {
+        let a = vec![Point::new(0.0, 0.0), Point::new(5.0, 0.0), Point::new(10.0, 0.0), Point::new(10.0, 10.0), Point::new(10.0, 10.0), Point::new(0.0, 10.0)];
+        let b = rect(2.0, 0.0, 8.0, 5.0);
+        let diff = run(a, b, BooleanOp::Difference);
+        assert_eq!(diff.len(), 1);
+        assert!((total_area(&diff) - 70.0).abs() < 1e-3);
+        assert!(boolean(&[], FillRule::EvenOdd, &[rect(0.0, 0.0, 1.0, 1.0)], FillRule::EvenOdd, BooleanOp::Intersection).is_empty());
+    }
+
+    #[test]
+    fn fill_rule_of_input_is_honoured() {
+        // dwa kwadraty jeden w drugim, oba w tym samym kierunku: even-odd robi z
+        // wewnetrznego dziure, non-zero wypelnia calosc
+        let nested = vec![rect(0.0, 0.0, 10.0, 10.0), rect(3.0, 3.0, 7.0, 7.0)];
+        let far = rect(20.0, 0.0, 21.0, 1.0);
+        let even_odd = boolean(&nested, FillRule::EvenOdd, std::slice::from_ref(&far), FillRule::EvenOdd, BooleanOp::Union);
+        assert!((total_area(&even_odd) - 85.0).abs() < 1e-3);
+        let non_zero = boolean(&nested, FillRule::NonZero, std::slice::from_ref(&far), FillRule::EvenOdd, BooleanOp::Union);
+        assert!((total_area(&non_zero) - 101.0).abs() < 1e-3);
+        assert!(non_zero.iter().all(|r| r.holes.is_empty()));
+
+        let inner = rect(4.0, 4.0, 6.0, 6.0);
+        assert!(boolean(&nested, FillRule::EvenOdd, std::slice::from_ref(&inner), FillRule::EvenOdd, BooleanOp::Intersection).is_empty());
+        let hit = boolean(&nested, FillRule::NonZero, &[inner], FillRule::EvenOdd, BooleanOp::Intersection);
+        assert!((total_area(&hit) - 4.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn orphan_hole_is_kept() {
+        // kontur zgodny z ruchem wskazowek zegara (ujemne pole) bez konturu, ktory by go zawieral
+        let ring: Vec<P> = rect(0.0, 0.0, 2.0, 2.0).iter().rev().map(P::from).collect();
+        let regions = group(vec![ring]);
+        assert_eq!(regions.len(), 1);
+        assert!(regions[0].holes.is_empty());
+        assert!((total_area(&regions) - 4.0).abs() < 1e-3);
+    }
+}
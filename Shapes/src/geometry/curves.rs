@@ -0,0 +1,301 @@
+use super::point::Point;
+
+/// Domyślna tolerancja spłaszczania krzywych (w pikselach).
+pub const DEFAULT_FLATNESS: f32 = 0.25;
+const MAX_SUBDIVISION_DEPTH: u32 = 16;
+
+/// Adaptacyjne spłaszczanie krzywej Beziera dowolnego stopnia algorytmem de Casteljau:
+/// dzielimy krzywą na pół, dopóki punkty kontrolne odstają od cięciwy (odcinka, nie prostej)
+/// o więcej niż `tolerance`. `points` to cały wielobok kontrolny (początek, punkty kontrolne, koniec),
+/// wynik to łamana od pierwszego do ostatniego punktu (oba końce włącznie).
+pub fn flatten_bezier_curve(points: &[Point], tolerance: f32) -> Vec<Point> {
+    let Some(&first) = points.first() else {
+        return Vec::new();
+    };
+    let mut out = vec![first];
+    if points.len() > 1 {
+        flatten_recursive(points, tolerance.max(1e-3), 0, &mut out);
+    }
+    out
+}
+
+fn flatten_recursive(points: &[Point], tolerance: f32, depth: u32, out: &mut Vec<Point>) {
+    let first = points[0];
+    let last = points[points.len() - 1];
+    let flat = points[1..points.len() - 1]
+        .iter()
+        .all(|p| distance_to_segment(*p, first, last) <= tolerance);
+    if flat || depth >= MAX_SUBDIVISION_DEPTH {
+        out.push(last);
+        return;
+    }
+
+    let (left, right) = split_bezier(points, 0.5);
+    flatten_recursive(&left, tolerance, depth + 1, out);
+    flatten_recursive(&right, tolerance, depth + 1, out);
+}
+
+/// Dzieli krzywą w punkcie `t` na dwie krzywe tego samego stopnia.
+pub fn split_bezier(points: &[Point], t: f32) -> (Vec<Point>, Vec<Point>) {
+    let mut work = points.to_vec();
+    let mut left = Vec::with_capacity(points.len());
+    let mut right = Vec::with_capacity(points.len());
+    for level in 0..points.len() {
+        let len = points.len() - level;
+        left.push(work[0]);
+        right.push(work[len - 1]);
+        for i in 0..len - 1 {
+            work[i] = lerp(work[i], work[i + 1], t);
+        }
+    }
+    right.reverse();
+    (left, right)
+}
+
+/// Punkt krzywej dla parametru `t` (de Casteljau).
+pub fn bezier_point(points: &[Point], t: f32) -> Point {
+    let mut work = points.to_vec();
+    for len in (1..points.len()).rev() {
+        for i in 0..len {
+            work[i] = lerp(work[i], work[i + 1], t);
+        }
+    }
+    work[0]
+}
+
+/// Najbliższy punkt krzywej do `p` jako (t, punkt): gęste próbkowanie, a potem
+/// zawężanie przedziału wokół najlepszej próbki (krzywa może mieć kilka minimów lokalnych).
+pub fn closest_point_on_bezier(points: &[Point], p: Point) -> (f32, Point) {
+    const SAMPLES: usize = 32;
+    let dist = |t: f32| bezier_point(points, t).distance(&p);
+    let best = (0..=SAMPLES)
+        .map(|i| i as f32 / SAMPLES as f32)
+        .min_by(|a, b| dist(*a).total_cmp(&dist(*b)))
+        .unwrap_or(0.0);
+
+    let step = 1.0 / SAMPLES as f32;
+    let (mut lo, mut hi) = ((best - step).max(0.0), (best + step).min(1.0));
+    for _ in 0..24 {
+        let m1 = lo + (hi - lo) / 3.0;
+        let m2 = hi - (hi - lo) / 3.0;
+        if dist(m1) < dist(m2) {
+            hi = m2;
+        } else {
+            lo = m1;
+        }
+    }
+    let t = (lo + hi) / 2.0;
+    (t, bezier_point(points, t))
+}
+
+/// Odległość punktu od odcinka a-b.
+pub fn distance_to_segment(p: Point, a: Point, b: Point) -> f32 {
+    let ab = b - a;
+    let len2 = ab.dot(ab);
+    if len2 < f32::EPSILON {
+        return p.distance(&a);
+    }
+    let t = ((p - a).dot(ab) / len2).clamp(0.0, 1.0);
+    p.distance(&(a + ab * t))
+}
+
+/// Odległość punktu od łuku (geometria jak w `Polygon::arc_geometry`): do okręgu,
+/// jeśli rzut punktu wypada na łuku, a w przeciwnym razie do bliższego końca.
+pub fn distance_to_arc(p: Point, center: Point, radius: f32, start_angle: f32, sweep: f32) -> f32 {
+    let angle = (p.y - center.y).atan2(p.x - center.x);
+    if angle_in_sweep(angle, start_angle, sweep) {
+        return (p.distance(&center) - radius).abs();
+    }
+    let end_angle = start_angle + sweep;
+    let start = Point::new(center.x + radius * start_angle.cos(), center.y + radius * start_angle.sin());
+    let end = Point::new(center.x + radius * end_angle.cos(), center.y + radius * end_angle.sin());
+    p.distance(&start).min(p.distance(&end))
+}
+
+/// Podniesienie stopnia o 1 - ta sama krzywa, jeden punkt kontrolny więcej.
+pub fn elevate_degree(points: &[Point]) -> Vec<Point> {
+    let n = points.len();
+    if n < 2 {
+        return points.to_vec();
+    }
+    let degree = (n - 1) as f32;
+    let mut out = Vec::with_capacity(n + 1);
+    out.push(points[0]);
+    for i in 1..n {
+        let a = i as f32 / (degree + 1.0);
+        out.push(lerp(points[i], points[i - 1], a));
+    }
+    out.push(points[n - 1]);
+    out
+}
+
+/// Obniżenie stopnia o 1 (metoda Forresta: pierwsza połowa punktów liczona od początku,
+/// druga od końca). Dokładne, gdy krzywa powstała przez `elevate_degree`, w pozostałych
+/// przypadkach przybliżone. Zwraca `None` dla odcinka.
+pub fn reduce_degree(points: &[Point]) -> Option<Vec<Point>> {
+    let n = points.len();
+    if n < 3 {
+        return None;
+    }
+    let degree = (n - 1) as f32;
+
+    // od poczatku: Q_i = (n P_i - i Q_{i-1}) / (n - i)
+    let mut forward = vec![points[0]; n - 1];
+    for i in 1..n - 1 {
+        let fi = i as f32;
+        forward[i] = (points[i] * degree - forward[i - 1] * fi) * (1.0 / (degree - fi));
+    }
+
+    // od konca: Q_{i-1} = (n P_i - (n - i) Q_i) / i
+    let mut backward = vec![points[n - 1]; n - 1];
+    for i in (1..n - 1).rev() {
+        let fi = i as f32;
+        backward[i - 1] = (points[i] * degree - backward[i] * (degree - fi)) * (1.0 / fi);
+    }
+
+    let half = (n - 1) / 2;
+    let mut out: Vec<Point> = (0..n - 1)
+        .map(|i| if i < half { forward[i] } else { backward[i] })
+        .collect();
+    out[0] = points[0];
+    out[n - 2] = points[n - 1];
+    Some(out)
+}
+
+fn lerp(a: Point, b: Point, t: f32) -> Point {
+    Point { x: a.x + (b.x - a.x) * t, y: a.y + (b.y - a.y) * t, ..a }
+}
+
+/// Łamana po łuku okręgu od kąta `start_angle` do `end_angle` (bez zawijania przez ±π).
+pub fn sample_arc(center: Point, radius: f32, start_angle: f32, end_angle: f32, steps: usize) -> Vec<Point> {
+    let steps = steps.max(1);
+    (0..=steps)
+        .map(|i| {
+            let angle = start_angle + (end_angle - start_angle) * i as f32 / steps as f32;
+            Point::new(center.x + radius * angle.cos(), center.y + radius * angle.sin())
+        })
+        .collect()
+}
+
+/// Czy kąt `angle` leży na łuku zaczynającym się w `start_angle` o skierowanej rozwartości
+/// `sweep` (dodatni - rosnące kąty, ujemny - malejące). Działa też przy przejściu przez ±π.
+pub fn angle_in_sweep(angle: f32, start_angle: f32, sweep: f32) -> bool {
+    let tau = std::f32::consts::TAU;
+    if sweep.abs() >= tau {
+        return true;
+    }
+    if sweep >= 0.0 {
+        (angle - start_angle).rem_euclid(tau) <= sweep
+    } else {
+        (start_angle - angle).rem_euclid(tau) <= -sweep
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+    use super::*;
+
+    fn arch() -> Vec<Point> {
+        vec![Point::new(0.0, 0.0), Point::new(0.0, 10.0), Point::new(10.0, 10.0), Point::new(10.0, 0.0)]
+    }
+
+    #[test]
+    fn closest_point_in_the_middle() {
+        // krzywa jest symetryczna, wiec punkt nad srodkiem rzutuje sie na t = 0.5 (wierzcholek luku, y = 7.5)
+        // (odleglosc przy minimum prawie sie nie zmienia, stad luzniejsza tolerancja dla t)
+        let p = Point::new(5.0, 20.0);
+        let (t, point) = closest_point_on_bezier(&arch(), p);
+        assert!((t - 0.5).abs() < 1e-2);
+        assert!((point.distance(&p) - 12.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn closest_point_clamps_to_ends() {
+        let (t, point) = closest_point_on_bezier(&arch(), Point::new(-5.0, -5.0));
+        assert!(t < 1e-3);
+        assert!(point.distance(&Point::new(0.0, 0.0)) < 1e-3);
+        let (t, point) = closest_point_on_bezier(&arch(), Point::new(15.0, -5.0));
+        assert!(t > 1.0 - 1e-3);
+        assert!(point.distance(&Point::new(10.0, 0.0)) < 1e-3);
+    }
+
+    #[test]
+    fn distance_to_arc_across_pi() {
+        let center = Point::new(0.0, 0.0);
+        let at = |deg: f32, r: f32| Point::new(r * deg.to_radians().cos(), r * deg.to_radians().sin());
+        // luk 170° -> 190° przechodzi przez ±180°, gdzie atan2 przeskakuje z pi na -pi
+        for (start, sweep) in [(170.0_f32, 20.0_f32), (-170.0, -20.0)] {
+            let (start, sweep) = (start.to_radians(), sweep.to_radians());
+            assert!((distance_to_arc(at(180.0, 12.0), center, 10.0, start, sweep) - 2.0).abs() < 1e-4);
+            assert!((distance_to_arc(at(-175.0, 9.0), center, 10.0, start, sweep) - 1.0).abs() < 1e-4);
+            // po drugiej stronie okregu liczy sie blizszy koniec luku, nie okrag
+            let far = at(0.0, 10.0);
+            let end = at(170.0, 10.0);
+            assert!((distance_to_arc(far, center, 10.0, start, sweep) - far.distance(&end)).abs() < 1e-3);
+        }
+        assert!(distance_to_arc(at(90.0, 10.0), center, 10.0, 0.0, 2.0 * PI) < 1e-4);
+    }
+
+    // najwieksza odleglosc punktow krzywej od lamanej
+    fn deviation(points: &[Point], polyline: &[Point]) -> f32 {
+        (0..=400)
+            .map(|k| {
+                let p = bezier_point(points, k as f32 / 400.0);
+                polyline.windows(2).map(|w| distance_to_segment(p, w[0], w[1])).fold(f32::MAX, f32::min)
+            })
+            .fold(0.0, f32::max)
+    }
+
+    #[test]
+    fn flatten_keeps_overshoot_past_the_chord() {
+        // punkty kontrolne leza na prostej przez konce, ale poza cieciwa: krzywa siega x ~ 35.4,
+        // a cieciwa tylko do 10
+        let points = [Point::new(0.0, 0.0), Point::new(100.0, 0.0), Point::new(-50.0, 0.0), Point::new(10.0, 0.0)];
+        let flat = flatten_bezier_curve(&points, DEFAULT_FLATNESS);
+        let reach = flat.iter().map(|p| p.x).fold(f32::MIN, f32::max);
+        assert!(reach > 35.39 - DEFAULT_FLATNESS, "lamana siega tylko do x = {}", reach);
+        assert!(deviation(&points, &flat) <= DEFAULT_FLATNESS + 1e-3);
+    }
+
+    #[test]
+    fn flatten_stays_within_tolerance() {
+        for tolerance in [2.0, 0.25, 0.01] {
+            let flat = flatten_bezier_curve(&arch(), tolerance);
+            assert!(flat[0] == arch()[0] && *flat.last().unwrap() == arch()[3]);
+            assert!(deviation(&arch(), &flat) <= tolerance + 1e-4);
+        }
+        // mniejsza tolerancja - wiecej odcinkow
+        assert!(flatten_bezier_curve(&arch(), 0.01).len() > flatten_bezier_curve(&arch(), 2.0).len());
+    }
+
+    #[test]
+    fn flatten_stops_at_depth_cap() {
+        // przy tej skali tolerancji nie da sie osiagnac, podzial konczy dopiero limit glebokosci
+        let huge: Vec<Point> = arch().iter().map(|p| *p * 1e8).collect();
+        let flat = flatten_bezier_curve(&huge, 0.0);
+        assert!(flat.len() <= (1 << MAX_SUBDIVISION_DEPTH) + 1);
+        assert!(flat[0] == huge[0] && *flat.last().unwrap() == huge[3]);
+    }
+
+    #[test]
+    fn elevate_keeps_the_curve() {
+        let elevated = elevate_degree(&arch());
+        assert_eq!(elevated.len(), 5);
+        for k in 0..=20 {
+            let t = k as f32 / 20.0;
+            assert!(bezier_point(&arch(), t).distance(&bezier_point(&elevated, t)) < 1e-4);
+        }
+    }
+
+    #[test]
+    fn reduce_undoes_elevate() {
+        let reduced = reduce_degree(&elevate_degree(&arch())).unwrap();
+        assert_eq!(reduced.len(), 4);
+        for (a, b) in reduced.iter().zip(arch()) {
+            assert!(a.distance(&b) < 1e-4);
+        }
+        // odcinka nie da sie obnizyc
+        assert!(reduce_degree(&arch()[..2]).is_none());
+    }
+}
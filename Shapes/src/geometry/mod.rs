@@ -1,3 +1,7 @@
 pub mod point;
 pub mod polygon;
-
+pub mod curves;
+pub mod relation;
+pub mod vertex_constraint;
+pub mod boolean;
+pub mod transform;
@@ -1,7 +1,10 @@
 use crate::geometry::point::PointRole::Vertex;
 use super::point::{Continuity, Point, PointRole};
+use super::curves::{closest_point_on_bezier, distance_to_arc, distance_to_segment, elevate_degree, flatten_bezier_curve, reduce_degree, sample_arc, DEFAULT_FLATNESS};
+use super::vertex_constraint::VertexConstraint;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Copy)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub enum ConstraintType {
     Horizontal,
     Vertical,
@@ -10,6 +13,15 @@ pub enum ConstraintType {
     Arc {
         g1_start: bool,
         g1_end: bool,
+        /// promień łuku; mniejszy niż pół cięciwy (np. 0) oznacza półokrąg
+        #[serde(default)]
+        radius: f32,
+        /// kierunek obiegu od początku do końca krawędzi, tak jak widać na ekranie (y w dół)
+        #[serde(default)]
+        clockwise: bool,
+        /// dłuższy z dwóch łuków o tym promieniu
+        #[serde(default)]
+        large_arc: bool,
     },
     Line,
     Bezier {
@@ -20,18 +32,171 @@ pub enum ConstraintType {
         c1_start: bool,
         c1_end: bool,
     },
+    /// krzywa Beziera drugiego stopnia - jeden punkt kontrolny
+    QuadraticBezier {
+        control: Point,
+    },
+    /// krzywa Beziera dowolnego stopnia (stopień = liczba punktów kontrolnych + 1)
+    BezierN {
+        controls: Vec<Point>,
+    },
+}
+
+impl ConstraintType {
+    pub fn is_bezier(&self) -> bool {
+        matches!(
+            self,
+            ConstraintType::Bezier { .. } | ConstraintType::QuadraticBezier { .. } | ConstraintType::BezierN { .. }
+        )
+    }
+
+    /// Punkty kontrolne krzywej (bez końców krawędzi), `None` gdy to nie jest Bezier.
+    pub fn bezier_controls(&self) -> Option<Vec<Point>> {
+        match self {
+            ConstraintType::Bezier { control1, control2, .. } => Some(vec![*control1, *control2]),
+            ConstraintType::QuadraticBezier { control } => Some(vec![*control]),
+            ConstraintType::BezierN { controls } => Some(controls.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn bezier_controls_mut(&mut self) -> Vec<&mut Point> {
+        match self {
+            ConstraintType::Bezier { control1, control2, .. } => vec![control1, control2],
+            ConstraintType::QuadraticBezier { control } => vec![control],
+            ConstraintType::BezierN { controls } => controls.iter_mut().collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Stopień krzywej (0 dla krawędzi, które nie są krzywymi Beziera).
+    pub fn bezier_degree(&self) -> usize {
+        self.bezier_controls().map_or(0, |c| c.len() + 1)
+    }
+
+    /// Wybiera wariant pasujący do liczby punktów kontrolnych (0 punktów = zwykła krawędź).
+    pub fn bezier_from_controls(controls: Vec<Point>) -> Option<ConstraintType> {
+        let controls: Vec<Point> = controls
+            .into_iter()
+            .map(|p| Point { role: PointRole::Control, ..p })
+            .collect();
+        match controls.len() {
+            0 => None,
+            1 => Some(ConstraintType::QuadraticBezier { control: controls[0] }),
+            2 => Some(ConstraintType::Bezier {
+                control1: controls[0],
+                control2: controls[1],
+                g1_start: false,
+                g1_end: false,
+                c1_start: false,
+                c1_end: false,
+            }),
+            _ => Some(ConstraintType::BezierN { controls }),
+        }
+    }
+}
+
+/// Na ile odcinków dzielimy łuk w `Polygon::outline`.
+pub const OUTLINE_ARC_STEPS: usize = 32;
+
+/// Maksymalne residuum (w pikselach), przy którym uznajemy ograniczenia za spełnione.
+pub const SOLVER_TOLERANCE: f32 = 1e-3;
+pub const SOLVER_MAX_ITERATIONS: usize = 200;
+/// Residuum po wyczerpaniu iteracji, poniżej którego układ uznajemy za wykonalny, tylko wolno
+/// zbieżny: sto razy `SOLVER_TOLERANCE`, czyli 0,1 piksela - tyle zostaje np. po 200 iteracjach
+/// na 48-kącie ze stałymi długościami, a sprzeczne ograniczenia zostawiają odchyłkę rzędu długości krawędzi.
+pub const SOLVER_FEASIBLE_RESIDUAL: f32 = 100.0 * SOLVER_TOLERANCE;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SolveResult {
+    Converged { iterations: usize },
+    NotConverged { residual: f32 },
+}
+
+impl SolveResult {
+    /// Czy ograniczenia da się spełnić: solver się zbiegł albo zabrakło mu tylko iteracji.
+    pub fn is_feasible(&self) -> bool {
+        match *self {
+            SolveResult::Converged { .. } => true,
+            SolveResult::NotConverged { residual } => residual < SOLVER_FEASIBLE_RESIDUAL,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ConflictKind {
+    /// dwie sąsiednie krawędzie poziome (albo pionowe)
+    AdjacentParallel,
+    /// stałe długości, z których nie da się zamknąć wielokąta
+    LengthsCannotClose,
+    /// solver nie znalazł rozwiązania spełniającego wszystkie ograniczenia
+    Unsatisfiable,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConstraintConflict {
+    pub kind: ConflictKind,
+    pub edges: Vec<usize>,
+}
+
+impl ConstraintConflict {
+    pub fn message(&self) -> String {
+        let edges = self.edges.iter().map(|e| e.to_string()).collect::<Vec<_>>().join(", ");
+        match self.kind {
+            ConflictKind::AdjacentParallel => {
+                format!("Sasiednie krawedzie {} nie moga byc jednoczesnie poziome ani pionowe", edges)
+            }
+            ConflictKind::LengthsCannotClose => {
+                format!("Z dlugosci krawedzi {} nie da sie zamknac wielokata (najdluzsza jest dluzsza niz suma pozostalych)", edges)
+            }
+            ConflictKind::Unsatisfiable => {
+                format!("Nie da sie jednoczesnie spelnic ograniczen na krawedziach {}", edges)
+            }
+        }
+    }
 }
 
-#[derive(Clone, Debug)]
-pub enum ContinuityType {
-    G0,
-    G1,
-    G2,
+/// Reguła wypełniania dla wielokątów samoprzecinających się.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FillRule {
+    #[default]
+    EvenOdd,
+    NonZero,
 }
 
+impl FillRule {
+    /// Czy punkt o danym numerze obiegu leży wewnątrz.
+    pub fn contains(self, winding: i32) -> bool {
+        match self {
+            FillRule::EvenOdd => winding % 2 != 0,
+            FillRule::NonZero => winding != 0,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct Polygon {
     pub vertices: Vec<Point>,
     pub constraints: Vec<Option<ConstraintType>>,
+    /// ograniczenia wierzchołków (przypięcie, prowadnica, wspólny punkt) - indeks jak w `vertices`
+    #[serde(default)]
+    pub vertex_constraints: Vec<Option<VertexConstraint>>,
+    /// kolor wypełnienia RGBA (None = tylko kontur)
+    #[serde(default)]
+    pub fill: Option<[u8; 4]>,
+    #[serde(default)]
+    pub fill_rule: FillRule,
+    /// false = otwarta łamana: nie ma krawędzi z ostatniego wierzchołka do pierwszego
+    #[serde(default = "default_closed")]
+    pub closed: bool,
+    /// dziury - każda to osobny zamknięty kontur z własnymi ograniczeniami krawędzi;
+    /// wypełnienie i reguła wypełniania są brane z konturu zewnętrznego
+    #[serde(default)]
+    pub holes: Vec<Polygon>,
+}
+
+fn default_closed() -> bool {
+    true
 }
 
 
@@ -39,17 +204,118 @@ impl Polygon {
     //klasyk konstruktor
     pub fn new(vertices: Vec<Point>) -> Self {
         let constraints = vec![None; vertices.len()];
-        Self{vertices, constraints }
+        let vertex_constraints = vec![None; vertices.len()];
+        Self{vertices, constraints, vertex_constraints, fill: None, fill_rule: FillRule::EvenOdd, closed: true, holes: Vec::new() }
+    }
+
+    /// Otwarta łamana (albo otwarty splajn Beziera).
+    pub fn new_open(vertices: Vec<Point>) -> Self {
+        let mut polygon = Self::new(vertices);
+        polygon.closed = false;
+        polygon.ensure_constraints_len();
+        polygon
     }
 
-    fn sync_constraints(&mut self){
-        if self.constraints.len() != self.vertices.len() {
-            self.constraints.resize(self.vertices.len(), None);
+    /// Liczba krawędzi - w otwartej łamanej o jedną mniej niż wierzchołków.
+    /// Krawędź `i` zawsze biegnie od wierzchołka `i` do `edge_end(i)`.
+    pub fn edge_count(&self) -> usize {
+        let n = self.vertices.len();
+        if n < 2 {
+            0
+        } else if self.closed {
+            n
+        } else {
+            n - 1
         }
     }
 
-    //normal move
+    pub fn edge_end(&self, edge_idx: usize) -> usize {
+        (edge_idx + 1) % self.vertices.len()
+    }
+
+    /// Krawędź wchodząca do wierzchołka (brak dla początku otwartej łamanej).
+    pub fn incoming_edge(&self, v_idx: usize) -> Option<usize> {
+        let n = self.vertices.len();
+        if n < 2 || (v_idx == 0 && !self.closed) {
+            return None;
+        }
+        Some((v_idx + n - 1) % n)
+    }
+
+    /// Krawędź wychodząca z wierzchołka (brak dla końca otwartej łamanej).
+    pub fn outgoing_edge(&self, v_idx: usize) -> Option<usize> {
+        (v_idx < self.edge_count()).then_some(v_idx)
+    }
+
+    /// Zamyka otwartą łamaną krawędzią od ostatniego wierzchołka do pierwszego.
+    pub fn close(&mut self) {
+        if self.closed || self.vertices.len() < 3 {
+            return;
+        }
+        self.closed = true;
+        self.ensure_constraints_len();
+        self.apply_constraints();
+    }
+
+    /// Rozcina zamknięty wielokąt w krawędzi `edge_idx`: wierzchołki są przenumerowane tak,
+    /// że łamana zaczyna się na końcu tej krawędzi, a kończy na jej początku.
+    pub fn open_at(&mut self, edge_idx: usize) {
+        if !self.closed || edge_idx >= self.edge_count() {
+            return;
+        }
+        self.ensure_constraints_len();
+        let shift = self.edge_end(edge_idx);
+        self.vertices.rotate_left(shift);
+        self.constraints.rotate_left(shift);
+        self.vertex_constraints.rotate_left(shift);
+        self.closed = false;
+        // rozcieta krawedz wyladowala na koncu - znika razem z ograniczeniem
+        self.ensure_constraints_len();
+    }
+
+    /// Kontur zewnętrzny i dziury: 0 to sam wielokąt, `k` to dziura `holes[k - 1]`.
+    pub fn contour_count(&self) -> usize {
+        1 + self.holes.len()
+    }
+
+    pub fn contour(&self, contour: usize) -> &Polygon {
+        if contour == 0 { self } else { &self.holes[contour - 1] }
+    }
+
+    pub fn contour_mut(&mut self, contour: usize) -> &mut Polygon {
+        if contour == 0 { self } else { &mut self.holes[contour - 1] }
+    }
+
+    /// Dodaje dziurę; ma sens tylko w zamkniętym wielokącie.
+    pub fn add_hole(&mut self, vertices: Vec<Point>) {
+        let mut hole = Polygon::new(vertices);
+        hole.apply_constraints();
+        self.holes.push(hole);
+    }
+
+    /// Przesuwa cały kształt (wierzchołki, punkty kontrolne, dziury) o (dx, dy).
+    pub fn translate(&mut self, dx: f32, dy: f32) {
+        for v in &mut self.vertices {
+            v.x += dx;
+            v.y += dy;
+        }
+        for constraint in self.constraints.iter_mut().flatten() {
+            for control in constraint.bezier_controls_mut() {
+                control.x += dx;
+                control.y += dy;
+            }
+        }
+        for hole in &mut self.holes {
+            hole.translate(dx, dy);
+        }
+    }
+
+    //normal move - przypiety stoi, na prowadnicy jedzie tylko wzdluz niej
     pub fn move_vertex(&mut self, index: usize, dx:f32, dy: f32){
+        let (dx, dy) = match self.vertex_constraint(index) {
+            Some(constraint) => constraint.constrain_move(dx, dy),
+            None => (dx, dy),
+        };
         if let Some(v) = self.vertices.get_mut(index){
             v.translate(dx, dy);
         }
@@ -58,16 +324,34 @@ impl Polygon {
 
     //usun wierzcholek
     pub fn remove_vertex(&mut self, index: usize){
-        if self.vertices.len() <= 3 {
+        if self.closed && self.vertices.len() <= 3 {
+            return;
+        }
+        if self.vertices.len() <= 2 {
             return;
         }
+        self.ensure_constraints_len();
+        let incoming = self.incoming_edge(index);
+        let outgoing = self.outgoing_edge(index);
         self.vertices.remove(index);
-        if index > 0 {
-            self.constraints[index - 1] = None;
+        if index < self.vertex_constraints.len() {
+            self.vertex_constraints.remove(index);
         }
 
-        if index < self.constraints.len() {
-            self.constraints[index] = None;
+        // znika krawedz wychodzaca, a wchodzaca siega teraz do nastepnego wierzcholka
+        match (incoming, outgoing) {
+            (Some(incoming), Some(outgoing)) => {
+                self.constraints.remove(outgoing);
+                let merged = if incoming < outgoing { incoming } else { self.constraints.len() - 1 };
+                self.constraints[merged] = None;
+            }
+            (None, Some(outgoing)) => {
+                self.constraints.remove(outgoing);
+            }
+            (Some(incoming), None) => {
+                self.constraints.remove(incoming);
+            }
+            (None, None) => {}
         }
         self.ensure_constraints_len();
         self.apply_constraints();
@@ -76,7 +360,7 @@ impl Polygon {
     //dodaj wierzcholek ez
     pub fn add_vertex_mid_edge(&mut self, start_idx: usize, end_idx: usize) {
         let n = self.vertices.len();
-        if n < 2 {
+        if n < 2 || start_idx >= self.edge_count() {
             return
         }
 
@@ -92,9 +376,13 @@ impl Polygon {
         if end_idx == 0 {
             self.vertices.push(mid);
             self.constraints.push(None);
+            self.vertex_constraints.push(None);
         } else {
             self.vertices.insert(end_idx, mid);
             self.constraints.insert(start_idx + 1, None);
+            if end_idx <= self.vertex_constraints.len() {
+                self.vertex_constraints.insert(end_idx, None);
+            }
         }
 
         // usuń constraint na starej krawędzi
@@ -106,13 +394,6 @@ impl Polygon {
         self.apply_constraints();
     }
 
-    // ustawiamy ograniczenia na wybrana krawedz
-    pub fn set_constaint(&mut self, edge_idx: usize, constaint: ConstraintType){
-        if edge_idx < self.constraints.len() {
-            self.constraints[edge_idx] = Some(constaint);
-        }
-    }
-
     // usuwamy ograniczenia na wybrana krawedz
     pub fn remove_constaint(&mut self, edge_idx: usize){
         if edge_idx < self.constraints.len() {
@@ -123,7 +404,7 @@ impl Polygon {
     // self explaiable
     pub fn get_constraint(&self, edge_idx: usize) -> Option<ConstraintType> {
         if edge_idx < self.constraints.len() {
-            self.constraints[edge_idx]
+            self.constraints[edge_idx].clone()
         }else{
             None
         }
@@ -131,29 +412,211 @@ impl Polygon {
 
     // sprawdzanie constrainow
     pub fn ensure_constraints_len(&mut self){
-        let edge_count = if self.vertices.len() > 1 {
-            self.vertices.len()
-        }else{
-            0
-        };
+        let edge_count = self.edge_count();
         if self.constraints.len() != edge_count {
             self.constraints.resize(edge_count, None);
         }
+        if self.vertex_constraints.len() != self.vertices.len() {
+            self.vertex_constraints.resize(self.vertices.len(), None);
+        }
+    }
+
+    pub fn vertex_constraint(&self, index: usize) -> Option<VertexConstraint> {
+        self.vertex_constraints.get(index).copied().flatten()
+    }
+
+    pub fn is_vertex_pinned(&self, index: usize) -> bool {
+        self.vertex_constraint(index).is_some_and(|c| c.is_pinned())
+    }
+
+    /// Przesunięcie całego wielokąta przycięte tak, żeby nie ruszyć przypiętych wierzchołków
+    /// ani nie zepchnąć żadnego z prowadnicy.
+    pub fn constrain_translation(&self, dx: f32, dy: f32) -> (f32, f32) {
+        let own = self
+            .vertex_constraints
+            .iter()
+            .flatten()
+            .fold((dx, dy), |(dx, dy), c| c.constrain_move(dx, dy));
+        self.holes.iter().fold(own, |(dx, dy), hole| hole.constrain_translation(dx, dy))
     }
 
     pub fn apply_constraints(&mut self) {
+        self.solve_constraints(&[]);
+    }
+
+    /// Iteracyjny solver (relaksacja Gaussa-Seidla): rzutuje kolejne krawędzie na ich
+    /// ograniczenia tak długo, aż największe residuum spadnie poniżej `SOLVER_TOLERANCE`.
+    /// Wierzchołki z `pinned` (np. aktualnie przeciągany) nigdy nie są przesuwane.
+    pub fn solve_constraints(&mut self, pinned: &[usize]) -> SolveResult {
+        let n = self.vertices.len();
+        if n < 2 {
+            return SolveResult::Converged { iterations: 0 };
+        }
+        self.ensure_constraints_len();
+
+        let mut result = SolveResult::Converged { iterations: 0 };
+        for iteration in 0..=SOLVER_MAX_ITERATIONS {
+            let residual = self.max_residual();
+            if residual < SOLVER_TOLERANCE {
+                result = SolveResult::Converged { iterations: iteration };
+                break;
+            }
+            if iteration == SOLVER_MAX_ITERATIONS {
+                result = SolveResult::NotConverged { residual };
+                break;
+            }
+
+            self.relax(pinned, &[]);
+        }
+
+        self.fit_curves();
+        result
+    }
+
+    /// Jeden krok relaksacji: każda krawędź z ograniczeniem rzutowana raz.
+    /// Przypięte wierzchołki wracają na swoje miejsce i dalej traktujemy je jak `pinned`.
+    /// `hole_pinned` to trzymane wierzchołki dziur jako (kontur, wierzchołek), kontur jak w `contour`.
+    pub fn relax(&mut self, pinned: &[usize], hole_pinned: &[(usize, usize)]) {
         let n = self.vertices.len();
         if n < 2 {
             return;
         }
+        self.ensure_constraints_len();
+        let edges = self.edge_count();
 
+        let mut pinned = pinned.to_vec();
         for i in 0..n {
+            if let Some(constraint) = self.vertex_constraint(i) {
+                self.vertices[i] = constraint.project(self.vertices[i]);
+                if constraint.is_pinned() {
+                    pinned.push(i);
+                }
+            }
+        }
+
+        for i in 0..edges {
             if let Some(constraint) = self.get_constraint(i) {
-                let start_idx = i;
-                let end_idx = (i + 1) % n;
-                self.enforce_constraint(start_idx, end_idx, &constraint);
+                self.project_edge(i, (i + 1) % n, &constraint, &pinned);
             }
         }
+
+        // dziury maja wlasne ograniczenia i wlasne przypiete wierzcholki
+        for (k, hole) in self.holes.iter_mut().enumerate() {
+            let local: Vec<usize> = hole_pinned.iter().filter(|(c, _)| *c == k + 1).map(|(_, v)| *v).collect();
+            hole.relax(&local, &[]);
+        }
+    }
+
+    /// Punkty kontrolne Beziera nie wpływają na wierzchołki - dopasowujemy je po solverze.
+    pub fn fit_curves(&mut self) {
+        let n = self.vertices.len();
+        for i in 0..self.edge_count() {
+            if let Some(constraint) = self.get_constraint(i).filter(|c| c.is_bezier()) {
+                self.enforce_constraint(i, (i + 1) % n, &constraint);
+            }
+        }
+        for hole in &mut self.holes {
+            hole.fit_curves();
+        }
+    }
+
+    /// Odchyłka krawędzi od jej ograniczenia (0 dla ograniczeń nie dotyczących wierzchołków).
+    pub fn constraint_residual(&self, edge_idx: usize) -> f32 {
+        let n = self.vertices.len();
+        if edge_idx >= self.edge_count() {
+            return 0.0;
+        }
+        let start = self.vertices[edge_idx % n];
+        let end = self.vertices[(edge_idx + 1) % n];
+        let dx = end.x - start.x;
+        let dy = end.y - start.y;
+
+        match self.get_constraint(edge_idx) {
+            Some(ConstraintType::Horizontal) => dy.abs(),
+            Some(ConstraintType::Vertical) => dx.abs(),
+            Some(ConstraintType::Diagonal45) => (dx.abs() - dy.abs()).abs() / std::f32::consts::SQRT_2,
+            Some(ConstraintType::FixedLength(len)) => ((dx * dx + dy * dy).sqrt() - len as f32).abs(),
+            Some(ConstraintType::Arc { g1_start, g1_end, .. }) => {
+                let start_idx = edge_idx % n;
+                let end_idx = (edge_idx + 1) % n;
+                let mut residual: f32 = 0.0;
+                if g1_start {
+                    residual = residual.max(self.tangent_mismatch(start_idx));
+                }
+                if g1_end {
+                    residual = residual.max(self.tangent_mismatch(end_idx));
+                }
+                residual
+            }
+            _ => 0.0,
+        }
+    }
+
+    pub fn max_residual(&self) -> f32 {
+        let vertex_residual = self
+            .vertices
+            .iter()
+            .zip(&self.vertex_constraints)
+            .filter_map(|(v, c)| c.map(|c| c.residual(*v)))
+            .fold(0.0, f32::max);
+        let hole_residual = self.holes.iter().map(|h| h.max_residual()).fold(vertex_residual, f32::max);
+        (0..self.constraints.len())
+            .map(|i| self.constraint_residual(i))
+            .fold(hole_residual, f32::max)
+    }
+
+    // jeden krok relaksacji: przesuwamy konce krawedzi minimalnie, tak by spelnic ograniczenie
+    fn project_edge(&mut self, start_idx: usize, end_idx: usize, constraint: &ConstraintType, pinned: &[usize]) {
+        let w_start = if pinned.contains(&start_idx) { 0.0 } else { 1.0 };
+        let w_end = if pinned.contains(&end_idx) { 0.0 } else { 1.0 };
+        let w_sum = w_start + w_end;
+        if w_sum == 0.0 {
+            return;
+        }
+
+        let start = self.vertices[start_idx];
+        let end = self.vertices[end_idx];
+        let dx = end.x - start.x;
+        let dy = end.y - start.y;
+
+        // docelowy wektor krawedzi (end - start)
+        let (tx, ty) = match constraint {
+            ConstraintType::Horizontal => (dx, 0.0),
+            ConstraintType::Vertical => (0.0, dy),
+            ConstraintType::Diagonal45 => {
+                let sign_x = if dx >= 0.0 { 1.0 } else { -1.0 };
+                let sign_y = if dy >= 0.0 { 1.0 } else { -1.0 };
+                // rzut na najblizsza przekatna o tej samej orientacji
+                let along = (dx * sign_x + dy * sign_y) / 2.0;
+                (along * sign_x, along * sign_y)
+            }
+            ConstraintType::FixedLength(len) => {
+                let current_len = (dx * dx + dy * dy).sqrt();
+                if current_len < f32::EPSILON {
+                    return;
+                }
+                let scale = *len as f32 / current_len;
+                (dx * scale, dy * scale)
+            }
+            ConstraintType::Arc { g1_start, g1_end, .. } => {
+                // geometria luku jest stala, to sasiedzi obracaja sie do jego stycznych
+                if *g1_start {
+                    self.match_neighbour(start_idx, false, Continuity::G1, true, pinned);
+                }
+                if *g1_end {
+                    self.match_neighbour(end_idx, true, Continuity::G1, true, pinned);
+                }
+                return;
+            }
+            _ => return,
+        };
+
+        let cx = tx - dx;
+        let cy = ty - dy;
+        self.vertices[start_idx].x -= cx * w_start / w_sum;
+        self.vertices[start_idx].y -= cy * w_start / w_sum;
+        self.vertices[end_idx].x += cx * w_end / w_sum;
+        self.vertices[end_idx].y += cy * w_end / w_sum;
     }
 
     // matematyczna logika constrainow (najwazniejsza funkcja)
@@ -214,145 +677,86 @@ impl Polygon {
                 }
             }
 
-            ConstraintType::Bezier { .. } => {
-                let n = self.vertices.len();
-                if n < 2 {
-                    return;
-                }
-
-                let v_start = self.vertices[start_idx];
-                let v_end = self.vertices[end_idx];
-                let prev_idx = if start_idx == 0 { n - 1 } else { start_idx - 1 };
-                let next_idx = (end_idx + 1) % n;
-
-                // --- Jeśli ruszono początek tej krzywej ---
-                if let Some(ConstraintType::Bezier { control1, .. }) =
-                    self.constraints.get_mut(start_idx).and_then(|c| c.as_mut())
-                {
-                    let cont = v_start.continuity;
-                    let prev = self.vertices[prev_idx];
-
-                    let new_control1 = match cont {
-                        Continuity::G1 =>{
-                            let dir_x = v_start.x - prev.x;
-                            let dir_y = v_start.y - prev.y;
-                            let len = (dir_x * dir_x + dir_y * dir_y).sqrt();
-
-                            if len < std::f32::EPSILON {
-                            *control1 // nie zmieniamy, bo nie ma sensu
-                            } else {
-                                let current_len = ((control1.x - v_start.x).powi(2) + (control1.y - v_start.y).powi(2)).sqrt();
-                                let norm_x = dir_x / len;
-                                let norm_y = dir_y / len;
-
-                                Point {
-                                    x: v_start.x + norm_x * current_len,
-                                    y: v_start.y + norm_y * current_len,
-                                    role: PointRole::Control,
-                                    continuity: cont,
-                                }
-                            }
-                        },
-                        Continuity::C1 => {
-                            let dx = (v_start.x - prev.x)/3.0;
-                            let dy = (v_start.y - prev.y)/3.0;
-                            Point {
-                                x: v_start.x + dx,
-                                y: v_start.y + dy,
-                                role: PointRole::Control,
-                                continuity: cont,
-                            }
-                        }
-                        _ => *control1,
-                    };
-
-                    *control1 = new_control1;
-
-                    // aktualizuj poprzednią krzywą (odbicie control2)
+            ConstraintType::Bezier { .. } | ConstraintType::QuadraticBezier { .. } | ConstraintType::BezierN { .. } => {
+                // ciaglosc liczymy wzgledem faktycznego sasiedniego segmentu w obu koncach krzywej
+                self.enforce_vertex_continuity(start_idx, None);
+                self.enforce_vertex_continuity(end_idx, None);
+            }
 
-                }
+            _=> {}
+        }
+    }
 
-                //
-                // --- Jeśli ruszono koniec tej krzywej ---
-                //
-                if let Some(ConstraintType::Bezier { control2, .. }) =
-                    self.constraints.get_mut(start_idx).and_then(|c| c.as_mut())
-                {
-                    //eprintln!("CHUJ");
-                    let cont = v_end.continuity;
-                    let next = self.vertices[next_idx];
-
-                    let new_control2 = match cont {
-                        Continuity::G1 => {
-                            let dir_x = v_end.x - next.x;
-                            let dir_y = v_end.y - next.y;
-                            let len = (dir_x * dir_x + dir_y * dir_y).sqrt();
-
-                            if len < std::f32::EPSILON {
-                                *control2 // nie zmieniamy, bo nie ma sensu
-                            } else {
-                                let current_len = ((control2.x - v_end.x).powi(2) + (control2.y - v_end.y).powi(2)).sqrt();
-                                let norm_x = dir_x / len;
-                                let norm_y = dir_y / len;
-
-                                Point {
-                                x: v_end.x + norm_x * current_len,
-                                y: v_end.y + norm_y * current_len,
-                                role: PointRole::Control,
-                                continuity: cont,
-                                }
-                            }
-                        },
-                        Continuity::C1 => {
-                            let dx = (v_end.x - next.x)/3.0;
-                            let dy = (v_end.y - next.y)/3.0;
-                            Point {
-                                x: v_end.x + dx,
-                                y: v_end.y + dy,
-                                role: PointRole::Control,
-                                continuity: cont,
-                            }
-                        }
-                        _ => *control2,
-                    };
-
-                    *control2 = new_control2;
+    /// Sprawdza cały wielokąt pod kątem sprzecznych ograniczeń i zwraca listę konfliktów
+    /// (pusta lista = da się je wszystkie spełnić).
+    pub fn check_constraints(&self) -> Vec<ConstraintConflict> {
+        let mut conflicts = Vec::new();
+        let n = self.vertices.len();
+        if n < 2 {
+            return conflicts;
+        }
+        let edges = self.edge_count();
+        let constraints: Vec<Option<ConstraintType>> = (0..edges).map(|i| self.get_constraint(i)).collect();
 
-                }
+        // dwie sasiednie krawedzie poziome/pionowe sklejaja wierzcholek
+        for i in 0..edges {
+            let Some(next) = self.outgoing_edge(self.edge_end(i)) else {
+                continue;
+            };
+            if next == i {
+                continue;
             }
+            let same_axis = matches!(
+                (&constraints[i], &constraints[next]),
+                (Some(ConstraintType::Horizontal), Some(ConstraintType::Horizontal))
+                    | (Some(ConstraintType::Vertical), Some(ConstraintType::Vertical))
+            );
+            if same_axis {
+                conflicts.push(ConstraintConflict { kind: ConflictKind::AdjacentParallel, edges: vec![i, next] });
+            }
+        }
 
-
-            _=> {}
+        // wszystkie krawedzie maja stala dlugosc - najdluzsza nie moze przekraczac sumy pozostalych
+        let lengths: Vec<f64> = constraints
+            .iter()
+            .filter_map(|c| match c {
+                Some(ConstraintType::FixedLength(len)) => Some(*len),
+                _ => None,
+            })
+            .collect();
+        if self.closed && lengths.len() == n {
+            let total: f64 = lengths.iter().sum();
+            let longest = lengths.iter().cloned().fold(0.0, f64::max);
+            if longest > total - longest + SOLVER_TOLERANCE as f64 {
+                conflicts.push(ConstraintConflict { kind: ConflictKind::LengthsCannotClose, edges: (0..n).collect() });
+            }
         }
-    }
 
-    // sprawdzanie czy obok edge nie ma juz takiego samego constraintu
-    pub fn is_constraint_legal(&self, edge_idx:usize, new_constraint: &ConstraintType) -> bool {
-        if !matches!(new_constraint, ConstraintType::Horizontal | ConstraintType::Vertical) {
-            return true;
+        if !conflicts.is_empty() {
+            return conflicts;
         }
 
-        let n= self.constraints.len();
-        if n == 0 {
-            return true;
+        // reszte wylapujemy probnym uruchomieniem solvera na kopii; samo wyczerpanie iteracji
+        // przy malej odchylce to jeszcze nie sprzecznosc
+        let mut trial = self.clone();
+        if !trial.solve_constraints(&[]).is_feasible() {
+            let edges: Vec<usize> = (0..edges)
+                .filter(|&i| trial.constraint_residual(i) >= SOLVER_TOLERANCE)
+                .collect();
+            conflicts.push(ConstraintConflict { kind: ConflictKind::Unsatisfiable, edges });
         }
 
-        let prev_idx = if edge_idx == 0 {n -1} else {edge_idx - 1};
-        let next_idx = (edge_idx + 1) % n;
-        let prev = &self.constraints[prev_idx];
-        let next = &self.constraints[next_idx];
+        conflicts
+    }
 
-        match  new_constraint {
-            ConstraintType::Horizontal => {
-                !matches!(prev, Some(ConstraintType::Horizontal)) &&
-                !matches!(next, Some(ConstraintType::Horizontal))
-            }
-            ConstraintType::Vertical => {
-                matches!(prev, Some(ConstraintType::Vertical)) &&
-                matches!(next, Some(ConstraintType::Vertical))
-            }
-            _=> true,
+    /// Jak `check_constraints`, ale dla wielokąta z podmienionym ograniczeniem krawędzi `edge_idx`.
+    pub fn check_constraint_change(&self, edge_idx: usize, new_constraint: Option<ConstraintType>) -> Vec<ConstraintConflict> {
+        let mut trial = self.clone();
+        trial.ensure_constraints_len();
+        if edge_idx < trial.constraints.len() {
+            trial.constraints[edge_idx] = new_constraint;
         }
+        trial.check_constraints()
     }
 
 
@@ -423,150 +827,787 @@ impl Polygon {
     }
 
     ///BEZIER
+    /// Po przesunięciu punktu kontrolnego `control_id` (liczonego od 1) poprawia ciągłość
+    /// w wierzchołkach, na które ten punkt wpływa. Przesunięty uchwyt zostaje na miejscu,
+    /// dopasowuje się druga strona wierzchołka.
     pub fn enforce_continuity_after_control_move(&mut self, constraint_index: usize, control_id: u8) {
-        if let Some(ConstraintType::Bezier {
-                        control1,
-                        control2,
-                        ..
-                    }) = &mut self.constraints[constraint_index]
-        {
-            let n = self.vertices.len();
-            let start_idx = constraint_index;
-            let end_idx = (constraint_index + 1) % n;
-
-            match control_id {
-                1 => {
-                    // Przesunięto control1 → modyfikujemy poprzedni wierzchołek (vertex[i - 1])
-                    let v_start = self.vertices[start_idx];
-                    let prev_idx = if start_idx == 0 { n - 1 } else { start_idx - 1 };
-                    let prev = self.vertices[prev_idx];
-                    let cont = v_start.continuity;
-
-                    match cont {
-                        Continuity::G1 => {
-                            // chcemy zachować długość poprzedniej krawędzi, tylko ustawić jej kierunek
-                            let dx = control1.x - v_start.x;
-                            let dy = control1.y - v_start.y;
-                            let norm = (dx * dx + dy * dy).sqrt().max(1e-6);
-                            let ux = dx / norm;
-                            let uy = dy / norm;
-
-                            // oryginalna długość krawędzi prev -> v_start
-                            let prev_len = prev.distance(&v_start).max(1e-6);
-
-                            // nowy poprzedni wierzchołek: v_start - unit * prev_len
-                            self.vertices[prev_idx].x = v_start.x - ux * prev_len;
-                            self.vertices[prev_idx].y = v_start.y - uy * prev_len;
-                            let constraint_opt = self.constraints.get(prev_idx).cloned();
-                        }
-                        Continuity::C1 => {
-                            let dx = control1.x - v_start.x;
-                            let dy = control1.y - v_start.y;
-                            // C1: skala 1:3 (kontrolka wpływa na położenie wierzchołka z mnożnikiem 3)
-                            self.vertices[prev_idx].x = v_start.x - dx * 3.0;
-                            self.vertices[prev_idx].y = v_start.y - dy * 3.0;
-                        }
-                        _ => {}
-                    }
+        let Some(count) = self.get_constraint(constraint_index).and_then(|c| c.bezier_controls()).map(|c| c.len()) else {
+            return;
+        };
+        let n = self.vertices.len();
+        let start_idx = constraint_index;
+        let end_idx = (constraint_index + 1) % n;
+        let k = control_id as usize;
+        let fixed = Some((constraint_index, control_id));
+
+        // przy G2 liczy sie tez drugi punkt kontrolny od wierzcholka (krzywizna)
+        let start_reach = if self.vertices[start_idx].continuity == Continuity::G2 { 2 } else { 1 };
+        let end_reach = if self.vertices[end_idx].continuity == Continuity::G2 { 2 } else { 1 };
+        if k <= start_reach {
+            self.enforce_vertex_continuity(start_idx, fixed);
+        }
+        if k + end_reach > count {
+            self.enforce_vertex_continuity(end_idx, fixed);
+        }
+    }
+
+    /// Wymusza ciągłość (G1/C1/G2) w wierzchołku `v_idx` względem faktycznego typu obu
+    /// sąsiednich krawędzi (Bezier, odcinek, łuk). Poprawiana jest strona będąca krzywą
+    /// Beziera, a gdy obie nią są - wychodząca. Jeśli `fixed` wskazuje przeciągany uchwyt
+    /// (krawędź, numer punktu), poprawiamy stronę przeciwną; odcinek jest wtedy obracany
+    /// wokół wierzchołka (tak jak wcześniej), a łuku nie ruszamy.
+    pub fn enforce_vertex_continuity(&mut self, v_idx: usize, fixed: Option<(usize, u8)>) {
+        let n = self.vertices.len();
+        if n < 2 {
+            return;
+        }
+        let cont = self.vertices[v_idx].continuity;
+        if !matches!(cont, Continuity::G1 | Continuity::C1 | Continuity::G2) {
+            return;
+        }
 
+        // koniec otwartej lamanej nie ma z czym trzymac ciaglosci
+        let (Some(in_edge), Some(out_edge)) = (self.incoming_edge(v_idx), self.outgoing_edge(v_idx)) else {
+            return;
+        };
+        let in_bezier = self.get_constraint(in_edge).is_some_and(|c| c.is_bezier());
+        let out_bezier = self.get_constraint(out_edge).is_some_and(|c| c.is_bezier());
+
+        let adjust_outgoing = match fixed {
+            Some((edge, _)) if edge == out_edge => false,
+            Some((edge, _)) if edge == in_edge => true,
+            _ => {
+                if !in_bezier && !out_bezier {
+                    return;
                 }
+                out_bezier
+            }
+        };
+        self.match_neighbour(v_idx, adjust_outgoing, cont, fixed.is_some(), &[]);
+    }
 
-                2 => {
-                    // Przesunięto control2 → modyfikujemy następny wierzchołek (vertex[i + 2])
-                    let v_end = self.vertices[end_idx];
-                    let next_idx = (end_idx + 1) % n;
-                    let next = self.vertices[next_idx];
-                    let cont = v_end.continuity;
-
-                    match cont {
-                        Continuity::G1 => {
-                            // zachowujemy długość krawędzi v_end -> next, tylko zmieniamy kierunek
-                            let dx = control2.x - v_end.x;
-                            let dy = control2.y - v_end.y;
-                            let norm = (dx * dx + dy * dy).sqrt().max(1e-6);
-                            let ux = dx / norm;
-                            let uy = dy / norm;
-
-                            // oryginalna długość krawędzi v_end -> next
-                            let next_len = next.distance(&v_end).max(1e-6);
-
-                            // nowy next: v_end + unit * next_len
-                            self.vertices[next_idx].x = v_end.x - ux * next_len;
-                            self.vertices[next_idx].y = v_end.y - uy * next_len;
-                        }
-                        Continuity::C1 => {
-                            let dx = control2.x - v_end.x;
-                            let dy = control2.y - v_end.y;
-                            self.vertices[next_idx].x = v_end.x - dx * 3.0;
-                            self.vertices[next_idx].y = v_end.y - dy * 3.0;
-                        }
-                        _ => {}
-                    }
+    /// Dopasowuje jedną stronę wierzchołka `v_idx` (wychodzącą, gdy `adjust_outgoing`) do
+    /// drugiej. Krzywa Beziera zmienia punkty kontrolne, odcinek (gdy `move_lines`) obraca
+    /// się wokół wierzchołka, łuk zostaje bez zmian. Wierzchołki z `pinned` się nie ruszają.
+    fn match_neighbour(&mut self, v_idx: usize, adjust_outgoing: bool, cont: Continuity, move_lines: bool, pinned: &[usize]) {
+        let n = self.vertices.len();
+        if n < 2 {
+            return;
+        }
+        let (Some(in_edge), Some(out_edge)) = (self.incoming_edge(v_idx), self.outgoing_edge(v_idx)) else {
+            return;
+        };
+        let (adjusted, reference) = if adjust_outgoing { (out_edge, in_edge) } else { (in_edge, out_edge) };
 
-                }
+        // pochodna i krzywizna drugiej strony w wierzcholku (w kierunku obchodzenia)
+        let Some((derivative, curvature)) = self.edge_end_geometry(reference, !adjust_outgoing) else {
+            return;
+        };
+        if derivative.length() < f32::EPSILON {
+            return;
+        }
 
-                _ => {}
+        if let Some(mut points) = self.bezier_points(adjusted) {
+            fit_bezier_end(&mut points, adjust_outgoing, derivative, curvature, cont);
+            if let Some(constraint) = self.constraints[adjusted].as_mut() {
+                for (control, fitted) in constraint.bezier_controls_mut().into_iter().zip(&points[1..points.len() - 1]) {
+                    *control = Point { role: PointRole::Control, ..*fitted };
+                }
             }
+            return;
+        }
+
+        if !move_lines || matches!(self.get_constraint(adjusted), Some(ConstraintType::Arc { .. })) {
+            return;
         }
+
+        // odcinek: obracamy go wokol wierzcholka, drugi koniec idzie za styczna
+        let vertex = self.vertices[v_idx];
+        let far_idx = if adjust_outgoing { (v_idx + 1) % n } else { in_edge };
+        if pinned.contains(&far_idx) {
+            return;
+        }
+        let far = self.vertices[far_idx];
+        let dir = derivative.normalized();
+        let sign = if adjust_outgoing { 1.0 } else { -1.0 };
+        let offset = match cont {
+            // pochodna odcinka to po prostu (koniec - poczatek)
+            Continuity::C1 => derivative * sign,
+            _ => dir * (sign * far.distance(&vertex).max(1e-6)),
+        };
+        self.vertices[far_idx].x = vertex.x + offset.x;
+        self.vertices[far_idx].y = vertex.y + offset.y;
+    }
+
+    /// Pochodna (względem parametru 0..1) i krzywizna ze znakiem krawędzi `edge_idx`
+    /// na jej początku (`at_start`) albo końcu.
+    pub fn edge_end_geometry(&self, edge_idx: usize, at_start: bool) -> Option<(Point, f32)> {
+        let n = self.vertices.len();
+        if edge_idx >= self.edge_count() {
+            return None;
+        }
+        let start = self.vertices[edge_idx % n];
+        let end = self.vertices[(edge_idx + 1) % n];
+
+        if let Some(points) = self.bezier_points(edge_idx) {
+            let degree = (points.len() - 1) as f32;
+            let last = points.len() - 1;
+            let (first_leg, second_leg) = if at_start {
+                (points[1] - points[0], points[2] - points[1])
+            } else {
+                (points[last - 1] - points[last - 2], points[last] - points[last - 1])
+            };
+            let tangent_leg = if at_start { first_leg } else { second_leg };
+            let d = tangent_leg.length();
+            let curvature = if d < f32::EPSILON {
+                0.0
+            } else {
+                (degree - 1.0) / degree * cross(first_leg, second_leg) / (d * d * d)
+            };
+            return Some((tangent_leg * degree, curvature));
+        }
+
+        if let Some(ConstraintType::Arc { .. }) = self.get_constraint(edge_idx) {
+            let (center, radius, _, sweep) = self.arc_geometry(edge_idx)?;
+            let p = if at_start { start } else { end };
+            // p(t) = c + r (cos, sin)(a0 + sweep t)  =>  p'(t) = sweep * rot90(p - c)
+            let curvature = if radius > f32::EPSILON { sweep.signum() / radius } else { 0.0 };
+            return Some(((p - center).rot90() * sweep, curvature));
+        }
+
+        Some((end - start, 0.0))
+    }
+
+    /// Kąt (w radianach) między stycznymi po obu stronach wierzchołka, przeskalowany
+    /// długością stycznej wchodzącej - residuum ograniczeń G1 łuków, w pikselach.
+    fn tangent_mismatch(&self, v_idx: usize) -> f32 {
+        let Some(in_edge) = self.incoming_edge(v_idx) else {
+            return 0.0;
+        };
+        let (Some((incoming, _)), Some((outgoing, _))) =
+            (self.edge_end_geometry(in_edge, false), self.edge_end_geometry(v_idx, true))
+        else {
+            return 0.0;
+        };
+        if incoming.length() < f32::EPSILON || outgoing.length() < f32::EPSILON {
+            return 0.0;
+        }
+        let angle = cross(incoming, outgoing).atan2(incoming.dot(outgoing)).abs();
+        angle * incoming.length().min(outgoing.length())
+    }
+
+    /// Środek, promień, kąt początkowy i skierowana rozwartość (dodatnia = zgodnie z ruchem
+    /// wskazówek zegara na ekranie) łuku na krawędzi. `None` dla zdegenerowanej cięciwy.
+    pub fn arc_geometry(&self, edge_idx: usize) -> Option<(Point, f32, f32, f32)> {
+        let n = self.vertices.len();
+        let Some(ConstraintType::Arc { radius, clockwise, large_arc, .. }) = self.get_constraint(edge_idx) else {
+            return None;
+        };
+        let start = self.vertices[edge_idx % n];
+        let end = self.vertices[(edge_idx + 1) % n];
+        let half_chord = start.distance(&end) / 2.0;
+
+        // maly luk zgodny z ruchem wskazowek ma srodek po stronie normalnej (-dy, dx)
+        let (center, radius) = Self::compute_arc_from_chord(start, end, Some(radius.max(half_chord)), clockwise != large_arc)?;
+        let start_angle = (start - center).y.atan2((start - center).x);
+        let end_angle = (end - center).y.atan2((end - center).x);
+        let tau = std::f32::consts::TAU;
+        let sweep = if clockwise {
+            (end_angle - start_angle).rem_euclid(tau)
+        } else {
+            -(start_angle - end_angle).rem_euclid(tau)
+        };
+        Some((center, radius, start_angle, sweep))
+    }
+
+    /// Punkt w połowie łuku - tam rysujemy uchwyt do jego przeciągania.
+    pub fn arc_midpoint(&self, edge_idx: usize) -> Option<Point> {
+        let (center, radius, start_angle, sweep) = self.arc_geometry(edge_idx)?;
+        let angle = start_angle + sweep / 2.0;
+        Some(Point::new(center.x + radius * angle.cos(), center.y + radius * angle.sin()))
+    }
+
+    /// Ustawia promień, kierunek i wielkość łuku tak, by przechodził przez `through`
+    /// (okrąg opisany na początku, `through` i końcu krawędzi).
+    pub fn set_arc_through(&mut self, edge_idx: usize, through: Point) -> bool {
+        let n = self.vertices.len();
+        let start = self.vertices[edge_idx % n];
+        let end = self.vertices[(edge_idx + 1) % n];
+
+        let a = start - through;
+        let b = end - through;
+        let denom = 2.0 * cross(a, b);
+        if denom.abs() < 1e-3 {
+            // punkty prawie wspolliniowe - taki luk nie istnieje
+            return false;
+        }
+        let a2 = a.dot(a);
+        let b2 = b.dot(b);
+        let offset = Point::new((b.y * a2 - a.y * b2) / denom, (a.x * b2 - b.x * a2) / denom);
+        let center = through + offset;
+        let mid = (start + end) * 0.5;
+
+        if let Some(Some(ConstraintType::Arc { radius, clockwise, large_arc, .. })) = self.constraints.get_mut(edge_idx) {
+            *radius = offset.length();
+            // start -> through -> end skreca w prawo na ekranie
+            *clockwise = cross(through - start, end - through) > 0.0;
+            *large_arc = (center - mid).dot(through - mid) > 0.0;
+            return true;
+        }
+        false
+    }
+
+    /// Cały wielobok kontrolny krawędzi-krzywej: początek, punkty kontrolne, koniec.
+    pub fn bezier_points(&self, edge_idx: usize) -> Option<Vec<Point>> {
+        let n = self.vertices.len();
+        let controls = self.get_constraint(edge_idx)?.bezier_controls()?;
+        let mut points = Vec::with_capacity(controls.len() + 2);
+        points.push(self.vertices[edge_idx % n]);
+        points.extend(controls);
+        points.push(self.vertices[(edge_idx + 1) % n]);
+        Some(points)
+    }
+
+    /// Podnosi stopień krzywej na krawędzi o 1 (zwykła krawędź staje się krzywą kwadratową).
+    pub fn elevate_bezier_degree(&mut self, edge_idx: usize) -> bool {
+        let n = self.vertices.len();
+        if edge_idx >= self.constraints.len() {
+            return false;
+        }
+        let points = match self.bezier_points(edge_idx) {
+            Some(points) => points,
+            None if matches!(self.constraints[edge_idx], None | Some(ConstraintType::Line)) => {
+                vec![self.vertices[edge_idx], self.vertices[(edge_idx + 1) % n]]
+            }
+            None => return false,
+        };
+        let elevated = elevate_degree(&points);
+        self.constraints[edge_idx] = ConstraintType::bezier_from_controls(elevated[1..elevated.len() - 1].to_vec());
+        true
+    }
+
+    /// Obniża stopień krzywej o 1 (krzywa kwadratowa staje się zwykłą krawędzią).
+    pub fn reduce_bezier_degree(&mut self, edge_idx: usize) -> bool {
+        let Some(reduced) = self.bezier_points(edge_idx).and_then(|points| reduce_degree(&points)) else {
+            return false;
+        };
+        self.constraints[edge_idx] = ConstraintType::bezier_from_controls(reduced[1..reduced.len() - 1].to_vec());
+        true
     }
 
+    /// Kontur jako łamana - łuki i krzywe Beziera są spłaszczane. Dla otwartej
+    /// łamanej ostatni punkt to jej koniec (bez powrotu do początku).
+    pub fn outline(&self) -> Vec<Point> {
+        self.outline_within(DEFAULT_FLATNESS)
+    }
 
-    /// Wymusza wszystkie ograniczenia oprócz jednej wskazanej krawędzi.
-    /// Użyteczne np. przy przesuwaniu punktu kontrolnego, aby nie „cofnąć” jego pozycji.
-    pub fn apply_constraints_except(&mut self, exclude_edge: Option<usize>) {
+    /// Jak `outline`, ale krzywe Beziera spłaszczone z tolerancją `flatness`.
+    pub fn outline_within(&self, flatness: f32) -> Vec<Point> {
         let n = self.vertices.len();
+        let mut points = Vec::new();
         if n < 2 {
-            return;
+            return points;
         }
 
         for i in 0..n {
-            // Pomijamy krawędź, której nie chcemy teraz wymuszać
-            if Some(i) == exclude_edge {
+            points.push(self.vertices[i]);
+            if i >= self.edge_count() {
+                continue; // koniec otwartej lamanej
+            }
+
+            if let Some(control_points) = self.bezier_points(i) {
+                let samples = flatten_bezier_curve(&control_points, flatness);
+                points.extend_from_slice(&samples[1..samples.len() - 1]);
                 continue;
             }
 
-            if let Some(constraint) = self.get_constraint(i) {
-                let start_idx = i;
-                let end_idx = (i + 1) % n;
-                self.enforce_constraint(start_idx, end_idx, &constraint);
+            if let Some((center, radius, start_angle, sweep)) = self.arc_geometry(i) {
+                let samples = sample_arc(center, radius, start_angle, start_angle + sweep, OUTLINE_ARC_STEPS);
+                points.extend_from_slice(&samples[1..samples.len() - 1]);
             }
         }
+        points
     }
 
+    /// Odległość punktu od krawędzi z uwzględnieniem jej prawdziwego kształtu: najbliższy
+    /// punkt krzywej Beziera, odległość od łuku albo od odcinka.
+    pub fn distance_to_edge(&self, edge_idx: usize, p: Point) -> f32 {
+        if edge_idx >= self.edge_count() {
+            return f32::INFINITY;
+        }
+        if let Some(points) = self.bezier_points(edge_idx) {
+            return p.distance(&closest_point_on_bezier(&points, p).1);
+        }
+        if let Some((center, radius, start_angle, sweep)) = self.arc_geometry(edge_idx) {
+            return distance_to_arc(p, center, radius, start_angle, sweep);
+        }
+        distance_to_segment(p, self.vertices[edge_idx], self.vertices[self.edge_end(edge_idx)])
+    }
+
+    /// Kontur zewnętrzny i dziury jako łamane. Dziury są odwrócone przeciwnie do konturu
+    /// zewnętrznego, więc wypełnienie omija je przy obu regułach (even-odd i non-zero).
+    pub fn outlines(&self) -> Vec<Vec<Point>> {
+        self.outlines_within(DEFAULT_FLATNESS)
+    }
+
+    /// Jak `outlines`, z tolerancją spłaszczania krzywych `flatness`.
+    pub fn outlines_within(&self, flatness: f32) -> Vec<Vec<Point>> {
+        let outer = self.outline_within(flatness);
+        let outer_sign = signed_area(&outer).signum();
+        let mut outlines = vec![outer];
+        for hole in &self.holes {
+            let mut ring = hole.outline_within(flatness);
+            if signed_area(&ring).signum() == outer_sign {
+                ring.reverse();
+            }
+            outlines.push(ring);
+        }
+        outlines
+    }
+
+    /// Punkt wewnątrz konturu zewnętrznego i poza wszystkimi dziurami.
     pub fn contains_point(&self, p: Point) -> bool {
+        self.ring_contains(p) && !self.holes.iter().any(|hole| hole.ring_contains(p))
+    }
+
+    // numer obiegu wzgledem konturu z regula wypelniania wielokata, jak przy rysowaniu;
+    // proste krawedzie liczymy wprost, splaszczamy tylko krzywe i luki (ich wybrzuszenia tez sie licza)
+    fn ring_contains(&self, p: Point) -> bool {
+        // otwarta lamana nie ma wnetrza
         let n = self.vertices.len();
-        if n < 3 {
+        if !self.closed || n < 2 {
             return false;
         }
 
-        let mut inside = false;
-        let mut j = n - 1;
+        let mut winding = 0;
+        let mut crossing = |a: Point, b: Point| {
+            if (a.y > p.y) != (b.y > p.y) && p.x < (b.x - a.x) * (p.y - a.y) / (b.y - a.y) + a.x {
+                winding += if b.y > a.y { 1 } else { -1 };
+            }
+        };
+        for i in 0..self.edge_count() {
+            if let Some(points) = self.bezier_points(i) {
+                flatten_bezier_curve(&points, DEFAULT_FLATNESS).windows(2).for_each(|w| crossing(w[0], w[1]));
+            } else if let Some((center, radius, start_angle, sweep)) = self.arc_geometry(i) {
+                sample_arc(center, radius, start_angle, start_angle + sweep, OUTLINE_ARC_STEPS)
+                    .windows(2)
+                    .for_each(|w| crossing(w[0], w[1]));
+            } else {
+                crossing(self.vertices[i], self.vertices[(i + 1) % n]);
+            }
+        }
+        self.fill_rule.contains(winding)
+    }
+
 
-        for i in 0..n {
-            let v_i = self.vertices[i];
-            let v_j = self.vertices[j];
 
-            let intersects_vertically = (v_i.y > p.y) != (v_j.y > p.y);
 
-            if intersects_vertically {
-                let x_intersect = (v_j.x - v_i.x) * (p.y - v_i.y) / (v_j.y - v_i.y) + v_i.x;
 
-                if p.x < x_intersect {
-                    inside = !inside;
-                }
-            }
 
-            j = i;
+}
+
+fn signed_area(ring: &[Point]) -> f32 {
+    let n = ring.len();
+    (0..n).map(|i| cross(ring[i], ring[(i + 1) % n])).sum::<f32>() / 2.0
+}
+
+fn cross(a: Point, b: Point) -> f32 {
+    a.x * b.y - a.y * b.x
+}
+
+// Dopasowuje poczatek (at_start) albo koniec wieloboku kontrolnego `points` do pochodnej
+// `derivative` i krzywizny `curvature` sasiedniego segmentu. Dla krzywej stopnia n:
+// pochodna na koncu to n * (P1 - P0), krzywizna (n - 1) / n * h / d^2, gdzie d = |P1 - P0|,
+// a h to odleglosc P2 od stycznej (ze znakiem).
+fn fit_bezier_end(points: &mut [Point], at_start: bool, derivative: Point, curvature: f32, cont: Continuity) {
+    let degree = points.len() - 1;
+    if degree < 2 {
+        return;
+    }
+    let n = degree as f32;
+    let dir = derivative.normalized();
+    let (v, c, c2) = if at_start { (0, 1, 2) } else { (degree, degree - 1, degree - 2) };
+    let vertex = points[v];
+    // uchwyt lezy po stronie krzywej: przed wierzcholkiem na koncu, za nim na poczatku
+    let handle_dir = if at_start { dir } else { dir * -1.0 };
+
+    points[c] = match cont {
+        Continuity::C1 => vertex + handle_dir * (derivative.length() / n),
+        _ => vertex + handle_dir * points[c].distance(&vertex),
+    };
+
+    if cont != Continuity::G2 {
+        return;
+    }
+    let normal = dir.rot90();
+    let factor = n / (n - 1.0);
+    if degree == 2 {
+        // kwadratowa: drugi punkt to drugi koniec krawedzi, wiec zmieniamy dlugosc uchwytu
+        let h = (points[c2] - vertex).dot(normal);
+        let d2 = h / (curvature * factor);
+        if curvature.abs() > f32::EPSILON && d2 > 0.0 {
+            points[c] = vertex + handle_dir * d2.sqrt();
         }
+    } else {
+        let d = points[c].distance(&vertex);
+        let along = (points[c2] - vertex).dot(dir);
+        let h = curvature * factor * d * d;
+        points[c2] = vertex + dir * along + normal * h;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        inside
+    fn square(x0: f32, y0: f32, x1: f32, y1: f32) -> Vec<Point> {
+        vec![Point::new(x0, y0), Point::new(x1, y0), Point::new(x1, y1), Point::new(x0, y1)]
     }
 
+    // czworokat lekko odksztalcony od prostokata, z ograniczeniami H, V, stala dlugosc, V
+    fn skewed_with_constraints() -> Polygon {
+        let mut polygon = Polygon::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(50.0, 3.0),
+            Point::new(52.0, 40.0),
+            Point::new(-2.0, 38.0),
+        ]);
+        polygon.constraints = vec![
+            Some(ConstraintType::Horizontal),
+            Some(ConstraintType::Vertical),
+            Some(ConstraintType::FixedLength(60.0)),
+            Some(ConstraintType::Vertical),
+        ];
+        polygon
+    }
 
+    #[test]
+    fn solver_converges_on_mixed_constraints() {
+        let mut polygon = skewed_with_constraints();
+        let result = polygon.solve_constraints(&[]);
+        assert!(matches!(result, SolveResult::Converged { .. }));
+        let v = &polygon.vertices;
+        assert!((v[0].y - v[1].y).abs() < SOLVER_TOLERANCE);
+        assert!((v[1].x - v[2].x).abs() < SOLVER_TOLERANCE);
+        assert!((v[3].x - v[0].x).abs() < SOLVER_TOLERANCE);
+        assert!((v[2].distance(&v[3]) - 60.0).abs() < SOLVER_TOLERANCE);
+        assert!(polygon.max_residual() < SOLVER_TOLERANCE);
+    }
 
+    #[test]
+    fn solver_keeps_pinned_vertex() {
+        let mut polygon = skewed_with_constraints();
+        let pinned = polygon.vertices[1];
+        assert!(matches!(polygon.solve_constraints(&[1]), SolveResult::Converged { .. }));
+        assert!(polygon.vertices[1] == pinned);
+        assert!(polygon.max_residual() < SOLVER_TOLERANCE);
+    }
 
+    #[test]
+    fn solver_reports_infeasible_lengths() {
+        // z bokow 10, 10 i 50 nie da sie zamknac trojkata
+        let mut polygon = Polygon::new(vec![Point::new(0.0, 0.0), Point::new(10.0, 0.0), Point::new(5.0, 8.0)]);
+        polygon.constraints = vec![
+            Some(ConstraintType::FixedLength(10.0)),
+            Some(ConstraintType::FixedLength(10.0)),
+            Some(ConstraintType::FixedLength(50.0)),
+        ];
+        assert!(matches!(polygon.solve_constraints(&[]), SolveResult::NotConverged { .. }));
+    }
 
+    #[test]
+    fn outlines_orient_holes_against_outer() {
+        let mut polygon = Polygon::new(square(0.0, 0.0, 10.0, 10.0));
+        // jedna dziura w tym samym kierunku co kontur zewnetrzny, druga w przeciwnym
+        polygon.add_hole(square(1.0, 1.0, 3.0, 3.0));
+        let mut reversed = square(5.0, 5.0, 8.0, 8.0);
+        reversed.reverse();
+        polygon.add_hole(reversed);
+
+        let outlines = polygon.outlines();
+        assert_eq!(outlines.len(), 3);
+        let outer = signed_area(&outlines[0]).signum();
+        assert!(outlines[1..].iter().all(|ring| signed_area(ring).signum() == -outer));
+    }
 
-}
+    #[test]
+    fn add_hole_makes_closed_contour() {
+        let mut polygon = Polygon::new(square(0.0, 0.0, 10.0, 10.0));
+        assert_eq!(polygon.contour_count(), 1);
+        polygon.add_hole(square(2.0, 2.0, 4.0, 4.0));
+        assert_eq!(polygon.contour_count(), 2);
+        let hole = polygon.contour(1);
+        assert!(hole.closed);
+        assert_eq!(hole.vertices.len(), 4);
+        assert_eq!(hole.constraints.len(), hole.edge_count());
+        assert_eq!(hole.vertex_constraints.len(), 4);
+    }
+
+    #[test]
+    fn contour_mut_indexes_outer_then_holes() {
+        let mut polygon = Polygon::new(square(0.0, 0.0, 10.0, 10.0));
+        polygon.add_hole(square(1.0, 1.0, 2.0, 2.0));
+        polygon.add_hole(square(5.0, 5.0, 6.0, 6.0));
+
+        polygon.contour_mut(0).vertices[0].x = -1.0;
+        polygon.contour_mut(2).vertices[0].x = 4.0;
+        assert_eq!(polygon.vertices[0].x, -1.0);
+        assert_eq!(polygon.holes[0].vertices[0].x, 1.0);
+        assert_eq!(polygon.holes[1].vertices[0].x, 4.0);
+    }
+
+    #[test]
+    fn relax_keeps_pinned_hole_vertex() {
+        let mut polygon = Polygon::new(square(0.0, 0.0, 10.0, 10.0));
+        polygon.add_hole(square(2.0, 2.0, 4.0, 4.0));
+        polygon.holes[0].constraints[0] = Some(ConstraintType::Horizontal);
+        // przeciagniety wierzcholek dziury psuje poziom krawedzi 0-1
+        polygon.holes[0].vertices[1] = Point::new(4.5, 3.0);
+
+        polygon.relax(&[], &[(1, 1)]);
+        let hole = &polygon.holes[0];
+        assert_eq!((hole.vertices[1].x, hole.vertices[1].y), (4.5, 3.0));
+        assert!((hole.vertices[0].y - 3.0).abs() < 1e-4);
+    }
 
+    #[test]
+    fn hit_test_follows_fill_rule() {
+        // pentagram: srodek jest obiegany dwa razy - dziura przy even-odd, wnetrze przy non-zero
+        let star: Vec<Point> = (0..5)
+            .map(|k| {
+                let angle = (k as f32 * 144.0 - 90.0).to_radians();
+                Point::new(50.0 + 40.0 * angle.cos(), 50.0 + 40.0 * angle.sin())
+            })
+            .collect();
+        let mut polygon = Polygon::new(star);
+        let center = Point::new(50.0, 50.0);
+        let tip = Point::new(50.0, 15.0);
+        assert!(!polygon.contains_point(center));
+        assert!(polygon.contains_point(tip));
+        polygon.fill_rule = FillRule::NonZero;
+        assert!(polygon.contains_point(center));
+        assert!(polygon.contains_point(tip));
+        assert!(!polygon.contains_point(Point::new(0.0, 0.0)));
+    }
+
+    #[test]
+    fn slow_but_feasible_lengths_are_not_a_conflict() {
+        // 48-kat foremny z dlugosciami bokow wiekszymi o 5%: wykonalne, ale solver nie konczy w limicie iteracji
+        let n = 48;
+        let ring: Vec<Point> = (0..n)
+            .map(|k| {
+                let angle = k as f32 / n as f32 * std::f32::consts::TAU;
+                Point::new(100.0 * angle.cos(), 100.0 * angle.sin())
+            })
+            .collect();
+        let mut polygon = Polygon::new(ring);
+        polygon.ensure_constraints_len();
+        for i in 0..n {
+            let length = polygon.vertices[i].distance(&polygon.vertices[(i + 1) % n]) as f64;
+            polygon.constraints[i] = Some(ConstraintType::FixedLength(length * 1.05));
+        }
+        let result = polygon.clone().solve_constraints(&[]);
+        assert!(matches!(result, SolveResult::NotConverged { .. }));
+        assert!(result.is_feasible());
+        assert!(polygon.check_constraints().is_empty());
+    }
 
+    #[test]
+    fn unsatisfiable_set_is_still_rejected() {
+        // oba konce krawedzi przypiete 100 px od siebie, a krawedz ma miec dlugosc 10 -
+        // tego nie wylapuja wstepne testy, dopiero solver
+        let mut polygon = Polygon::new(vec![Point::new(0.0, 0.0), Point::new(100.0, 0.0), Point::new(50.0, 50.0)]);
+        polygon.constraints = vec![Some(ConstraintType::FixedLength(10.0)), None, None];
+        polygon.vertex_constraints = vec![
+            Some(VertexConstraint::Pinned { x: 0.0, y: 0.0 }),
+            Some(VertexConstraint::Pinned { x: 100.0, y: 0.0 }),
+            None,
+        ];
+        let result = polygon.clone().solve_constraints(&[]);
+        assert!(matches!(result, SolveResult::NotConverged { residual } if residual >= SOLVER_FEASIBLE_RESIDUAL));
+        assert!(!result.is_feasible());
+        let conflicts = polygon.check_constraints();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].kind, ConflictKind::Unsatisfiable);
+    }
+
+    #[test]
+    fn bezier_degree_steps_on_an_edge() {
+        let mut polygon = Polygon::new(square(0.0, 0.0, 30.0, 30.0));
+        polygon.ensure_constraints_len();
+        // zwykla krawedz -> kwadratowa -> szescienna i z powrotem
+        assert!(polygon.elevate_bezier_degree(0));
+        assert!(matches!(polygon.constraints[0], Some(ConstraintType::QuadraticBezier { .. })));
+        assert!(polygon.elevate_bezier_degree(0));
+        let cubic = polygon.bezier_points(0).unwrap();
+        assert_eq!(cubic.len(), 4);
+        assert!((cubic[1].x - 10.0).abs() < 1e-4 && (cubic[2].x - 20.0).abs() < 1e-4);
+        assert!(polygon.reduce_bezier_degree(0));
+        assert!(polygon.reduce_bezier_degree(0));
+        assert!(polygon.constraints[0].is_none());
+
+        // ponizej odcinka juz nie schodzimy, a luku nie podnosimy
+        assert!(!polygon.reduce_bezier_degree(0));
+        polygon.constraints[1] = Some(ConstraintType::Arc { g1_start: false, g1_end: false, radius: 0.0, clockwise: true, large_arc: false });
+        assert!(!polygon.elevate_bezier_degree(1));
+        assert!(!polygon.reduce_bezier_degree(1));
+    }
+
+    // otwarta lamana z krzywa szescienna na pierwszej krawedzi i `second` na drugiej;
+    // wierzcholek 1 ma ciaglosc `cont`, obie strony startuja z wyraznym zalamaniem
+    fn joint(cont: Continuity, second: Option<ConstraintType>) -> Polygon {
+        let mut polygon = Polygon::new_open(vec![Point::new(0.0, 0.0), Point::new(30.0, 0.0), Point::new(60.0, 10.0)]);
+        polygon.constraints[0] = ConstraintType::bezier_from_controls(vec![Point::new(10.0, 10.0), Point::new(20.0, 10.0)]);
+        polygon.constraints[1] = second;
+        polygon.vertices[1].continuity = cont;
+        polygon
+    }
+
+    fn joint_geometry(polygon: &Polygon) -> ((Point, f32), (Point, f32)) {
+        (polygon.edge_end_geometry(0, false).unwrap(), polygon.edge_end_geometry(1, true).unwrap())
+    }
+
+    fn assert_tangent(incoming: Point, outgoing: Point) {
+        let (a, b) = (incoming.normalized(), outgoing.normalized());
+        assert!(cross(a, b).abs() < 1e-4 && a.dot(b) > 0.0);
+    }
+
+    #[test]
+    fn continuity_between_two_curves() {
+        let second = ConstraintType::bezier_from_controls(vec![Point::new(40.0, -5.0), Point::new(50.0, 20.0)]);
+        for cont in [Continuity::G1, Continuity::C1, Continuity::G2] {
+            let mut polygon = joint(cont, second.clone());
+            // przeciagamy punkt kontrolny pierwszej krzywej - dopasowuje sie druga
+            polygon.enforce_vertex_continuity(1, Some((0, 1)));
+            let ((d_in, k_in), (d_out, k_out)) = joint_geometry(&polygon);
+            assert_tangent(d_in, d_out);
+            if cont == Continuity::C1 {
+                assert!(d_in.distance(&d_out) < 1e-3);
+            }
+            if cont == Continuity::G2 {
+                assert!(k_in.abs() > 1e-3 && (k_in - k_out).abs() < 1e-4);
+            }
+            // pierwsza krzywa zostala nietknieta
+            assert!(polygon.bezier_points(0).unwrap()[2].distance(&Point::new(20.0, 10.0)) == 0.0);
+        }
+    }
+
+    #[test]
+    fn continuity_between_curve_and_line() {
+        for cont in [Continuity::G1, Continuity::C1, Continuity::G2] {
+            let mut polygon = joint(cont, None);
+            // odcinek zostaje, dopasowuje sie krzywa
+            polygon.enforce_vertex_continuity(1, None);
+            let ((d_in, k_in), (d_out, k_out)) = joint_geometry(&polygon);
+            assert_eq!(k_out, 0.0);
+            assert_tangent(d_in, d_out);
+            if cont == Continuity::C1 {
+                assert!(d_in.distance(&d_out) < 1e-3);
+            }
+            if cont == Continuity::G2 {
+                assert!(k_in.abs() < 1e-4);
+            }
+            assert!(polygon.vertices[2] == Point::new(60.0, 10.0));
+        }
+    }
+
+    fn arc_edge(radius: f32, clockwise: bool, large_arc: bool) -> Polygon {
+        let mut polygon = Polygon::new_open(vec![Point::new(0.0, 0.0), Point::new(10.0, 0.0)]);
+        polygon.constraints[0] = Some(ConstraintType::Arc { g1_start: false, g1_end: false, radius, clockwise, large_arc });
+        polygon
+    }
+
+    #[test]
+    fn arc_geometry_for_each_direction_and_size() {
+        let h = 75.0_f32.sqrt();
+        // (clockwise, large_arc, y srodka, y punktu w polowie luku); y rosnie w dol ekranu
+        for (clockwise, large_arc, center_y, mid_y) in [
+            (true, false, h, h - 10.0),
+            (false, false, -h, 10.0 - h),
+            (true, true, -h, -h - 10.0),
+            (false, true, h, h + 10.0),
+        ] {
+            let polygon = arc_edge(10.0, clockwise, large_arc);
+            let (center, radius, _, sweep) = polygon.arc_geometry(0).unwrap();
+            assert_eq!(radius, 10.0);
+            assert!((center.x - 5.0).abs() < 1e-4 && (center.y - center_y).abs() < 1e-4);
+            assert_eq!(sweep > 0.0, clockwise);
+            assert_eq!(sweep.abs() > std::f32::consts::PI, large_arc);
+            let mid = polygon.arc_midpoint(0).unwrap();
+            assert!((mid.x - 5.0).abs() < 1e-3 && (mid.y - mid_y).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn arc_radius_below_half_chord_is_a_semicircle() {
+        let (center, radius, _, sweep) = arc_edge(2.0, true, false).arc_geometry(0).unwrap();
+        assert_eq!(radius, 5.0);
+        assert!(center.distance(&Point::new(5.0, 0.0)) < 1e-4);
+        assert!((sweep.abs() - std::f32::consts::PI).abs() < 1e-4);
+    }
+
+    #[test]
+    fn set_arc_through_reproduces_dragged_point() {
+        for (clockwise, large_arc) in [(true, false), (false, false), (true, true), (false, true)] {
+            let mid = arc_edge(10.0, clockwise, large_arc).arc_midpoint(0).unwrap();
+            // startujemy z innego luku i przeciagamy uchwyt w to samo miejsce
+            let mut polygon = arc_edge(0.0, !clockwise, !large_arc);
+            assert!(polygon.set_arc_through(0, mid));
+            assert!(matches!(
+                polygon.constraints[0],
+                Some(ConstraintType::Arc { radius, clockwise: c, large_arc: l, .. })
+                    if (radius - 10.0).abs() < 1e-3 && c == clockwise && l == large_arc
+            ));
+            assert!(polygon.arc_midpoint(0).unwrap().distance(&mid) < 1e-3);
+        }
+        // punkt na cieciwie nie wyznacza luku
+        assert!(!arc_edge(10.0, true, false).set_arc_through(0, Point::new(5.0, 0.0)));
+    }
+
+    #[test]
+    fn open_and_close_keep_edges_and_constraints_in_step() {
+        let mut polygon = Polygon::new(square(0.0, 0.0, 10.0, 10.0));
+        polygon.constraints = vec![
+            Some(ConstraintType::Horizontal),
+            Some(ConstraintType::Vertical),
+            Some(ConstraintType::FixedLength(10.0)),
+            Some(ConstraintType::Vertical),
+        ];
+        assert_eq!(polygon.edge_count(), 4);
+
+        // rozciecie krawedzi 1 (z (10,0) do (10,10)): lamana zaczyna sie w (10,10), konczy w (10,0)
+        polygon.open_at(1);
+        assert!(!polygon.closed);
+        assert_eq!(polygon.edge_count(), 3);
+        assert_eq!(polygon.constraints.len(), 3);
+        let coords: Vec<(f32, f32)> = polygon.vertices.iter().map(|v| (v.x, v.y)).collect();
+        assert_eq!(coords, vec![(10.0, 10.0), (0.0, 10.0), (0.0, 0.0), (10.0, 0.0)]);
+        assert!(matches!(polygon.constraints[0], Some(ConstraintType::FixedLength(_))));
+        assert!(matches!(polygon.constraints[1], Some(ConstraintType::Vertical)));
+        assert!(matches!(polygon.constraints[2], Some(ConstraintType::Horizontal)));
+        assert_eq!(polygon.incoming_edge(0), None);
+        assert_eq!(polygon.outgoing_edge(3), None);
+        assert_eq!(polygon.outgoing_edge(2), Some(2));
+
+        // ponowne rozciecie otwartej lamanej nic nie robi
+        polygon.open_at(0);
+        assert_eq!(polygon.edge_count(), 3);
+
+        polygon.close();
+        assert!(polygon.closed);
+        assert_eq!(polygon.edge_count(), 4);
+        assert_eq!(polygon.constraints.len(), 4);
+        assert!(polygon.constraints[3].is_none());
+        assert_eq!(polygon.incoming_edge(0), Some(3));
+        assert_eq!(polygon.outgoing_edge(3), Some(3));
+    }
+
+    #[test]
+    fn short_polyline_does_not_close() {
+        let mut polygon = Polygon::new_open(vec![Point::new(0.0, 0.0), Point::new(10.0, 0.0)]);
+        assert_eq!(polygon.edge_count(), 1);
+        polygon.close();
+        assert!(!polygon.closed);
+        assert_eq!(polygon.edge_count(), 1);
+    }
+}
@@ -0,0 +1,566 @@
+use serde::{Deserialize, Serialize};
+use super::point::Point;
+use super::polygon::{Polygon, SolveResult, SOLVER_MAX_ITERATIONS, SOLVER_TOLERANCE};
+use super::vertex_constraint::{coincident_residual, project_coincident};
+
+/// Krawędź `edge` wielokąta `polygon` (indeksy w scenie).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EdgeRef {
+    pub polygon: usize,
+    pub edge: usize,
+}
+
+/// Wierzchołek `vertex` wielokąta `polygon` (indeksy w scenie).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VertexRef {
+    pub polygon: usize,
+    pub vertex: usize,
+}
+
+/// Wierzchołek `vertex` dziury `contour` (numeracja jak w `Polygon::contour`, od 1) wielokąta `polygon`.
+/// Relacje łączą tylko kontury zewnętrzne, więc dziury potrzebujemy jedynie przy przypinaniu.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HoleVertexRef {
+    pub polygon: usize,
+    pub contour: usize,
+    pub vertex: usize,
+}
+
+/// Rodzaj relacji dwóch krawędzi - wybierany w menu, zanim użytkownik wskaże drugą krawędź.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RelationKind {
+    Parallel,
+    Perpendicular,
+    EqualLength,
+}
+
+/// Ograniczenie obejmujące więcej niż jedną krawędź, także z różnych wielokątów.
+/// Krzywe i łuki traktujemy tu jak ich cięciwy.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Relation {
+    Parallel(EdgeRef, EdgeRef),
+    Perpendicular(EdgeRef, EdgeRef),
+    EqualLength(EdgeRef, EdgeRef),
+    /// kąt wewnętrzny wielokąta w wierzchołku, w stopniach
+    FixedAngle { at: VertexRef, degrees: f32 },
+}
+
+impl Relation {
+    pub fn between(kind: RelationKind, a: EdgeRef, b: EdgeRef) -> Relation {
+        match kind {
+            RelationKind::Parallel => Relation::Parallel(a, b),
+            RelationKind::Perpendicular => Relation::Perpendicular(a, b),
+            RelationKind::EqualLength => Relation::EqualLength(a, b),
+        }
+    }
+
+    pub fn edges(&self) -> Vec<EdgeRef> {
+        match *self {
+            Relation::Parallel(a, b) | Relation::Perpendicular(a, b) | Relation::EqualLength(a, b) => vec![a, b],
+            Relation::FixedAngle { .. } => Vec::new(),
+        }
+    }
+
+    pub fn involves_edge(&self, edge: EdgeRef) -> bool {
+        self.edges().contains(&edge)
+    }
+
+    /// Krótki znaczek rysowany przy krawędziach (albo przy wierzchołku dla kąta).
+    pub fn glyph(&self) -> String {
+        match self {
+            Relation::Parallel(..) => "||".to_string(),
+            Relation::Perpendicular(..) => "|_".to_string(),
+            Relation::EqualLength(..) => "=".to_string(),
+            Relation::FixedAngle { degrees, .. } => format!("{:.0}°", degrees),
+        }
+    }
+
+    /// Czy wszystkie indeksy wskazują na istniejące wielokąty, krawędzie i wierzchołki.
+    pub fn is_valid(&self, polygons: &[Polygon]) -> bool {
+        match *self {
+            // kat istnieje tylko tam, gdzie wierzcholek ma obie krawedzie
+            Relation::FixedAngle { at, .. } => polygons.get(at.polygon).is_some_and(|p| {
+                p.vertices.len() >= 3 && p.incoming_edge(at.vertex).is_some() && p.outgoing_edge(at.vertex).is_some()
+            }),
+            _ => self
+                .edges()
+                .iter()
+                .all(|e| polygons.get(e.polygon).is_some_and(|p| e.edge < p.edge_count())),
+        }
+    }
+
+    /// Odchyłka od relacji, w przybliżeniu w pikselach.
+    pub fn residual(&self, polygons: &[Polygon]) -> f32 {
+        match *self {
+            Relation::Parallel(a, b) | Relation::Perpendicular(a, b) => {
+                let perpendicular = matches!(self, Relation::Perpendicular(..));
+                let (Some((a0, a1)), Some((b0, b1))) = (edge_points(polygons, a), edge_points(polygons, b)) else {
+                    return 0.0;
+                };
+                let error = direction_error(a1 - a0, b1 - b0, perpendicular);
+                error.abs() * (a1 - a0).length().min((b1 - b0).length())
+            }
+            Relation::EqualLength(a, b) => {
+                let (Some((a0, a1)), Some((b0, b1))) = (edge_points(polygons, a), edge_points(polygons, b)) else {
+                    return 0.0;
+                };
+                ((a1 - a0).length() - (b1 - b0).length()).abs()
+            }
+            Relation::FixedAngle { at, degrees } => {
+                let Some((prev, vertex, next, orientation)) = angle_points(polygons, at) else {
+                    return 0.0;
+                };
+                let error = wrap_pi(degrees.to_radians() - interior_angle(prev - vertex, next - vertex, orientation));
+                error.abs() * (prev - vertex).length().min((next - vertex).length())
+            }
+        }
+    }
+
+    /// Jeden krok relaksacji dla tej relacji. Wierzchołki z `pinned` się nie ruszają.
+    pub fn project(&self, polygons: &mut [Polygon], pinned: &[VertexRef]) {
+        if !self.is_valid(polygons) {
+            return;
+        }
+        match *self {
+            Relation::Parallel(a, b) | Relation::Perpendicular(a, b) => {
+                let perpendicular = matches!(self, Relation::Perpendicular(..));
+                let (Some((a0, a1)), Some((b0, b1))) = (edge_points(polygons, a), edge_points(polygons, b)) else {
+                    return;
+                };
+                let error = direction_error(a1 - a0, b1 - b0, perpendicular);
+                // obracamy obie krawedzie po polowie; przypieta krawedz zostawia calosc drugiej
+                let (turn_a, turn_b) = split_between(error, can_move_edge(polygons, a, pinned), can_move_edge(polygons, b, pinned));
+                transform_edge(polygons, a, pinned, turn_a, 1.0);
+                transform_edge(polygons, b, pinned, -turn_b, 1.0);
+            }
+            Relation::EqualLength(a, b) => {
+                let (Some((a0, a1)), Some((b0, b1))) = (edge_points(polygons, a), edge_points(polygons, b)) else {
+                    return;
+                };
+                let len_a = (a1 - a0).length();
+                let len_b = (b1 - b0).length();
+                if len_a < f32::EPSILON || len_b < f32::EPSILON {
+                    return;
+                }
+                let (grow_a, grow_b) = split_between(len_b - len_a, can_move_edge(polygons, a, pinned), can_move_edge(polygons, b, pinned));
+                transform_edge(polygons, a, pinned, 0.0, (len_a + grow_a) / len_a);
+                transform_edge(polygons, b, pinned, 0.0, (len_b - grow_b) / len_b);
+            }
+            Relation::FixedAngle { at, degrees } => {
+                let Some((prev, vertex, next, orientation)) = angle_points(polygons, at) else {
+                    return;
+                };
+                let error = wrap_pi(degrees.to_radians() - interior_angle(prev - vertex, next - vertex, orientation));
+                let n = polygons[at.polygon].vertices.len();
+                let prev_idx = (at.vertex + n - 1) % n;
+                let next_idx = (at.vertex + 1) % n;
+                let prev_free = !pinned.contains(&VertexRef { polygon: at.polygon, vertex: prev_idx });
+                let next_free = !pinned.contains(&VertexRef { polygon: at.polygon, vertex: next_idx });
+                // kat rosnie, gdy ramie do poprzedniego obraca sie w strone orientacji, a drugie odwrotnie
+                let (turn_prev, turn_next) = split_between(error, prev_free, next_free);
+                let polygon = &mut polygons[at.polygon];
+                polygon.vertices[prev_idx] = rotate_about(prev, vertex, orientation * turn_prev);
+                polygon.vertices[next_idx] = rotate_about(next, vertex, -orientation * turn_next);
+            }
+        }
+    }
+}
+
+/// Relaksacja całej sceny: ograniczenia krawędzi w każdym wielokącie na przemian z relacjami
+/// między krawędziami, aż największe residuum spadnie poniżej `SOLVER_TOLERANCE`.
+/// Wierzchołki z `pinned` i `hole_pinned` (np. przeciągane) nie są przesuwane.
+pub fn solve_scene(
+    polygons: &mut [Polygon],
+    relations: &[Relation],
+    pinned: &[VertexRef],
+    hole_pinned: &[HoleVertexRef],
+) -> SolveResult {
+    // przypiete wierzcholki (VertexConstraint::Pinned) trzymaja sie tak samo jak przeciagany
+    let mut pinned = pinned.to_vec();
+    for (p_idx, polygon) in polygons.iter().enumerate() {
+        pinned.extend((0..polygon.vertices.len()).filter(|&v| polygon.is_vertex_pinned(v)).map(|vertex| VertexRef { polygon: p_idx, vertex }));
+    }
+    let pinned = pinned.as_slice();
+
+    let mut result = SolveResult::Converged { iterations: 0 };
+    for iteration in 0..=SOLVER_MAX_ITERATIONS {
+        let residual = scene_residual(polygons, relations);
+        if residual < SOLVER_TOLERANCE {
+            result = SolveResult::Converged { iterations: iteration };
+            break;
+        }
+        if iteration == SOLVER_MAX_ITERATIONS {
+            result = SolveResult::NotConverged { residual };
+            break;
+        }
+
+        for (idx, polygon) in polygons.iter_mut().enumerate() {
+            let local: Vec<usize> = pinned.iter().filter(|v| v.polygon == idx).map(|v| v.vertex).collect();
+            let holes: Vec<(usize, usize)> =
+                hole_pinned.iter().filter(|v| v.polygon == idx).map(|v| (v.contour, v.vertex)).collect();
+            polygon.relax(&local, &holes);
+        }
+        for relation in relations {
+            relation.project(polygons, pinned);
+        }
+        project_coincident(polygons, pinned);
+    }
+
+    for polygon in polygons.iter_mut() {
+        polygon.fit_curves();
+    }
+    result
+}
+
+pub fn scene_residual(polygons: &[Polygon], relations: &[Relation]) -> f32 {
+    let polygon_residual = polygons
+        .iter()
+        .map(|p| p.max_residual())
+        .fold(coincident_residual(polygons), f32::max);
+    relations
+        .iter()
+        .map(|r| r.residual(polygons))
+        .fold(polygon_residual, f32::max)
+}
+
+/// Sprawdza, czy nową relację da się dodać: próbne rozwiązanie sceny na kopii.
+pub fn check_relation(polygons: &[Polygon], relations: &[Relation], candidate: &Relation) -> Result<(), String> {
+    if let [a, b] = candidate.edges()[..]
+        && a == b
+    {
+        return Err("Krawedz nie moze byc w relacji sama ze soba".to_string());
+    }
+    if !candidate.is_valid(polygons) {
+        return Err("Relacja wskazuje nieistniejaca krawedz".to_string());
+    }
+    let mut trial = polygons.to_vec();
+    let mut all = relations.to_vec();
+    all.push(*candidate);
+    if solve_scene(&mut trial, &all, &[], &[]).is_feasible() {
+        Ok(())
+    } else {
+        Err("Nie da sie jednoczesnie spelnic tej relacji i pozostalych ograniczen".to_string())
+    }
+}
+
+/// Obecny kąt wewnętrzny w wierzchołku (w stopniach) - wartość startowa dla `FixedAngle`.
+pub fn current_angle(polygons: &[Polygon], at: VertexRef) -> Option<f32> {
+    let (prev, vertex, next, orientation) = angle_points(polygons, at)?;
+    Some(interior_angle(prev - vertex, next - vertex, orientation).to_degrees())
+}
+
+// --- utrzymanie indeksow po zmianach topologii ---
+
+/// Usunięto wielokąt `polygon`: jego relacje znikają, dalsze indeksy przesuwamy.
+pub fn on_polygon_removed(relations: &mut Vec<Relation>, polygon: usize) {
+    relations.retain(|r| !involves_polygon(r, polygon));
+    for relation in relations.iter_mut() {
+        remap(relation, |p, i| Some((if p > polygon { p - 1 } else { p }, i)), |p, i| Some((if p > polygon { p - 1 } else { p }, i)));
+    }
+}
+
+/// Krawędź `edge` wielokąta `polygon` podzielono nowym wierzchołkiem (o indeksie `edge + 1`).
+pub fn on_vertex_inserted(relations: &mut Vec<Relation>, polygon: usize, edge: usize) {
+    relations.retain(|r| !r.involves_edge(EdgeRef { polygon, edge }));
+    let shift = |p: usize, i: usize| Some((p, if p == polygon && i > edge { i + 1 } else { i }));
+    for relation in relations.iter_mut() {
+        remap(relation, shift, shift);
+    }
+}
+
+/// Usunięto wierzchołek `vertex` z wielokąta, który miał `old_len` wierzchołków - dwie
+/// sąsiednie krawędzie zlały się w jedną, więc ich relacje znikają.
+pub fn on_vertex_removed(relations: &mut Vec<Relation>, polygon: usize, vertex: usize, old_len: usize) {
+    let merged_prev = (vertex + old_len - 1) % old_len;
+    let mut kept = Vec::with_capacity(relations.len());
+    for mut relation in relations.drain(..) {
+        let ok = remap(
+            &mut relation,
+            |p, e| {
+                if p != polygon {
+                    Some((p, e))
+                } else if e == vertex || e == merged_prev {
+                    None
+                } else {
+                    Some((p, if e > vertex { e - 1 } else { e }))
+                }
+            },
+            |p, v| {
+                if p != polygon {
+                    Some((p, v))
+                } else if v == vertex {
+                    None
+                } else {
+                    Some((p, if v > vertex { v - 1 } else { v }))
+                }
+            },
+        );
+        if ok {
+            kept.push(relation);
+        }
+    }
+    *relations = kept;
+}
+
+/// Zamknięty wielokąt rozcięto w krawędzi `edge` (`Polygon::open_at`): ta krawędź znika,
+/// indeksy przesuwają się tak, że wierzchołek `edge + 1` ma teraz numer 0.
+pub fn on_chain_opened(relations: &mut Vec<Relation>, polygon: usize, edge: usize, len: usize) {
+    let shift = (edge + 1) % len;
+    let rotate = |i: usize| (i + len - shift) % len;
+    let mut kept = Vec::with_capacity(relations.len());
+    for mut relation in relations.drain(..) {
+        let ok = remap(
+            &mut relation,
+            |p, e| {
+                if p != polygon {
+                    Some((p, e))
+                } else if e == edge {
+                    None
+                } else {
+                    Some((p, rotate(e)))
+                }
+            },
+            |p, v| {
+                if p != polygon {
+                    return Some((p, v));
+                }
+                // nowe konce lamanej nie maja juz kata
+                let v = rotate(v);
+                (v != 0 && v != len - 1).then_some((p, v))
+            },
+        );
+        if ok {
+            kept.push(relation);
+        }
+    }
+    *relations = kept;
+}
+
+fn involves_polygon(relation: &Relation, polygon: usize) -> bool {
+    match relation {
+        Relation::FixedAngle { at, .. } => at.polygon == polygon,
+        _ => relation.edges().iter().any(|e| e.polygon == polygon),
+    }
+}
+
+// przepisuje indeksy krawedzi i wierzcholkow; false, gdy ktorys przestal istniec
+fn remap(
+    relation: &mut Relation,
+    edge_map: impl Fn(usize, usize) -> Option<(usize, usize)>,
+    vertex_map: impl Fn(usize, usize) -> Option<(usize, usize)>,
+) -> bool {
+    let map_edge = |e: &mut EdgeRef| -> bool {
+        match edge_map(e.polygon, e.edge) {
+            Some((polygon, edge)) => {
+                *e = EdgeRef { polygon, edge };
+                true
+            }
+            None => false,
+        }
+    };
+    match relation {
+        Relation::Parallel(a, b) | Relation::Perpendicular(a, b) | Relation::EqualLength(a, b) => {
+            let ok_a = map_edge(a);
+            let ok_b = map_edge(b);
+            ok_a && ok_b
+        }
+        Relation::FixedAngle { at, .. } => match vertex_map(at.polygon, at.vertex) {
+            Some((polygon, vertex)) => {
+                *at = VertexRef { polygon, vertex };
+                true
+            }
+            None => false,
+        },
+    }
+}
+
+// --- geometria ---
+
+fn edge_points(polygons: &[Polygon], e: EdgeRef) -> Option<(Point, Point)> {
+    let polygon = polygons.get(e.polygon)?;
+    let n = polygon.vertices.len();
+    if e.edge >= polygon.edge_count() {
+        return None;
+    }
+    Some((polygon.vertices[e.edge], polygon.vertices[(e.edge + 1) % n]))
+}
+
+// (poprzedni, wierzcholek, nastepny, orientacja wielokata: +1 albo -1)
+fn angle_points(polygons: &[Polygon], at: VertexRef) -> Option<(Point, Point, Point, f32)> {
+    let polygon = polygons.get(at.polygon)?;
+    let n = polygon.vertices.len();
+    if n < 3 || polygon.incoming_edge(at.vertex).is_none() || polygon.outgoing_edge(at.vertex).is_none() {
+        return None;
+    }
+    let area: f32 = (0..n)
+        .map(|i| cross(polygon.vertices[i], polygon.vertices[(i + 1) % n]))
+        .sum();
+    let orientation = if area >= 0.0 { 1.0 } else { -1.0 };
+    Some((
+        polygon.vertices[(at.vertex + n - 1) % n],
+        polygon.vertices[at.vertex],
+        polygon.vertices[(at.vertex + 1) % n],
+        orientation,
+    ))
+}
+
+// kat wewnetrzny miedzy ramionami do poprzedniego (`to_prev`) i nastepnego (`to_next`) wierzcholka
+fn interior_angle(to_prev: Point, to_next: Point, orientation: f32) -> f32 {
+    let tau = std::f32::consts::TAU;
+    (orientation * cross(to_next, to_prev)).atan2(to_next.dot(to_prev)).rem_euclid(tau)
+}
+
+// o ile obrocic `a`, zeby byla rownolegla (prostopadla) do `b`, w zakresie (-pi/2, pi/2]
+fn direction_error(a: Point, b: Point, perpendicular: bool) -> f32 {
+    let target = if perpendicular { std::f32::consts::FRAC_PI_2 } else { 0.0 };
+    let diff = b.y.atan2(b.x) - a.y.atan2(a.x) - target;
+    let pi = std::f32::consts::PI;
+    diff - pi * (diff / pi).round()
+}
+
+fn wrap_pi(angle: f32) -> f32 {
+    let tau = std::f32::consts::TAU;
+    angle - tau * (angle / tau).round()
+}
+
+// dzieli korekte miedzy dwie strony; strona, ktora nie moze sie ruszyc, oddaje calosc drugiej
+fn split_between(amount: f32, first_free: bool, second_free: bool) -> (f32, f32) {
+    match (first_free, second_free) {
+        (true, true) => (amount / 2.0, amount / 2.0),
+        (true, false) => (amount, 0.0),
+        (false, true) => (0.0, amount),
+        (false, false) => (0.0, 0.0),
+    }
+}
+
+fn can_move_edge(polygons: &[Polygon], e: EdgeRef, pinned: &[VertexRef]) -> bool {
+    let n = polygons[e.polygon].vertices.len();
+    let start = VertexRef { polygon: e.polygon, vertex: e.edge };
+    let end = VertexRef { polygon: e.polygon, vertex: (e.edge + 1) % n };
+    !(pinned.contains(&start) && pinned.contains(&end))
+}
+
+// obrot o `angle` i skalowanie o `scale` wokol srodka krawedzi (albo przypietego konca)
+fn transform_edge(polygons: &mut [Polygon], e: EdgeRef, pinned: &[VertexRef], angle: f32, scale: f32) {
+    let polygon = &mut polygons[e.polygon];
+    let n = polygon.vertices.len();
+    let start_idx = e.edge;
+    let end_idx = (e.edge + 1) % n;
+    let start_pinned = pinned.contains(&VertexRef { polygon: e.polygon, vertex: start_idx });
+    let end_pinned = pinned.contains(&VertexRef { polygon: e.polygon, vertex: end_idx });
+    let start = polygon.vertices[start_idx];
+    let end = polygon.vertices[end_idx];
+
+    let pivot = match (start_pinned, end_pinned) {
+        (true, true) => return,
+        (true, false) => start,
+        (false, true) => end,
+        (false, false) => (start + end) * 0.5,
+    };
+    let map = |p: Point| {
+        let moved = rotate_about(p, pivot, angle);
+        Point { x: pivot.x + (moved.x - pivot.x) * scale, y: pivot.y + (moved.y - pivot.y) * scale, ..p }
+    };
+    polygon.vertices[start_idx] = map(start);
+    polygon.vertices[end_idx] = map(end);
+}
+
+fn rotate_about(p: Point, pivot: Point, angle: f32) -> Point {
+    let (sin, cos) = angle.sin_cos();
+    let dx = p.x - pivot.x;
+    let dy = p.y - pivot.y;
+    Point { x: pivot.x + dx * cos - dy * sin, y: pivot.y + dx * sin + dy * cos, ..p }
+}
+
+fn cross(a: Point, b: Point) -> f32 {
+    a.x * b.y - a.y * b.x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quad(dx: f32, skew: f32) -> Polygon {
+        Polygon::new(vec![
+            Point::new(dx, 0.0),
+            Point::new(dx + 40.0, skew),
+            Point::new(dx + 40.0, 30.0),
+            Point::new(dx, 30.0),
+        ])
+    }
+
+    fn edge(polygon: usize, edge: usize) -> EdgeRef {
+        EdgeRef { polygon, edge }
+    }
+
+    fn direction(polygons: &[Polygon], e: EdgeRef) -> Point {
+        let (a, b) = edge_points(polygons, e).unwrap();
+        (b - a).normalized()
+    }
+
+    #[test]
+    fn parallel_edges_converge() {
+        let mut polygons = vec![quad(0.0, 0.0), quad(100.0, 12.0)];
+        let relations = [Relation::Parallel(edge(0, 0), edge(1, 0))];
+        let result = solve_scene(&mut polygons, &relations, &[], &[]);
+        assert!(matches!(result, SolveResult::Converged { .. }));
+        assert!(relations[0].residual(&polygons) < SOLVER_TOLERANCE);
+        let (a, b) = (direction(&polygons, edge(0, 0)), direction(&polygons, edge(1, 0)));
+        assert!((a.x * b.y - a.y * b.x).abs() < 1e-3);
+    }
+
+    #[test]
+    fn perpendicular_turns_only_the_free_edge() {
+        let mut polygons = vec![quad(0.0, 0.0), quad(100.0, 12.0)];
+        let before = polygons[0].vertices.clone();
+        let relations = [Relation::Perpendicular(edge(0, 0), edge(1, 0))];
+        let pinned: Vec<VertexRef> = (0..4).map(|vertex| VertexRef { polygon: 0, vertex }).collect();
+        let result = solve_scene(&mut polygons, &relations, &pinned, &[]);
+        assert!(matches!(result, SolveResult::Converged { .. }));
+        assert!(polygons[0].vertices.iter().zip(&before).all(|(a, b)| a.distance(b) < 1e-6));
+        let (a, b) = (direction(&polygons, edge(0, 0)), direction(&polygons, edge(1, 0)));
+        assert!(a.dot(b).abs() < 1e-3);
+    }
+
+    #[test]
+    fn remap_after_vertex_removed() {
+        let mut relations = vec![
+            Relation::Parallel(edge(0, 0), edge(1, 2)),
+            Relation::Perpendicular(edge(0, 3), edge(1, 0)),
+            Relation::EqualLength(edge(1, 3), edge(0, 1)),
+            Relation::FixedAngle { at: VertexRef { polygon: 1, vertex: 3 }, degrees: 90.0 },
+            Relation::FixedAngle { at: VertexRef { polygon: 1, vertex: 1 }, degrees: 90.0 },
+        ];
+        // wierzcholek 1 z czworokata 1: krawedzie 0 i 1 zlewaja sie w jedna, dalsze indeksy o jeden w dol
+        on_vertex_removed(&mut relations, 1, 1, 4);
+        assert_eq!(
+            relations,
+            vec![
+                Relation::Parallel(edge(0, 0), edge(1, 1)),
+                Relation::EqualLength(edge(1, 2), edge(0, 1)),
+                Relation::FixedAngle { at: VertexRef { polygon: 1, vertex: 2 }, degrees: 90.0 },
+            ]
+        );
+    }
+
+    #[test]
+    fn remap_after_chain_opened() {
+        let mut relations = vec![
+            Relation::Parallel(edge(0, 0), edge(1, 2)),
+            Relation::Perpendicular(edge(0, 3), edge(1, 1)),
+            Relation::EqualLength(edge(1, 0), edge(0, 1)),
+            Relation::FixedAngle { at: VertexRef { polygon: 1, vertex: 3 }, degrees: 90.0 },
+            Relation::FixedAngle { at: VertexRef { polygon: 1, vertex: 2 }, degrees: 90.0 },
+        ];
+        // rozciecie w krawedzi 1: wierzcholek 2 dostaje numer 0 i staje sie koncem lamanej
+        on_chain_opened(&mut relations, 1, 1, 4);
+        assert_eq!(
+            relations,
+            vec![
+                Relation::Parallel(edge(0, 0), edge(1, 0)),
+                Relation::EqualLength(edge(1, 2), edge(0, 1)),
+                Relation::FixedAngle { at: VertexRef { polygon: 1, vertex: 1 }, degrees: 90.0 },
+            ]
+        );
+    }
+}
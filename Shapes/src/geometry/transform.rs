@@ -0,0 +1,260 @@
+use std::f32::consts::FRAC_PI_4;
+use super::point::Point;
+use super::polygon::{ConstraintType, Polygon};
+use super::vertex_constraint::{Guide, VertexConstraint};
+
+// kierunek uznajemy za poziomy / pionowy / 45°, jesli odchyla sie o mniej niz tyle
+const DIRECTION_EPS: f32 = 0.5 * std::f32::consts::PI / 180.0;
+
+/// Przekształcenie afiniczne: x' = a·x + b·y + tx, y' = c·x + d·y + ty.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Affine {
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+    pub d: f32,
+    pub tx: f32,
+    pub ty: f32,
+}
+
+impl Affine {
+    /// Obrót o `angle` radianów wokół `center` (przy osi y w dół dodatni kąt kręci zgodnie z zegarem).
+    pub fn rotation(center: Point, angle: f32) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Self::linear_about(center, cos, -sin, sin, cos)
+    }
+
+    /// Skalowanie osobno w x i y względem punktu `center`; ujemny współczynnik to odbicie.
+    pub fn scale(center: Point, sx: f32, sy: f32) -> Self {
+        Self::linear_about(center, sx, 0.0, 0.0, sy)
+    }
+
+    /// Odbicie lewo-prawo względem pionowej osi przez `center`.
+    pub fn mirror_horizontal(center: Point) -> Self {
+        Self::scale(center, -1.0, 1.0)
+    }
+
+    /// Odbicie góra-dół względem poziomej osi przez `center`.
+    pub fn mirror_vertical(center: Point) -> Self {
+        Self::scale(center, 1.0, -1.0)
+    }
+
+    // czesc liniowa (a b; c d) z punktem stalym `center`
+    fn linear_about(center: Point, a: f32, b: f32, c: f32, d: f32) -> Self {
+        Self {
+            a,
+            b,
+            c,
+            d,
+            tx: center.x - (a * center.x + b * center.y),
+            ty: center.y - (c * center.x + d * center.y),
+        }
+    }
+
+    /// Przekształcony punkt; rola i klasa ciągłości zostają.
+    pub fn apply(&self, p: Point) -> Point {
+        let (x, y) = self.apply_vector(p.x, p.y);
+        Point { x: x + self.tx, y: y + self.ty, ..p }
+    }
+
+    /// Sama część liniowa - dla wektorów (kierunków krawędzi).
+    pub fn apply_vector(&self, x: f32, y: f32) -> (f32, f32) {
+        (self.a * x + self.b * y, self.c * x + self.d * y)
+    }
+
+    pub fn determinant(&self) -> f32 {
+        self.a * self.d - self.b * self.c
+    }
+
+    /// Czy zachowuje kształt (obrót, skalowanie jednakowe w obu osiach, odbicie).
+    pub fn is_similarity(&self) -> bool {
+        let eps = 1e-4 * (self.a.abs() + self.b.abs() + self.c.abs() + self.d.abs()).max(1.0);
+        let rotation = (self.a - self.d).abs() < eps && (self.b + self.c).abs() < eps;
+        let reflection = (self.a + self.d).abs() < eps && (self.b - self.c).abs() < eps;
+        rotation || reflection
+    }
+}
+
+/// Przekształca cały kształt (wierzchołki, punkty kontrolne Beziera, dziury) i uzgadnia
+/// ograniczenia z nową geometrią:
+/// - H/V/45° dostają kierunek, w który trafiła krawędź, albo są zdejmowane,
+/// - stałe długości i promienie łuków są przeliczane razem ze skalą,
+/// - przypięcia i prowadnice jadą razem z wierzchołkami.
+///
+/// Zwraca opisy ograniczeń, których nie dało się zachować.
+pub fn transform_polygon(polygon: &mut Polygon, t: &Affine) -> Vec<String> {
+    polygon.ensure_constraints_len();
+    let mut notes = Vec::new();
+    let n = polygon.vertices.len();
+    let area_scale = t.determinant().abs().sqrt();
+
+    for edge in 0..polygon.edge_count() {
+        let start = polygon.vertices[edge];
+        let end = polygon.vertices[(edge + 1) % n];
+        let (dx, dy) = (end.x - start.x, end.y - start.y);
+        let (nx, ny) = t.apply_vector(dx, dy);
+        let old_len = (dx * dx + dy * dy).sqrt();
+        let new_len = (nx * nx + ny * ny).sqrt();
+
+        let Some(constraint) = polygon.constraints[edge].clone() else {
+            continue;
+        };
+        polygon.constraints[edge] = match constraint {
+            ConstraintType::Horizontal | ConstraintType::Vertical | ConstraintType::Diagonal45 if old_len > f32::EPSILON => {
+                let reinterpreted = classify_direction(nx, ny);
+                if reinterpreted.is_none() {
+                    notes.push(format!("krawedz {}: zdjeto ograniczenie kierunku", edge));
+                }
+                reinterpreted
+            }
+            ConstraintType::FixedLength(len) => {
+                let factor = if old_len > f32::EPSILON { new_len / old_len } else { area_scale };
+                Some(ConstraintType::FixedLength(len * factor as f64))
+            }
+            ConstraintType::Arc { g1_start, g1_end, radius, clockwise, large_arc } => {
+                if !t.is_similarity() {
+                    notes.push(format!("krawedz {}: luk zostaje lukiem okregu mimo nierownego skalowania", edge));
+                }
+                // odbicie odwraca kierunek obiegu
+                let clockwise = if t.determinant() < 0.0 { !clockwise } else { clockwise };
+                Some(ConstraintType::Arc { g1_start, g1_end, radius: radius * area_scale, clockwise, large_arc })
+            }
+            mut other => {
+                for control in other.bezier_controls_mut() {
+                    *control = t.apply(*control);
+                }
+                Some(other)
+            }
+        };
+    }
+
+    for v in polygon.vertices.iter_mut() {
+        *v = t.apply(*v);
+    }
+
+    // prowadnica pozioma / pionowa musi po przeksztalceniu dalej byc pozioma albo pionowa
+    for (idx, slot) in polygon.vertex_constraints.iter_mut().enumerate() {
+        let Some(constraint) = slot.as_mut() else {
+            continue;
+        };
+        let v = polygon.vertices[idx];
+        match constraint {
+            VertexConstraint::Pinned { x, y } => {
+                let moved = t.apply(Point::new(*x, *y));
+                *x = moved.x;
+                *y = moved.y;
+            }
+            VertexConstraint::OnGuide(guide) => {
+                let axis = match guide {
+                    Guide::Horizontal(_) => t.apply_vector(1.0, 0.0),
+                    Guide::Vertical(_) => t.apply_vector(0.0, 1.0),
+                };
+                match classify_direction(axis.0, axis.1) {
+                    Some(ConstraintType::Horizontal) => *guide = Guide::Horizontal(v.y),
+                    Some(ConstraintType::Vertical) => *guide = Guide::Vertical(v.x),
+                    _ => {
+                        notes.push(format!("wierzcholek {}: zdjeto prowadnice", idx));
+                        *slot = None;
+                    }
+                }
+            }
+            VertexConstraint::Coincident(_) => {}
+        }
+    }
+
+    for (k, hole) in polygon.holes.iter_mut().enumerate() {
+        notes.extend(transform_polygon(hole, t).into_iter().map(|note| format!("dziura {}, {}", k + 1, note)));
+    }
+    notes
+}
+
+// H/V/45° dla kierunku (x, y), None gdy to zaden z nich
+fn classify_direction(x: f32, y: f32) -> Option<ConstraintType> {
+    let angle = y.atan2(x);
+    let k = (angle / FRAC_PI_4).round();
+    if (angle - k * FRAC_PI_4).abs() > DIRECTION_EPS {
+        return None;
+    }
+    Some(match (k as i32).rem_euclid(4) {
+        0 => ConstraintType::Horizontal,
+        2 => ConstraintType::Vertical,
+        _ => ConstraintType::Diagonal45,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+    use super::*;
+
+    fn square() -> Polygon {
+        Polygon::new(vec![Point::new(0.0, 0.0), Point::new(10.0, 0.0), Point::new(10.0, 10.0), Point::new(0.0, 10.0)])
+    }
+
+    #[test]
+    fn quarter_turn_swaps_horizontal_and_vertical() {
+        let mut polygon = square();
+        polygon.constraints[0] = Some(ConstraintType::Horizontal);
+        polygon.constraints[1] = Some(ConstraintType::Vertical);
+        // odrobine mniej niz 90°, ale w granicach DIRECTION_EPS
+        let notes = transform_polygon(&mut polygon, &Affine::rotation(Point::new(5.0, 5.0), FRAC_PI_2 - 0.5 * DIRECTION_EPS));
+        assert!(notes.is_empty());
+        assert!(matches!(polygon.constraints[0], Some(ConstraintType::Vertical)));
+        assert!(matches!(polygon.constraints[1], Some(ConstraintType::Horizontal)));
+    }
+
+    #[test]
+    fn rotation_beyond_tolerance_drops_direction() {
+        let mut polygon = square();
+        polygon.constraints[0] = Some(ConstraintType::Horizontal);
+        let notes = transform_polygon(&mut polygon, &Affine::rotation(Point::new(5.0, 5.0), 2.0 * DIRECTION_EPS));
+        assert_eq!(notes.len(), 1);
+        assert!(polygon.constraints[0].is_none());
+    }
+
+    #[test]
+    fn fixed_length_follows_scale() {
+        let mut polygon = square();
+        polygon.constraints[0] = Some(ConstraintType::FixedLength(10.0));
+        polygon.constraints[1] = Some(ConstraintType::FixedLength(10.0));
+        transform_polygon(&mut polygon, &Affine::scale(Point::new(0.0, 0.0), 2.0, 3.0));
+        let length = |edge: usize| match polygon.constraints[edge] {
+            Some(ConstraintType::FixedLength(len)) => len,
+            _ => panic!("brak stalej dlugosci"),
+        };
+        assert!((length(0) - 20.0).abs() < 1e-4);
+        assert!((length(1) - 30.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn arc_radius_scales_with_sqrt_of_determinant() {
+        let mut polygon = square();
+        polygon.constraints[0] = Some(ConstraintType::Arc { g1_start: false, g1_end: false, radius: 8.0, clockwise: true, large_arc: false });
+        let notes = transform_polygon(&mut polygon, &Affine::scale(Point::new(0.0, 0.0), 2.0, 8.0));
+        // niejednakowe skalowanie: promien jak dla sredniej skali sqrt(2 * 8) = 4
+        assert_eq!(notes.len(), 1);
+        match polygon.constraints[0] {
+            Some(ConstraintType::Arc { radius, clockwise, .. }) => {
+                assert!((radius - 32.0).abs() < 1e-4);
+                assert!(clockwise);
+            }
+            _ => panic!("luk zniknal"),
+        }
+    }
+
+    #[test]
+    fn mirror_flips_arc_direction() {
+        for mirror in [Affine::mirror_horizontal(Point::new(5.0, 5.0)), Affine::mirror_vertical(Point::new(5.0, 5.0))] {
+            let mut polygon = square();
+            polygon.constraints[0] = Some(ConstraintType::Arc { g1_start: false, g1_end: false, radius: 6.0, clockwise: true, large_arc: false });
+            assert!(transform_polygon(&mut polygon, &mirror).is_empty());
+            match polygon.constraints[0] {
+                Some(ConstraintType::Arc { radius, clockwise, .. }) => {
+                    assert!((radius - 6.0).abs() < 1e-4);
+                    assert!(!clockwise);
+                }
+                _ => panic!("luk zniknal"),
+            }
+        }
+    }
+}
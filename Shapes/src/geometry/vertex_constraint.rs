@@ -0,0 +1,266 @@
+use serde::{Deserialize, Serialize};
+use super::point::Point;
+use super::polygon::Polygon;
+use super::relation::VertexRef;
+
+/// Pozioma (stałe y) albo pionowa (stałe x) prowadnica, po której może jeździć wierzchołek.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Guide {
+    Horizontal(f32),
+    Vertical(f32),
+}
+
+/// Ograniczenie nałożone na pojedynczy wierzchołek (a nie na krawędź).
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum VertexConstraint {
+    /// wierzchołek przypięty na stałe w punkcie (x, y)
+    Pinned { x: f32, y: f32 },
+    /// wierzchołek może się ruszać tylko wzdłuż prowadnicy
+    OnGuide(Guide),
+    /// wierzchołek pokrywa się z wierzchołkiem innego wielokąta
+    Coincident(VertexRef),
+}
+
+impl VertexConstraint {
+    pub fn is_pinned(&self) -> bool {
+        matches!(self, VertexConstraint::Pinned { .. })
+    }
+
+    /// Przycina przesunięcie (dx, dy) do kierunków, w których wierzchołek może się ruszyć.
+    pub fn constrain_move(&self, dx: f32, dy: f32) -> (f32, f32) {
+        match self {
+            VertexConstraint::Pinned { .. } => (0.0, 0.0),
+            VertexConstraint::OnGuide(Guide::Horizontal(_)) => (dx, 0.0),
+            VertexConstraint::OnGuide(Guide::Vertical(_)) => (0.0, dy),
+            VertexConstraint::Coincident(_) => (dx, dy),
+        }
+    }
+
+    /// Najbliższy punkt spełniający ograniczenie. `Coincident` rozwiązuje dopiero solver sceny.
+    pub fn project(&self, p: Point) -> Point {
+        match *self {
+            VertexConstraint::Pinned { x, y } => Point { x, y, ..p },
+            VertexConstraint::OnGuide(Guide::Horizontal(y)) => Point { y, ..p },
+            VertexConstraint::OnGuide(Guide::Vertical(x)) => Point { x, ..p },
+            VertexConstraint::Coincident(_) => p,
+        }
+    }
+
+    pub fn residual(&self, p: Point) -> f32 {
+        p.distance(&self.project(p))
+    }
+}
+
+/// Jeden krok dla wierzchołków połączonych `Coincident`: oba idą do wspólnego punktu,
+/// a jeśli któryś jest przypięty - drugi przeskakuje do niego.
+pub fn project_coincident(polygons: &mut [Polygon], pinned: &[VertexRef]) {
+    for (p_idx, v_idx, other) in coincident_pairs(polygons) {
+        let here = VertexRef { polygon: p_idx, vertex: v_idx };
+        let a = polygons[p_idx].vertices[v_idx];
+        let b = polygons[other.polygon].vertices[other.vertex];
+        let a_fixed = pinned.contains(&here) || polygons[p_idx].is_vertex_pinned(v_idx);
+        let b_fixed = pinned.contains(&other) || polygons[other.polygon].is_vertex_pinned(other.vertex);
+        let target = match (a_fixed, b_fixed) {
+            (true, true) => continue,
+            (true, false) => a,
+            (false, true) => b,
+            (false, false) => (a + b) * 0.5,
+        };
+        let a_vertex = &mut polygons[p_idx].vertices[v_idx];
+        a_vertex.x = target.x;
+        a_vertex.y = target.y;
+        let b_vertex = &mut polygons[other.polygon].vertices[other.vertex];
+        b_vertex.x = target.x;
+        b_vertex.y = target.y;
+    }
+}
+
+pub fn coincident_residual(polygons: &[Polygon]) -> f32 {
+    coincident_pairs(polygons)
+        .into_iter()
+        .map(|(p_idx, v_idx, other)| {
+            polygons[p_idx].vertices[v_idx].distance(&polygons[other.polygon].vertices[other.vertex])
+        })
+        .fold(0.0, f32::max)
+}
+
+// (wielokat, wierzcholek, drugi wierzcholek) - tylko pary wskazujace na istniejace wierzcholki
+fn coincident_pairs(polygons: &[Polygon]) -> Vec<(usize, usize, VertexRef)> {
+    let mut pairs = Vec::new();
+    for (p_idx, polygon) in polygons.iter().enumerate() {
+        for (v_idx, constraint) in polygon.vertex_constraints.iter().enumerate() {
+            if let Some(VertexConstraint::Coincident(other)) = constraint {
+                let exists = polygons.get(other.polygon).is_some_and(|p| other.vertex < p.vertices.len());
+                if exists && v_idx < polygon.vertices.len() && *other != (VertexRef { polygon: p_idx, vertex: v_idx }) {
+                    pairs.push((p_idx, v_idx, *other));
+                }
+            }
+        }
+    }
+    pairs
+}
+
+// --- utrzymanie indeksow w `Coincident` po zmianach topologii (jak w relation.rs) ---
+
+pub fn on_polygon_removed(polygons: &mut [Polygon], polygon: usize) {
+    remap(polygons, |r| match r.polygon.cmp(&polygon) {
+        std::cmp::Ordering::Less => Some(r),
+        std::cmp::Ordering::Equal => None,
+        std::cmp::Ordering::Greater => Some(VertexRef { polygon: r.polygon - 1, ..r }),
+    });
+}
+
+/// Nowy wierzchołek wstawiono w krawędź `edge`, czyli pod indeks `edge + 1`.
+pub fn on_vertex_inserted(polygons: &mut [Polygon], polygon: usize, edge: usize) {
+    remap(polygons, |r| {
+        Some(if r.polygon == polygon && r.vertex > edge { VertexRef { vertex: r.vertex + 1, ..r } } else { r })
+    });
+}
+
+pub fn on_vertex_removed(polygons: &mut [Polygon], polygon: usize, vertex: usize) {
+    remap(polygons, |r| {
+        if r.polygon != polygon || r.vertex < vertex {
+            Some(r)
+        } else if r.vertex == vertex {
+            None
+        } else {
+            Some(VertexRef { vertex: r.vertex - 1, ..r })
+        }
+    });
+}
+
+/// Odpowiednik `relation::on_chain_opened` - wierzchołek `edge + 1` dostaje numer 0.
+pub fn on_chain_opened(polygons: &mut [Polygon], polygon: usize, edge: usize, len: usize) {
+    let shift = (edge + 1) % len;
+    remap(polygons, |r| {
+        Some(if r.polygon == polygon { VertexRef { vertex: (r.vertex + len - shift) % len, ..r } } else { r })
+    });
+}
+
+fn remap(polygons: &mut [Polygon], map: impl Fn(VertexRef) -> Option<VertexRef>) {
+    for polygon in polygons.iter_mut() {
+        for constraint in polygon.vertex_constraints.iter_mut() {
+            if let Some(VertexConstraint::Coincident(other)) = *constraint {
+                *constraint = map(other).map(VertexConstraint::Coincident);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn triangle(dx: f32) -> Polygon {
+        let mut polygon = Polygon::new(vec![Point::new(dx, 0.0), Point::new(dx + 10.0, 0.0), Point::new(dx + 5.0, 8.0)]);
+        polygon.ensure_constraints_len();
+        polygon
+    }
+
+    // trzy trojkaty; wierzcholek 1 pierwszego pokrywa sie z `to`
+    fn linked(to: VertexRef) -> Vec<Polygon> {
+        let mut polygons = vec![triangle(0.0), triangle(20.0), triangle(40.0)];
+        polygons[0].vertex_constraints[1] = Some(VertexConstraint::Coincident(to));
+        polygons
+    }
+
+    fn target(polygons: &[Polygon]) -> Option<VertexRef> {
+        match polygons[0].vertex_constraints[1] {
+            Some(VertexConstraint::Coincident(other)) => Some(other),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn constrain_move_and_project() {
+        let pinned = VertexConstraint::Pinned { x: 1.0, y: 2.0 };
+        let horizontal = VertexConstraint::OnGuide(Guide::Horizontal(5.0));
+        let vertical = VertexConstraint::OnGuide(Guide::Vertical(-3.0));
+        let coincident = VertexConstraint::Coincident(VertexRef { polygon: 1, vertex: 0 });
+        assert_eq!(pinned.constrain_move(3.0, 4.0), (0.0, 0.0));
+        assert_eq!(horizontal.constrain_move(3.0, 4.0), (3.0, 0.0));
+        assert_eq!(vertical.constrain_move(3.0, 4.0), (0.0, 4.0));
+        assert_eq!(coincident.constrain_move(3.0, 4.0), (3.0, 4.0));
+
+        let p = Point::new(7.0, 9.0);
+        let at = |c: VertexConstraint| {
+            let q = c.project(p);
+            (q.x, q.y)
+        };
+        assert_eq!(at(pinned), (1.0, 2.0));
+        assert_eq!(at(horizontal), (7.0, 5.0));
+        assert_eq!(at(vertical), (-3.0, 9.0));
+        assert_eq!(at(coincident), (7.0, 9.0));
+    }
+
+    #[test]
+    fn residual_is_distance_to_projection() {
+        let p = Point::new(4.0, 6.0);
+        assert!((VertexConstraint::Pinned { x: 1.0, y: 2.0 }.residual(p) - 5.0).abs() < 1e-6);
+        assert!((VertexConstraint::OnGuide(Guide::Horizontal(1.0)).residual(p) - 5.0).abs() < 1e-6);
+        assert_eq!(VertexConstraint::OnGuide(Guide::Vertical(4.0)).residual(p), 0.0);
+        assert_eq!(VertexConstraint::Coincident(VertexRef { polygon: 0, vertex: 0 }).residual(p), 0.0);
+    }
+
+    #[test]
+    fn coincident_vertices_meet_halfway_or_at_pinned() {
+        let other = VertexRef { polygon: 1, vertex: 0 };
+        let mut polygons = linked(other);
+        assert!((coincident_residual(&polygons) - 10.0).abs() < 1e-6);
+        project_coincident(&mut polygons, &[]);
+        let (a, b) = (polygons[0].vertices[1], polygons[1].vertices[0]);
+        assert_eq!((a.x, a.y), (15.0, 0.0));
+        assert_eq!((b.x, b.y), (15.0, 0.0));
+        assert_eq!(coincident_residual(&polygons), 0.0);
+
+        // przeciagany (pinned) wierzcholek nie rusza sie, drugi do niego przeskakuje
+        let mut polygons = linked(other);
+        project_coincident(&mut polygons, &[other]);
+        assert_eq!((polygons[0].vertices[1].x, polygons[1].vertices[0].x), (20.0, 20.0));
+
+        // dwa przypiete wierzcholki zostaja na miejscu
+        let mut polygons = linked(other);
+        polygons[0].vertex_constraints[1] = None;
+        polygons[1].vertex_constraints[0] = Some(VertexConstraint::Pinned { x: 20.0, y: 0.0 });
+        polygons[0].vertex_constraints[2] = Some(VertexConstraint::Coincident(other));
+        project_coincident(&mut polygons, &[VertexRef { polygon: 0, vertex: 2 }]);
+        assert_eq!((polygons[0].vertices[2].x, polygons[1].vertices[0].x), (5.0, 20.0));
+    }
+
+    #[test]
+    fn remap_after_polygon_removed() {
+        let mut polygons = linked(VertexRef { polygon: 2, vertex: 1 });
+        on_polygon_removed(&mut polygons, 1);
+        assert_eq!(target(&polygons), Some(VertexRef { polygon: 1, vertex: 1 }));
+        on_polygon_removed(&mut polygons, 1);
+        assert_eq!(target(&polygons), None);
+    }
+
+    #[test]
+    fn remap_after_vertex_inserted_and_removed() {
+        let mut polygons = linked(VertexRef { polygon: 1, vertex: 1 });
+        on_vertex_inserted(&mut polygons, 1, 1);
+        assert_eq!(target(&polygons), Some(VertexRef { polygon: 1, vertex: 1 }));
+        on_vertex_inserted(&mut polygons, 1, 0);
+        assert_eq!(target(&polygons), Some(VertexRef { polygon: 1, vertex: 2 }));
+        on_vertex_inserted(&mut polygons, 2, 0);
+        assert_eq!(target(&polygons), Some(VertexRef { polygon: 1, vertex: 2 }));
+
+        on_vertex_removed(&mut polygons, 1, 0);
+        assert_eq!(target(&polygons), Some(VertexRef { polygon: 1, vertex: 1 }));
+        on_vertex_removed(&mut polygons, 1, 2);
+        assert_eq!(target(&polygons), Some(VertexRef { polygon: 1, vertex: 1 }));
+        on_vertex_removed(&mut polygons, 1, 1);
+        assert_eq!(target(&polygons), None);
+    }
+
+    #[test]
+    fn remap_after_chain_opened() {
+        // otwarcie trojkata na krawedzi 1: nowa numeracja zaczyna sie od wierzcholka 2
+        let mut polygons = linked(VertexRef { polygon: 1, vertex: 0 });
+        on_chain_opened(&mut polygons, 1, 1, 3);
+        assert_eq!(target(&polygons), Some(VertexRef { polygon: 1, vertex: 1 }));
+        on_chain_opened(&mut polygons, 2, 0, 3);
+        assert_eq!(target(&polygons), Some(VertexRef { polygon: 1, vertex: 1 }));
+    }
+}
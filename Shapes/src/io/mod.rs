@@ -0,0 +1,4 @@
+pub mod scene;
+pub mod svg;
+
+pub use scene::*;
@@ -0,0 +1,181 @@
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use crate::geometry::polygon::Polygon;
+use crate::geometry::relation::Relation;
+
+/// Wersja formatu pliku sceny - podbijamy przy każdej niekompatybilnej zmianie.
+pub const SCENE_VERSION: u32 = 1;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Scene {
+    pub version: u32,
+    pub polygons: Vec<Polygon>,
+    /// relacje miedzy krawedziami (rownoleglosc, prostopadlosc, ...) - starsze pliki ich nie maja
+    #[serde(default)]
+    pub relations: Vec<Relation>,
+}
+
+#[derive(Debug)]
+pub enum SceneError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SceneError::Io(e) => write!(f, "blad pliku: {}", e),
+            SceneError::Json(e) => write!(f, "niepoprawny plik sceny: {}", e),
+            SceneError::UnsupportedVersion(v) => {
+                write!(f, "nieobslugiwana wersja pliku sceny: {} (obslugiwana: {})", v, SCENE_VERSION)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SceneError {}
+
+impl From<std::io::Error> for SceneError {
+    fn from(e: std::io::Error) -> Self {
+        SceneError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for SceneError {
+    fn from(e: serde_json::Error) -> Self {
+        SceneError::Json(e)
+    }
+}
+
+impl Scene {
+    pub fn new(polygons: Vec<Polygon>, relations: Vec<Relation>) -> Self {
+        Self { version: SCENE_VERSION, polygons, relations }
+    }
+
+    pub fn to_json(&self) -> Result<String, SceneError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, SceneError> {
+        let mut scene: Scene = serde_json::from_str(text)?;
+        if scene.version > SCENE_VERSION {
+            return Err(SceneError::UnsupportedVersion(scene.version));
+        }
+        for polygon in &mut scene.polygons {
+            polygon.ensure_constraints_len();
+            for hole in &mut polygon.holes {
+                hole.ensure_constraints_len();
+            }
+        }
+        // relacje wskazujace poza scene pomijamy zamiast odrzucac caly plik
+        let polygons = &scene.polygons;
+        scene.relations.retain(|r| r.is_valid(polygons));
+        scene.version = SCENE_VERSION;
+        Ok(scene)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), SceneError> {
+        fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SceneError> {
+        let text = fs::read_to_string(path)?;
+        Self::from_json(&text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::point::{Continuity, Point};
+    use crate::geometry::polygon::{ConstraintType, FillRule};
+    use crate::geometry::relation::{EdgeRef, VertexRef};
+    use crate::geometry::vertex_constraint::{Guide, VertexConstraint};
+
+    fn sample_scene() -> Scene {
+        let mut curved = Polygon::new(vec![
+            Point::new(10.0, 10.0),
+            Point::new(110.5, 12.25),
+            Point::new(120.0, 90.0),
+            Point::new(15.0, 80.0),
+        ]);
+        curved.ensure_constraints_len();
+        curved.vertices[1].continuity = Continuity::G1;
+        curved.vertices[2].continuity = Continuity::C1;
+        curved.constraints[0] = Some(ConstraintType::Bezier {
+            control1: Point::new(40.0, -20.0),
+            control2: Point::new(80.0, -15.5),
+            g1_start: false,
+            g1_end: true,
+            c1_start: false,
+            c1_end: false,
+        });
+        curved.constraints[1] = Some(ConstraintType::Arc { g1_start: true, g1_end: false, radius: 55.5, clockwise: true, large_arc: false });
+        curved.constraints[2] = Some(ConstraintType::FixedLength(105.25));
+        curved.constraints[3] = Some(ConstraintType::Vertical);
+        curved.vertex_constraints[0] = Some(VertexConstraint::Pinned { x: 10.0, y: 10.0 });
+        curved.vertex_constraints[3] = Some(VertexConstraint::OnGuide(Guide::Horizontal(80.0)));
+        curved.fill = Some([200, 40, 40, 128]);
+        curved.fill_rule = FillRule::NonZero;
+        curved.add_hole(vec![Point::new(40.0, 30.0), Point::new(60.0, 30.0), Point::new(50.0, 50.0)]);
+
+        let mut open = Polygon::new_open(vec![Point::new(200.0, 0.0), Point::new(260.0, 40.0), Point::new(300.0, 0.0)]);
+        open.constraints[0] = Some(ConstraintType::QuadraticBezier { control: Point::new(220.0, 60.0) });
+        open.vertex_constraints[0] = Some(VertexConstraint::Coincident(VertexRef { polygon: 0, vertex: 2 }));
+
+        let relations = vec![
+            Relation::Parallel(EdgeRef { polygon: 0, edge: 2 }, EdgeRef { polygon: 1, edge: 1 }),
+            Relation::FixedAngle { at: VertexRef { polygon: 0, vertex: 3 }, degrees: 75.0 },
+        ];
+        Scene::new(vec![curved, open], relations)
+    }
+
+    #[test]
+    fn json_round_trip_keeps_everything() {
+        let scene = sample_scene();
+        let loaded = Scene::from_json(&scene.to_json().unwrap()).unwrap();
+        assert_eq!(loaded.version, SCENE_VERSION);
+        assert!(loaded.polygons == scene.polygons);
+        assert_eq!(loaded.relations, scene.relations);
+    }
+
+    #[test]
+    fn file_round_trip() {
+        let scene = sample_scene();
+        let path = std::env::temp_dir().join(format!("shapes_scene_test_{}.json", std::process::id()));
+        scene.save(&path).unwrap();
+        let loaded = Scene::load(&path);
+        let _ = fs::remove_file(&path);
+        let loaded = loaded.unwrap();
+        assert!(loaded.polygons == scene.polygons);
+        assert_eq!(loaded.relations, scene.relations);
+    }
+
+    #[test]
+    fn newer_version_is_rejected() {
+        let mut scene = sample_scene();
+        scene.version = SCENE_VERSION + 1;
+        let result = Scene::from_json(&scene.to_json().unwrap());
+        assert!(matches!(result, Err(SceneError::UnsupportedVersion(v)) if v == SCENE_VERSION + 1));
+    }
+
+    #[test]
+    fn hole_with_short_constraint_lists_is_padded() {
+        // recznie skrocone listy ograniczen dziury - po wczytaniu musza pasowac do wierzcholkow
+        let mut json: serde_json::Value = serde_json::from_str(&sample_scene().to_json().unwrap()).unwrap();
+        let hole = &mut json["polygons"][0]["holes"][0];
+        hole["constraints"] = serde_json::json!([]);
+        hole["vertex_constraints"] = serde_json::json!([null]);
+        let loaded = Scene::from_json(&json.to_string()).unwrap();
+        let hole = &loaded.polygons[0].holes[0];
+        assert_eq!(hole.constraints.len(), hole.edge_count());
+        assert_eq!(hole.vertex_constraints.len(), hole.vertices.len());
+        let mut polygon = loaded.polygons[0].clone();
+        polygon.relax(&[], &[(1, 2)]);
+        assert!(polygon.holes[0].constraints.iter().all(|c| c.is_none()));
+    }
+}
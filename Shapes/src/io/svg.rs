@@ -0,0 +1,779 @@
+use std::fmt;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+use crate::geometry::curves::{flatten_bezier_curve, DEFAULT_FLATNESS};
+use crate::geometry::point::{Continuity, Point, PointRole};
+use crate::geometry::polygon::{ConstraintType, FillRule, Polygon};
+
+const SVG_MARGIN: f32 = 10.0;
+// tolerancja przy rozpoznawaniu 45°, równych promieni i zgodnych stycznych
+const IMPORT_EPS: f32 = 1e-3;
+
+/// Zamienia wielokąty na dokument SVG - każdy wielokąt to jedna ścieżka `<path>`,
+/// a jego dziury są w niej kolejnymi podścieżkami.
+pub fn polygons_to_svg(polygons: &[Polygon]) -> String {
+    let (min, max) = bounding_box(polygons);
+    let width = (max.x - min.x + 2.0 * SVG_MARGIN).max(1.0);
+    let height = (max.y - min.y + 2.0 * SVG_MARGIN).max(1.0);
+
+    let mut out = String::new();
+    let _ = writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    let _ = writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}" width="{}" height="{}">"#,
+        fmt_num(min.x - SVG_MARGIN),
+        fmt_num(min.y - SVG_MARGIN),
+        fmt_num(width),
+        fmt_num(height),
+        fmt_num(width),
+        fmt_num(height),
+    );
+    for polygon in polygons {
+        let d = polygon_path_data(polygon);
+        if d.is_empty() {
+            continue;
+        }
+        // przy nonzero dziura musi biec przeciwnie do obrysu, inaczej zostalaby zamalowana
+        let outer_sign = signed_area(&polygon.outline()).signum();
+        let holes: String = polygon
+            .holes
+            .iter()
+            .map(|hole| format!(" {}", contour_path_data(hole, signed_area(&hole.outline()).signum() == outer_sign)))
+            .collect();
+        let _ = writeln!(
+            out,
+            r#"  <path d="{}{}" {} fill-rule="{}" stroke="black" stroke-width="1"/>"#,
+            d,
+            holes,
+            fill_attributes(polygon.fill),
+            match polygon.fill_rule {
+                FillRule::EvenOdd => "evenodd",
+                FillRule::NonZero => "nonzero",
+            },
+        );
+    }
+    out.push_str("</svg>\n");
+    out
+}
+
+pub fn export_svg(polygons: &[Polygon], path: impl AsRef<Path>) -> std::io::Result<()> {
+    fs::write(path, polygons_to_svg(polygons))
+}
+
+/// Atrybut `d` ścieżki dla jednego wielokąta (M ... Z; otwarta łamana bez Z).
+pub fn polygon_path_data(polygon: &Polygon) -> String {
+    contour_path_data(polygon, false)
+}
+
+// `reversed` - ten sam kontur obiegany od konca (krzywe z odwroconymi punktami kontrolnymi,
+// luki w przeciwna strone)
+fn contour_path_data(polygon: &Polygon, reversed: bool) -> String {
+    let n = polygon.vertices.len();
+    if n < 2 {
+        return String::new();
+    }
+
+    let first = if reversed { polygon.vertices[polygon.edge_end(polygon.edge_count() - 1)] } else { polygon.vertices[0] };
+    let mut d = format!("M {} {}", fmt_num(first.x), fmt_num(first.y));
+    let edges: Vec<usize> = if reversed { (0..polygon.edge_count()).rev().collect() } else { (0..polygon.edge_count()).collect() };
+    for i in edges {
+        let end = if reversed { polygon.vertices[i] } else { polygon.vertices[(i + 1) % n] };
+
+        match polygon.get_constraint(i) {
+            // H i V importer zamienia z powrotem na ograniczenia
+            Some(ConstraintType::Horizontal) => {
+                let _ = write!(d, " H {}", fmt_num(end.x));
+            }
+            Some(ConstraintType::Vertical) => {
+                let _ = write!(d, " V {}", fmt_num(end.y));
+            }
+            Some(ConstraintType::Bezier { control1, control2, .. }) => {
+                let (control1, control2) = if reversed { (control2, control1) } else { (control1, control2) };
+                let _ = write!(
+                    d,
+                    " C {} {} {} {} {} {}",
+                    fmt_num(control1.x), fmt_num(control1.y),
+                    fmt_num(control2.x), fmt_num(control2.y),
+                    fmt_num(end.x), fmt_num(end.y),
+                );
+            }
+            Some(ConstraintType::QuadraticBezier { control }) => {
+                let _ = write!(
+                    d,
+                    " Q {} {} {} {}",
+                    fmt_num(control.x), fmt_num(control.y),
+                    fmt_num(end.x), fmt_num(end.y),
+                );
+            }
+            Some(ConstraintType::BezierN { .. }) => {
+                // SVG nie zna krzywych wyzszych stopni - zapisujemy lamana
+                let mut control_points = polygon.bezier_points(i).unwrap_or_default();
+                if reversed {
+                    control_points.reverse();
+                }
+                for p in flatten_bezier_curve(&control_points, DEFAULT_FLATNESS).iter().skip(1) {
+                    let _ = write!(d, " L {} {}", fmt_num(p.x), fmt_num(p.y));
+                }
+            }
+            Some(ConstraintType::Arc { clockwise, large_arc, .. }) => {
+                // ta sama geometria co w rysowaniu (Polygon::arc_geometry); zdegenerowany luk to odcinek
+                let Some((_, radius, _, _)) = polygon.arc_geometry(i) else {
+                    let _ = write!(d, " L {} {}", fmt_num(end.x), fmt_num(end.y));
+                    continue;
+                };
+                let _ = write!(
+                    d,
+                    " A {} {} 0 {} {} {} {}",
+                    fmt_num(radius), fmt_num(radius),
+                    large_arc as u8, (clockwise != reversed) as u8,
+                    fmt_num(end.x), fmt_num(end.y),
+                );
+            }
+            _ => {
+                let _ = write!(d, " L {} {}", fmt_num(end.x), fmt_num(end.y));
+            }
+        }
+    }
+    if polygon.closed {
+        d.push_str(" Z");
+    }
+    d
+}
+
+fn fill_attributes(fill: Option<[u8; 4]>) -> String {
+    match fill {
+        None => r#"fill="none""#.to_string(),
+        Some([r, g, b, 255]) => format!(r##"fill="#{:02x}{:02x}{:02x}""##, r, g, b),
+        Some([r, g, b, a]) => format!(r##"fill="#{:02x}{:02x}{:02x}" fill-opacity="{}""##, r, g, b, fmt_num(a as f32 / 255.0)),
+    }
+}
+
+fn signed_area(ring: &[Point]) -> f32 {
+    let n = ring.len();
+    (0..n).map(|i| ring[i].x * ring[(i + 1) % n].y - ring[(i + 1) % n].x * ring[i].y).sum::<f32>() / 2.0
+}
+
+fn bounding_box(polygons: &[Polygon]) -> (Point, Point) {
+    let mut min = Point::new(f32::MAX, f32::MAX);
+    let mut max = Point::new(f32::MIN, f32::MIN);
+    for polygon in polygons.iter().flat_map(|p| std::iter::once(p).chain(&p.holes)) {
+        let controls = polygon
+            .constraints
+            .iter()
+            .flat_map(|c| c.as_ref().and_then(|c| c.bezier_controls()).unwrap_or_default());
+        for p in polygon.vertices.iter().cloned().chain(controls) {
+            min.x = min.x.min(p.x);
+            min.y = min.y.min(p.y);
+            max.x = max.x.max(p.x);
+            max.y = max.y.max(p.y);
+        }
+    }
+    if min.x > max.x {
+        return (Point::new(0.0, 0.0), Point::new(0.0, 0.0));
+    }
+    (min, max)
+}
+
+// ---------------------------------------------------------------------------
+// IMPORT
+// ---------------------------------------------------------------------------
+
+#[derive(Clone, Copy, Debug)]
+pub struct SvgImportOptions {
+    /// odcinki dokładnie pod kątem 45° dostają ograniczenie Diagonal45
+    pub infer_diagonal: bool,
+    /// wierzchołki przy krzywych z zgodnymi stycznymi dostają ciągłość G1/C1
+    pub infer_continuity: bool,
+}
+
+impl Default for SvgImportOptions {
+    fn default() -> Self {
+        Self { infer_diagonal: true, infer_continuity: true }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SvgImportError {
+    UnsupportedCommand(char),
+    EllipticalArc,
+    MissingNumber(char),
+    InvalidNumber(String),
+    MissingMoveTo,
+    TooFewVertices,
+    NoPath,
+}
+
+impl fmt::Display for SvgImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SvgImportError::UnsupportedCommand(c) => write!(f, "nieobslugiwana komenda sciezki: '{}'", c),
+            SvgImportError::EllipticalArc => write!(f, "luki eliptyczne (rx != ry) nie sa obslugiwane"),
+            SvgImportError::MissingNumber(c) => write!(f, "za malo argumentow dla komendy '{}'", c),
+            SvgImportError::InvalidNumber(s) => write!(f, "niepoprawna liczba: '{}'", s),
+            SvgImportError::MissingMoveTo => write!(f, "sciezka musi zaczynac sie od komendy M"),
+            SvgImportError::TooFewVertices => write!(f, "sciezka ma za malo wierzcholkow (zamknieta - 3, otwarta - 2)"),
+            SvgImportError::NoPath => write!(f, "w pliku nie ma zadnej sciezki <path>"),
+        }
+    }
+}
+
+impl std::error::Error for SvgImportError {}
+
+// segment sciezki od poprzedniego punktu do `end`
+#[derive(Clone, Copy)]
+enum Segment {
+    Line,
+    Horizontal,
+    Vertical,
+    Cubic(Point, Point),
+    Quadratic(Point),
+    Arc { radius: f32, large_arc: bool, clockwise: bool },
+}
+
+/// Wczytuje wszystkie `<path d="...">` z dokumentu SVG.
+pub fn import_svg_document(svg: &str, options: &SvgImportOptions) -> Result<Vec<Polygon>, SvgImportError> {
+    let paths = extract_path_data(svg);
+    if paths.is_empty() {
+        return Err(SvgImportError::NoPath);
+    }
+    let mut polygons = Vec::new();
+    for d in paths {
+        polygons.extend(import_path_data(&d, options)?);
+    }
+    Ok(polygons)
+}
+
+pub fn import_svg_file(path: impl AsRef<Path>, options: &SvgImportOptions) -> Result<Vec<Polygon>, Box<dyn std::error::Error>> {
+    let text = fs::read_to_string(path)?;
+    Ok(import_svg_document(&text, options)?)
+}
+
+/// Zamienia atrybut `d` na wielokąty - jeden na każdą podścieżkę zaczynającą się od M.
+pub fn import_path_data(d: &str, options: &SvgImportOptions) -> Result<Vec<Polygon>, SvgImportError> {
+    let mut tokens = PathTokens::new(d);
+    let mut polygons = Vec::new();
+
+    let mut points: Vec<Point> = Vec::new();
+    let mut segments: Vec<Segment> = Vec::new();
+    let mut current = Point::new(0.0, 0.0);
+    let mut command: Option<char> = None;
+    let mut started = false;
+
+    while let Some(cmd) = tokens.next_command(command)? {
+        if !"MZLHVCQA".contains(cmd.to_ascii_uppercase()) {
+            return Err(SvgImportError::UnsupportedCommand(cmd));
+        }
+        let relative = cmd.is_ascii_lowercase();
+        let base = if relative { current } else { Point::new(0.0, 0.0) };
+        // komenda rysujaca po Z bez M zaczyna nowa podsciezke w punkcie startowym poprzedniej
+        if started && points.is_empty() && !"MZ".contains(cmd.to_ascii_uppercase()) {
+            points.push(current);
+        }
+
+        match cmd.to_ascii_uppercase() {
+            'M' => {
+                // podsciezka bez Z to otwarta lamana
+                if points.len() > 1 {
+                    polygons.push(build_polygon(&points, &segments, false, options)?);
+                }
+                current = base + tokens.point(cmd)?;
+                points = vec![current];
+                segments.clear();
+                started = true;
+                // kolejne pary liczb po M to niejawne L
+                command = Some(if relative { 'l' } else { 'L' });
+                continue;
+            }
+            'Z' => {
+                if let Some(&start) = points.first() {
+                    if points.len() > 1 {
+                        polygons.push(build_polygon(&points, &segments, true, options)?);
+                    }
+                    current = start;
+                }
+                points.clear();
+                segments.clear();
+                command = None;
+                continue;
+            }
+            _ if !started => return Err(SvgImportError::MissingMoveTo),
+            'L' => {
+                current = base + tokens.point(cmd)?;
+                segments.push(Segment::Line);
+            }
+            'H' => {
+                let x = tokens.number(cmd)?;
+                current = Point::new(if relative { current.x + x } else { x }, current.y);
+                segments.push(Segment::Horizontal);
+            }
+            'V' => {
+                let y = tokens.number(cmd)?;
+                current = Point::new(current.x, if relative { current.y + y } else { y });
+                segments.push(Segment::Vertical);
+            }
+            'C' => {
+                let mut c1 = base + tokens.point(cmd)?;
+                let mut c2 = base + tokens.point(cmd)?;
+                c1.role = PointRole::Control;
+                c2.role = PointRole::Control;
+                current = base + tokens.point(cmd)?;
+                segments.push(Segment::Cubic(c1, c2));
+            }
+            'Q' => {
+                let mut control = base + tokens.point(cmd)?;
+                control.role = PointRole::Control;
+                current = base + tokens.point(cmd)?;
+                segments.push(Segment::Quadratic(control));
+            }
+            'A' => {
+                let rx = tokens.number(cmd)?;
+                let ry = tokens.number(cmd)?;
+                let _rotation = tokens.number(cmd)?;
+                let large_arc = tokens.flag(cmd)?;
+                let sweep = tokens.flag(cmd)?;
+                if (rx.abs() - ry.abs()).abs() > IMPORT_EPS {
+                    return Err(SvgImportError::EllipticalArc);
+                }
+                current = base + tokens.point(cmd)?;
+                // flaga sweep = 1 to rosnace katy, czyli zgodnie z ruchem wskazowek przy osi y w dol
+                segments.push(Segment::Arc { radius: rx.abs(), large_arc, clockwise: sweep });
+            }
+            other => return Err(SvgImportError::UnsupportedCommand(other)),
+        }
+        points.push(current);
+        command = Some(cmd);
+    }
+
+    if points.len() > 1 {
+        polygons.push(build_polygon(&points, &segments, false, options)?);
+    }
+    Ok(nest_holes(polygons))
+}
+
+// zamknieta podsciezka lezaca w ktorejs wczesniejszej z tej samej sciezki staje sie jej dziura
+fn nest_holes(polygons: Vec<Polygon>) -> Vec<Polygon> {
+    let mut result: Vec<Polygon> = Vec::new();
+    for polygon in polygons {
+        let owner = result
+            .iter_mut()
+            .find(|outer| polygon.closed && outer.closed && outer.contains_point(polygon.vertices[0]));
+        match owner {
+            Some(outer) => outer.holes.push(polygon),
+            None => result.push(polygon),
+        }
+    }
+    result
+}
+
+// points[k] -> points[k + 1] to segments[k]; w zamknietej (Z) ostatni punkt wraca do pierwszego
+fn build_polygon(points: &[Point], segments: &[Segment], closed: bool, options: &SvgImportOptions) -> Result<Polygon, SvgImportError> {
+    let mut points = points.to_vec();
+    let mut segments = segments.to_vec();
+
+    let first = points[0];
+    let last = points[points.len() - 1];
+    if !closed {
+        // otwarta lamana: wystarcza dwa punkty, bez krawedzi zamykajacej
+    } else if points.len() > 1 && first.distance(&last) < IMPORT_EPS {
+        // sciezka sama wraca do poczatku - ostatni segment jest krawedzia zamykajaca
+        points.pop();
+    } else {
+        segments.push(Segment::Line);
+    }
+
+    let n = points.len();
+    if n < if closed { 3 } else { 2 } {
+        return Err(SvgImportError::TooFewVertices);
+    }
+
+    let mut polygon = if closed { Polygon::new(points) } else { Polygon::new_open(points) };
+    for (i, segment) in segments.iter().enumerate() {
+        let start = polygon.vertices[i];
+        let end = polygon.vertices[(i + 1) % n];
+        polygon.constraints[i] = match *segment {
+            Segment::Horizontal => Some(ConstraintType::Horizontal),
+            Segment::Vertical => Some(ConstraintType::Vertical),
+            Segment::Cubic(control1, control2) => Some(ConstraintType::Bezier {
+                control1,
+                control2,
+                g1_start: false,
+                g1_end: false,
+                c1_start: false,
+                c1_end: false,
+            }),
+            Segment::Quadratic(control) => Some(ConstraintType::QuadraticBezier { control }),
+            Segment::Arc { radius, large_arc, clockwise } => Some(ConstraintType::Arc {
+                g1_start: false,
+                g1_end: false,
+                radius,
+                clockwise,
+                large_arc,
+            }),
+            Segment::Line => {
+                let dx = (end.x - start.x).abs();
+                let dy = (end.y - start.y).abs();
+                if options.infer_diagonal && dx > IMPORT_EPS && (dx - dy).abs() < IMPORT_EPS {
+                    Some(ConstraintType::Diagonal45)
+                } else {
+                    None
+                }
+            }
+        };
+    }
+
+    if options.infer_continuity {
+        infer_continuity(&mut polygon, &segments);
+    }
+    Ok(polygon)
+}
+
+// styczne liczymy jako pochodne: odcinek (end - start), kubiczna 3 * (c1 - p0) / 3 * (p3 - c2),
+// kwadratowa 2 * (c - p0) / 2 * (p2 - c)
+fn infer_continuity(polygon: &mut Polygon, segments: &[Segment]) {
+    let n = polygon.vertices.len();
+    for v in 0..n {
+        // konce otwartej lamanej nie maja drugiej strony
+        let (Some(in_idx), Some(out_idx)) = (polygon.incoming_edge(v), polygon.outgoing_edge(v)) else {
+            continue;
+        };
+        let (seg_in, seg_out) = (segments[in_idx], segments[out_idx]);
+        let is_curve = |seg: Segment| matches!(seg, Segment::Cubic(..) | Segment::Quadratic(..));
+        if !is_curve(seg_in) && !is_curve(seg_out) {
+            continue;
+        }
+
+        let prev = polygon.vertices[in_idx];
+        let vertex = polygon.vertices[v];
+        let next = polygon.vertices[(v + 1) % n];
+
+        let incoming = match seg_in {
+            Segment::Cubic(_, c2) => (vertex - c2) * 3.0,
+            Segment::Quadratic(c) => (vertex - c) * 2.0,
+            Segment::Arc { .. } => continue,
+            _ => vertex - prev,
+        };
+        let outgoing = match seg_out {
+            Segment::Cubic(c1, _) => (c1 - vertex) * 3.0,
+            Segment::Quadratic(c) => (c - vertex) * 2.0,
+            Segment::Arc { .. } => continue,
+            _ => next - vertex,
+        };
+
+        let len_in = incoming.length();
+        let len_out = outgoing.length();
+        if len_in < IMPORT_EPS || len_out < IMPORT_EPS {
+            continue;
+        }
+        let cross = incoming.x * outgoing.y - incoming.y * outgoing.x;
+        let aligned = (cross / (len_in * len_out)).abs() < IMPORT_EPS && incoming.dot(outgoing) > 0.0;
+        if !aligned {
+            continue;
+        }
+
+        let continuity = if (len_in - len_out).abs() < IMPORT_EPS * len_in.max(1.0) {
+            Continuity::C1
+        } else {
+            Continuity::G1
+        };
+        polygon.vertices[v].continuity = continuity;
+
+        let is_c1 = continuity == Continuity::C1;
+        if let Some(ConstraintType::Bezier { g1_end, c1_end, .. }) = polygon.constraints[in_idx].as_mut() {
+            *g1_end = true;
+            *c1_end = is_c1;
+        }
+        if let Some(ConstraintType::Bezier { g1_start, c1_start, .. }) = polygon.constraints[out_idx].as_mut() {
+            *g1_start = true;
+            *c1_start = is_c1;
+        }
+    }
+}
+
+// wyciaga atrybuty d="..." ze wszystkich elementow <path>
+fn extract_path_data(svg: &str) -> Vec<String> {
+    let mut result = Vec::new();
+    let mut rest = svg;
+    while let Some(start) = rest.find("<path") {
+        let after = &rest[start + 5..];
+        let end = after.find('>').unwrap_or(after.len());
+        let tag = &after[..end];
+        for quote in ['"', '\''] {
+            let pattern = format!("d={}", quote);
+            let found = tag.match_indices(&pattern).find(|(i, _)| {
+                *i == 0 || tag[..*i].ends_with(|c: char| c.is_whitespace())
+            });
+            if let Some((i, _)) = found {
+                let value = &tag[i + pattern.len()..];
+                if let Some(close) = value.find(quote) {
+                    result.push(value[..close].to_string());
+                }
+                break;
+            }
+        }
+        rest = &after[end..];
+    }
+    result
+}
+
+struct PathTokens<'a> {
+    chars: std::iter::Peekable<std::str::CharIndices<'a>>,
+    src: &'a str,
+}
+
+impl<'a> PathTokens<'a> {
+    fn new(src: &'a str) -> Self {
+        Self { chars: src.char_indices().peekable(), src }
+    }
+
+    fn skip_separators(&mut self) {
+        while let Some(&(_, c)) = self.chars.peek() {
+            if c.is_whitespace() || c == ',' {
+                self.chars.next();
+            } else {
+                break;
+            }
+        }
+    }
+
+    // kolejna komenda albo niejawne powtorzenie poprzedniej, gdy dalej sa liczby
+    fn next_command(&mut self, previous: Option<char>) -> Result<Option<char>, SvgImportError> {
+        self.skip_separators();
+        match self.chars.peek() {
+            None => Ok(None),
+            Some(&(_, c)) if c.is_ascii_alphabetic() && c != 'e' && c != 'E' => {
+                self.chars.next();
+                Ok(Some(c))
+            }
+            Some(&(_, c)) => match previous {
+                Some(p) if p != 'z' && p != 'Z' => Ok(Some(p)),
+                _ => Err(SvgImportError::InvalidNumber(c.to_string())),
+            },
+        }
+    }
+
+    fn number(&mut self, cmd: char) -> Result<f32, SvgImportError> {
+        self.skip_separators();
+        let start = match self.chars.peek() {
+            Some(&(i, c)) if c.is_ascii_digit() || c == '-' || c == '+' || c == '.' => i,
+            _ => return Err(SvgImportError::MissingNumber(cmd)),
+        };
+        let mut end = start;
+        let mut seen_dot = false;
+        let mut seen_exp = false;
+        let mut prev: Option<char> = None;
+        while let Some(&(i, c)) = self.chars.peek() {
+            let accept = match c {
+                '0'..='9' => true,
+                '-' | '+' => i == start || matches!(prev, Some('e') | Some('E')),
+                '.' if !seen_dot && !seen_exp => {
+                    seen_dot = true;
+                    true
+                }
+                'e' | 'E' if !seen_exp && i != start => {
+                    seen_exp = true;
+                    true
+                }
+                _ => false,
+            };
+            if !accept {
+                break;
+            }
+            end = i + c.len_utf8();
+            prev = Some(c);
+            self.chars.next();
+        }
+        let text = &self.src[start..end];
+        text.parse::<f32>().map_err(|_| SvgImportError::InvalidNumber(text.to_string()))
+    }
+
+    fn point(&mut self, cmd: char) -> Result<Point, SvgImportError> {
+        let x = self.number(cmd)?;
+        let y = self.number(cmd)?;
+        Ok(Point::new(x, y))
+    }
+
+    // flagi luku moga byc sklejone ("a5 5 0 011 1"), wiec czytamy pojedynczy znak
+    fn flag(&mut self, cmd: char) -> Result<bool, SvgImportError> {
+        self.skip_separators();
+        match self.chars.next() {
+            Some((_, '0')) => Ok(false),
+            Some((_, '1')) => Ok(true),
+            _ => Err(SvgImportError::MissingNumber(cmd)),
+        }
+    }
+}
+
+// liczby bez zbednych zer na koncu, zeby plik byl czytelny
+fn fmt_num(v: f32) -> String {
+    let s = format!("{:.3}", v);
+    let s = s.trim_end_matches('0').trim_end_matches('.');
+    if s == "-0" { "0".to_string() } else { s.to_string() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn import(d: &str) -> Vec<Polygon> {
+        import_path_data(d, &SvgImportOptions::default()).unwrap()
+    }
+
+    fn coords(polygon: &Polygon) -> Vec<(f32, f32)> {
+        polygon.vertices.iter().map(|v| (v.x, v.y)).collect()
+    }
+
+    #[test]
+    fn horizontal_and_vertical_lines() {
+        let polygons = import("M 10 10 H 50 V 40 H 10 Z");
+        assert_eq!(polygons.len(), 1);
+        let polygon = &polygons[0];
+        assert!(polygon.closed);
+        assert_eq!(coords(polygon), vec![(10.0, 10.0), (50.0, 10.0), (50.0, 40.0), (10.0, 40.0)]);
+        assert!(matches!(polygon.constraints[0], Some(ConstraintType::Horizontal)));
+        assert!(matches!(polygon.constraints[1], Some(ConstraintType::Vertical)));
+        assert!(matches!(polygon.constraints[2], Some(ConstraintType::Horizontal)));
+        // krawedz zamykajaca z Z to zwykly odcinek
+        assert!(polygon.constraints[3].is_none());
+    }
+
+    #[test]
+    fn relative_commands_match_absolute() {
+        let absolute = import("M 10 10 H 50 V 40 L 10 40 C 0 30 0 20 10 10 Z");
+        let relative = import("m 10 10 h 40 v 30 l -40 0 c -10 -10 -10 -20 0 -30 z");
+        assert_eq!(coords(&absolute[0]), coords(&relative[0]));
+        assert!(absolute[0] == relative[0]);
+    }
+
+    #[test]
+    fn cubic_keeps_controls_and_infers_continuity() {
+        // dwie krzywe z ta sama styczna i dlugoscia pochodnej w (10, 0) -> C1
+        let polygons = import("M 0 0 C 0 5 5 0 10 0 C 15 0 20 5 20 0");
+        let polygon = &polygons[0];
+        assert!(!polygon.closed);
+        assert_eq!(coords(polygon), vec![(0.0, 0.0), (10.0, 0.0), (20.0, 0.0)]);
+        match polygon.constraints[0] {
+            Some(ConstraintType::Bezier { control1, control2, g1_end, c1_end, .. }) => {
+                assert_eq!((control1.x, control1.y, control2.x, control2.y), (0.0, 5.0, 5.0, 0.0));
+                assert!(matches!(control1.role, PointRole::Control));
+                assert!(g1_end && c1_end);
+            }
+            _ => panic!("oczekiwano krzywej Beziera"),
+        }
+        assert_eq!(polygon.vertices[1].continuity, Continuity::C1);
+    }
+
+    #[test]
+    fn circular_arc_flags() {
+        let polygons = import("M 0 0 A 5 5 0 0 1 10 0 a 5 5 0 1 0 0 10 L 0 10 Z");
+        let polygon = &polygons[0];
+        assert_eq!(coords(polygon), vec![(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)]);
+        assert!(matches!(
+            polygon.constraints[0],
+            Some(ConstraintType::Arc { radius, clockwise: true, large_arc: false, .. }) if radius == 5.0
+        ));
+        assert!(matches!(polygon.constraints[1], Some(ConstraintType::Arc { clockwise: false, large_arc: true, .. })));
+    }
+
+    #[test]
+    fn implicit_repeated_commands() {
+        // pary liczb po M to niejawne L, a po L, H i C - kolejne segmenty tej samej komendy
+        let polygons = import("M 0 0 10 0 10 10 Z M 100 0 L 110 0 120 0 H 130 140");
+        assert_eq!(polygons.len(), 2);
+        assert_eq!(coords(&polygons[0]), vec![(0.0, 0.0), (10.0, 0.0), (10.0, 10.0)]);
+        assert_eq!(coords(&polygons[1]), vec![(100.0, 0.0), (110.0, 0.0), (120.0, 0.0), (130.0, 0.0), (140.0, 0.0)]);
+
+        let curves = import("M 0 0 c 0 5 5 5 5 0 0 -5 5 -5 5 0");
+        assert_eq!(coords(&curves[0]), vec![(0.0, 0.0), (5.0, 0.0), (10.0, 0.0)]);
+        assert!(curves[0].constraints.iter().all(|c| matches!(c, Some(ConstraintType::Bezier { .. }))));
+    }
+
+    #[test]
+    fn closed_subpath_inside_another_becomes_hole() {
+        let polygons = import("M 0 0 H 100 V 100 H 0 Z M 40 40 H 60 V 60 H 40 Z");
+        assert_eq!(polygons.len(), 1);
+        assert_eq!(polygons[0].holes.len(), 1);
+    }
+
+    #[test]
+    fn drawing_after_close_starts_from_subpath_start() {
+        let polygons = import("M 0 0 L 10 0 L 10 10 Z L 5 5 l 0 -5");
+        assert_eq!(polygons.len(), 2);
+        assert!(polygons[0].closed);
+        let open = &polygons[1];
+        assert!(!open.closed);
+        let expected = [(0.0, 0.0), (5.0, 5.0), (5.0, 0.0)];
+        assert_eq!(open.vertices.len(), expected.len());
+        for (v, (x, y)) in open.vertices.iter().zip(expected) {
+            assert!((v.x - x).abs() < 1e-4 && (v.y - y).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn errors() {
+        let options = SvgImportOptions::default();
+        let err = |d: &str| import_path_data(d, &options).err().unwrap();
+        assert_eq!(err("M 0 0 S 5 5 10 0"), SvgImportError::UnsupportedCommand('S'));
+        assert_eq!(err("M 0 0 t 5 5"), SvgImportError::UnsupportedCommand('t'));
+        assert_eq!(err("M 0 0 A 5 3 0 0 1 10 0"), SvgImportError::EllipticalArc);
+        assert_eq!(err("L 10 10"), SvgImportError::MissingMoveTo);
+        assert_eq!(err("Z L 10 10"), SvgImportError::MissingMoveTo);
+        assert_eq!(err("M 0 0 L 10"), SvgImportError::MissingNumber('L'));
+        assert_eq!(err("M 0 0 L 10 0 Z"), SvgImportError::TooFewVertices);
+        assert_eq!(import_svg_document("<svg></svg>", &options).err(), Some(SvgImportError::NoPath));
+    }
+
+    fn square(x: f32, y: f32, size: f32) -> Vec<Point> {
+        vec![Point::new(x, y), Point::new(x + size, y), Point::new(x + size, y + size), Point::new(x, y + size)]
+    }
+
+    #[test]
+    fn export_lines_and_fill() {
+        let mut polygon = Polygon::new(square(0.0, 0.0, 10.0));
+        assert_eq!(polygon_path_data(&polygon), "M 0 0 L 10 0 L 10 10 L 0 10 L 0 0 Z");
+        let svg = polygons_to_svg(std::slice::from_ref(&polygon));
+        assert!(svg.contains(r#"fill="none" fill-rule="evenodd""#));
+
+        polygon.fill = Some([255, 0, 16, 255]);
+        polygon.fill_rule = FillRule::NonZero;
+        let svg = polygons_to_svg(std::slice::from_ref(&polygon));
+        assert!(svg.contains(r##"fill="#ff0010" fill-rule="nonzero""##));
+
+        polygon.fill = Some([0, 0, 0, 51]);
+        assert!(polygons_to_svg(&[polygon]).contains(r##"fill="#000000" fill-opacity="0.2""##));
+
+        let mut open = Polygon::new_open(vec![Point::new(0.0, 0.0), Point::new(5.0, 5.0)]);
+        open.ensure_constraints_len();
+        assert_eq!(polygon_path_data(&open), "M 0 0 L 5 5");
+    }
+
+    #[test]
+    fn export_cubic_and_arc_flags() {
+        let polygons = import("M 0 0 C 0 5 5 5 10 0 A 5 5 0 1 0 10 10 A 5 5 0 0 1 0 10 Z");
+        assert_eq!(
+            polygon_path_data(&polygons[0]),
+            "M 0 0 C 0 5 5 5 10 0 A 5 5 0 1 0 10 10 A 5 5 0 0 1 0 10 L 0 0 Z"
+        );
+        let polygons = import("M 0 0 H 10 V 10 L 0 10 Z");
+        assert_eq!(polygon_path_data(&polygons[0]), "M 0 0 H 10 V 10 L 0 10 L 0 0 Z");
+    }
+
+    #[test]
+    fn export_reverses_hole_running_with_outer() {
+        let mut polygon = Polygon::new(square(0.0, 0.0, 100.0));
+        polygon.add_hole(square(40.0, 40.0, 20.0));
+        let svg = polygons_to_svg(&[polygon]);
+        assert!(svg.contains(r#"d="M 0 0 L 100 0 L 100 100 L 0 100 L 0 0 Z M 40 40 L 40 60 L 60 60 L 60 40 L 40 40 Z""#));
+    }
+
+    #[test]
+    fn export_import_round_trip() {
+        // dziura biegnie przeciwnie do obrysu, wiec eksport jej nie odwraca
+        let d = "M 0 0 H 40 C 50 0 50 30 40 30 A 15 15 0 0 1 10 30 Q 0 30 0 20 L 0 0 Z M 10 5 V 15 H 20 V 5 Z";
+        let polygons = import(d);
+        let svg = polygons_to_svg(&polygons);
+        let again = import_svg_document(&svg, &SvgImportOptions::default()).unwrap();
+        assert_eq!(again.len(), 1);
+        assert!(again[0] == polygons[0]);
+    }
+}
@@ -5,6 +5,7 @@ mod app;
 mod geometry;
 mod editor;
 mod view;
+mod io;
 
 fn main()-> eframe::Result<()> {
     let options = NativeOptions::default();
@@ -1,43 +1,72 @@
 use std::f32;
 use eframe::egui;
-use eframe::emath::Pos2;
 use eframe::epaint::Color32;
 use egui::{Painter, Stroke};
 use crate::geometry::polygon::{ConstraintType, Polygon};
 use crate::geometry::point::Point;
+use crate::geometry::vertex_constraint::{Guide, VertexConstraint};
+use crate::view::framebuffer::Framebuffer;
+use crate::view::viewport::Viewport;
 
 pub trait IPolygonDrawer {
+    // wielokat jest we wspolrzednych swiata - na ekran przelicza go widok z `set_view`
     fn draw(&self, painter: &egui::Painter, polygon: &mut Polygon);
 
 
-    fn draw_arc_between_points(
+    // luk okregu od kata `start_angle` o skierowana rozwartosc `sweep` (jak w Polygon::arc_geometry)
+    fn draw_arc(
         &self,
         painter: &Painter,
-        p1: Pos2,
-        p2: Pos2,
-        arc_angle: f32, // np. std::f32::consts::PI dla półokręgu
+        center: Point,
+        radius: f32,
+        start_angle: f32,
+        sweep: f32,
         color: Color32,
         thickness: f32,
     );
 
-
-    fn compute_arc_geometry(
-        start: Point,
-        end: Point,
-        tangent_start: Option<Point>, // punkt kierunku dla G1 start
-        tangent_end: Option<Point>,   // punkt kierunku dla G1 end
-        g1_start: bool,
-        g1_end: bool,
-    ) -> (Point, f32) where Self: Sized;
-    fn draw_cubic_bezier(
-        &self,
-        painter: &egui::Painter,
-        p0: Point,
-        p1: Point,
-        p2: Point,
-        p3: Point,
-        stroke: egui::Stroke,
-    );
+    // krzywa dowolnego stopnia: `points` = poczatek, punkty kontrolne, koniec
+    fn draw_bezier_curve(&self, painter: &egui::Painter, points: &[Point], stroke: egui::Stroke);
     fn draw_dashed_polyline(&self, painter: &egui::Painter, pts: &[egui::Pos2], stroke: egui::Stroke);
     fn draw_continuity_label(&self, painter: &egui::Painter, point: &Point);
+
+    // zwykly wierzcholek to czerwona kropka; przypiety - kwadrat, na prowadnicy - kropka z kreska
+    // wzdluz prowadnicy, wspolny z innym wielokatem - kropka w obwodce; `pos` jest juz na ekranie
+    fn draw_vertex(&self, painter: &egui::Painter, pos: egui::Pos2, constraint: Option<VertexConstraint>) {
+        match constraint {
+            Some(VertexConstraint::Pinned { .. }) => {
+                let rect = egui::Rect::from_center_size(pos, egui::vec2(11.0, 11.0));
+                painter.rect_filled(rect, 0.0, Color32::from_rgb(255, 170, 80));
+                painter.rect_stroke(rect, 0.0, Stroke::new(1.5, Color32::WHITE), egui::StrokeKind::Outside);
+            }
+            Some(VertexConstraint::OnGuide(guide)) => {
+                let along = match guide {
+                    Guide::Horizontal(_) => egui::vec2(12.0, 0.0),
+                    Guide::Vertical(_) => egui::vec2(0.0, 12.0),
+                };
+                painter.line_segment([pos - along, pos + along], Stroke::new(1.5, Color32::LIGHT_BLUE));
+                painter.circle_filled(pos, 5.0, Color32::RED);
+            }
+            Some(VertexConstraint::Coincident(_)) => {
+                painter.circle_filled(pos, 5.0, Color32::RED);
+                painter.circle_stroke(pos, 8.0, Stroke::new(1.5, Color32::WHITE));
+            }
+            None => {
+                painter.circle_filled(pos, 5.0, Color32::RED);
+            }
+        }
+    }
+
+    // ustawienia rasteryzacji - biblioteka egui sama antyaliasuje, wiec domyslnie nic nie robimy
+    fn set_antialiasing(&mut self, _enabled: bool) {}
+    fn set_line_thickness(&mut self, _thickness: f32) {}
+    // powiekszenie i przesuniecie plotna, ustawiane przed kazda klatka
+    fn set_view(&mut self, _view: Viewport) {}
+
+    // rysowanie do wlasnego bufora pikseli: przygotowanie i wyslanie go na ekran raz na klatke
+    fn begin_frame(&mut self, _painter: &egui::Painter, _rect: egui::Rect) {}
+    fn end_frame(&mut self, _painter: &egui::Painter) {}
+    fn framebuffer(&self) -> Option<Framebuffer> {
+        None
+    }
 }
\ No newline at end of file
@@ -0,0 +1,85 @@
+use std::path::Path;
+use eframe::egui::{Color32, ColorImage};
+
+/// Bufor RGBA w pamięci, do którego rasteryzuje `MyPolygonDrawer`.
+/// Współrzędne przyjmuje ekranowe - `origin` to piksel ekranu odpowiadający (0, 0) bufora.
+#[derive(Clone)]
+pub struct Framebuffer {
+    pub width: usize,
+    pub height: usize,
+    pub origin: (i32, i32),
+    pixels: Vec<Color32>,
+}
+
+impl Framebuffer {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self { width, height, origin: (0, 0), pixels: vec![Color32::TRANSPARENT; width * height] }
+    }
+
+    /// Zmienia rozmiar/położenie i czyści bufor (wywoływane na początku każdej klatki).
+    pub fn reset(&mut self, width: usize, height: usize, origin: (i32, i32)) {
+        self.width = width;
+        self.height = height;
+        self.origin = origin;
+        self.pixels.clear();
+        self.pixels.resize(width * height, Color32::TRANSPARENT);
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        let lx = x - self.origin.0;
+        let ly = y - self.origin.1;
+        if lx < 0 || ly < 0 || lx as usize >= self.width || ly as usize >= self.height {
+            return None;
+        }
+        Some(ly as usize * self.width + lx as usize)
+    }
+
+    #[cfg(test)]
+    pub fn pixel(&self, x: i32, y: i32) -> Option<Color32> {
+        self.index(x, y).map(|i| self.pixels[i])
+    }
+
+    /// Nakłada kolor z pokryciem z [0, 1] (alpha blending "over", kolory premultiplied).
+    pub fn blend_pixel(&mut self, x: i32, y: i32, color: Color32, coverage: f32) {
+        let Some(i) = self.index(x, y) else {
+            return;
+        };
+        let src = color.gamma_multiply(coverage.clamp(0.0, 1.0));
+        let dst = self.pixels[i];
+        let keep = 255 - src.a() as u32;
+        let mix = |s: u8, d: u8| (s as u32 + (d as u32 * keep + 127) / 255).min(255) as u8;
+        self.pixels[i] = Color32::from_rgba_premultiplied(
+            mix(src.r(), dst.r()),
+            mix(src.g(), dst.g()),
+            mix(src.b(), dst.b()),
+            mix(src.a(), dst.a()),
+        );
+    }
+
+    pub fn fill_rect(&mut self, x: i32, y: i32, width: i32, height: i32, color: Color32) {
+        for py in y..y + height {
+            for px in x..x + width {
+                self.blend_pixel(px, py, color, 1.0);
+            }
+        }
+    }
+
+    pub fn to_color_image(&self) -> ColorImage {
+        ColorImage::new([self.width, self.height], self.pixels.clone())
+    }
+
+    pub fn to_rgba_bytes(&self) -> Vec<u8> {
+        self.pixels.iter().flat_map(|c| c.to_srgba_unmultiplied()).collect()
+    }
+
+    pub fn save_png(&self, path: impl AsRef<Path>) -> image::ImageResult<()> {
+        image::save_buffer_with_format(
+            path,
+            &self.to_rgba_bytes(),
+            self.width as u32,
+            self.height as u32,
+            image::ExtendedColorType::Rgba8,
+            image::ImageFormat::Png,
+        )
+    }
+}
@@ -6,13 +6,18 @@ use crate::geometry::polygon::{Polygon, ConstraintType};
 use eframe::egui;
 //use egui::accesskit::Point;
 use crate::view::IPolygonDrawer::IPolygonDrawer;
+use crate::view::scanline;
+use crate::view::viewport::Viewport;
 use crate::geometry::point::{Continuity, Point};
+use crate::geometry::curves::{flatten_bezier_curve, sample_arc, DEFAULT_FLATNESS};
 
-pub struct PolygonDrawer;
+pub struct PolygonDrawer {
+    view: Viewport,
+}
 
 impl PolygonDrawer {
     pub fn new() -> Self{
-        Self
+        Self { view: Viewport::default() }
     }
 }
 impl IPolygonDrawer for PolygonDrawer {
@@ -24,78 +29,52 @@ impl IPolygonDrawer for PolygonDrawer {
         if n < 2 {
             return;
         }
+        // geometria jest w swiecie, na ekran przeliczamy pojedyncze punkty
+        let view = self.view;
+        let screen = |p: &Point| view.point_to_screen(*p);
 
-        for i in 0..n {
-            let start = &polygon.vertices[i];
-            let end = &polygon.vertices[(i + 1) % n]; // wrap-around
-
-            match polygon.constraints[i] {
-
-                Some(ConstraintType::Arc {g1_start, g1_end}) => {
-
-                    let prev = if i == 0 { None } else { Some(polygon.vertices[i - 1]) };
-                    let next = if i + 1 < n - 1 { Some(polygon.vertices[i + 2]) } else { None };
-
-                    let (center, radius) = PolygonDrawer::compute_arc_geometry(
-                        *start,
-                        *end,
-                        prev,
-                        next,
-                        g1_start,
-                        g1_end,
-                    );
+        // wypelnienie pod konturem
+        scanline::fill_polygon(painter, polygon, view);
 
+        for i in 0..polygon.edge_count() {
+            let start = &screen(&polygon.vertices[i]);
+            let end = &screen(&polygon.vertices[(i + 1) % n]); // wrap-around (w otwartej lamanej bez ostatniej)
 
-                    //if(g1_start || g1_end) { eprintln!("g1_start != g1_end");}
+            match polygon.constraints[i] {
 
-                    let start_angle = (*start - center).y.atan2((*start - center).x);
-                    let end_angle = (*end - center).y.atan2((*end - center).x);
-                    let mut arc_angle = end_angle - start_angle;
-                    if arc_angle < 0.0 {
-                        arc_angle = arc_angle * (-1.0);
-                    }
-                    self.draw_arc_between_points(
-                        painter,
-                        Pos2::new(start.x, start.y),
-                        Pos2::new(end.x, end.y),
-                        arc_angle,
-                        Color32::WHITE,
-                        1.0,
-                    );
+                Some(ConstraintType::Arc { .. }) if polygon.arc_geometry(i).is_some() => {
+                    // widok tylko skaluje jednakowo i przesuwa, wiec katy luku sie nie zmieniaja
+                    let (center, radius, start_angle, sweep) = polygon.arc_geometry(i).unwrap();
+                    self.draw_arc(painter, screen(&center), radius * view.zoom, start_angle, sweep, Color32::WHITE, 2.0);
                 }
 
-                Some(ConstraintType::Bezier {control1, control2, g1_start, g1_end, c1_start, c1_end }) => {
-                    self.draw_dashed_polyline(
-                        painter,
-                        &[
-                            egui::pos2(start.x, start.y),
-                            egui::pos2(control1.x, control1.y),
-                            egui::pos2(control2.x, control2.y),
-                            egui::pos2(end.x, end.y),
-                        ],
-                        egui::Stroke::new(1.0, egui::Color32::WHITE),
-                    );
-                    self.draw_cubic_bezier(
-                        painter,
-                        *start,
-                        control1,
-                        control2,
-                        *end,
-                        egui::Stroke::new(1.0, egui::Color32::WHITE),
-                    );
-                    painter.circle_filled(egui::pos2(control1.x, control1.y), 4.0, egui::Color32::GRAY);
-                    painter.circle_filled(egui::pos2(control2.x, control2.y), 4.0, egui::Color32::GRAY);
+                Some(ref constraint) if constraint.is_bezier() => {
+                    let points: Vec<Point> = polygon.bezier_points(i).unwrap_or_default().iter().map(screen).collect();
+                    let (g1_start, g1_end) = match constraint {
+                        ConstraintType::Bezier { g1_start, g1_end, .. } => (*g1_start, *g1_end),
+                        _ => (
+                            matches!(start.continuity, Continuity::G1 | Continuity::C1 | Continuity::G2),
+                            matches!(end.continuity, Continuity::G1 | Continuity::C1 | Continuity::G2),
+                        ),
+                    };
+                    let pts: Vec<egui::Pos2> = points.iter().map(|p| egui::pos2(p.x, p.y)).collect();
+                    let last = pts.len() - 1;
+                    self.draw_dashed_polyline(painter, &pts, egui::Stroke::new(1.0, egui::Color32::WHITE));
+                    self.draw_bezier_curve(painter, &points, egui::Stroke::new(1.0, egui::Color32::WHITE));
+                    for control in &pts[1..last] {
+                        painter.circle_filled(*control, 4.0, egui::Color32::GRAY);
+                    }
 
                     if g1_start {
                         painter.line_segment(
-                            [egui::pos2(start.x, start.y), egui::pos2(control1.x, control1.y)],
+                            [pts[0], pts[1]],
                             egui::Stroke::new(1.0, egui::Color32::LIGHT_BLUE),
                         );
                     }
 
                     if g1_end {
                         painter.line_segment(
-                            [egui::pos2(end.x, end.y), egui::pos2(control2.x, control2.y)],
+                            [pts[last], pts[last - 1]],
                             egui::Stroke::new(1.0, egui::Color32::LIGHT_BLUE),
                         );
                     }
@@ -119,7 +98,7 @@ impl IPolygonDrawer for PolygonDrawer {
                     ConstraintType::Horizontal => "H".to_string(),
                     ConstraintType::Vertical => "V".to_string(),
                     ConstraintType::Diagonal45 => "D".to_string(),
-                    ConstraintType::Arc { g1_start: _, g1_end: _ } => "A".to_string(),
+                    ConstraintType::Arc { .. } => "A".to_string(),
                     ConstraintType::Bezier { .. } => "B".to_string(),
                     ConstraintType::FixedLength(len) => format!("{:.1}", len),
                     _=> "".to_string(),
@@ -134,162 +113,43 @@ impl IPolygonDrawer for PolygonDrawer {
             }
         }
 
-        for v in &polygon.vertices { //TODO można zmieniac kolor jak jesteś nad nim
-            painter.circle_filled(egui::pos2(v.x, v.y), 5.0, egui::Color32::RED);
-            self.draw_continuity_label(painter, v);
+        for idx in 0..polygon.vertices.len() { //TODO można zmieniac kolor jak jesteś nad nim
+            let vertex = screen(&polygon.vertices[idx]);
+            self.draw_vertex(painter, egui::pos2(vertex.x, vertex.y), polygon.vertex_constraint(idx));
+            self.draw_continuity_label(painter, &vertex);
+        }
+        // dziury nie maja wlasnego wypelnienia, wiec rysuja sie jak zwykle kontury
+        for hole in polygon.holes.iter_mut() {
+            self.draw(painter, hole);
         }
     }
 
     // funkcja tylko rsuje odpowiednio okrag
-    fn draw_arc_between_points(
+    fn draw_arc(
         &self,
         painter: &Painter,
-        p1: Pos2,
-        p2: Pos2,
-        arc_angle: f32,
+        center: Point,
+        radius: f32,
+        start_angle: f32,
+        sweep: f32,
         color: Color32,
         thickness: f32,
     ) {
-        let mid = Pos2::new((p1.x + p2.x) / 2.0, (p1.y + p2.y) / 2.0);
-
-        let dx = p2.x - p1.x;
-        let dy = p2.y - p1.y;
-        let chord_length = (dx * dx + dy * dy).sqrt();
-
-        let radius = chord_length / (2.0 * (arc_angle / 2.0).sin());
-
-        let chord_angle = dy.atan2(dx);
-
-        let perp_angle = chord_angle + std::f32::consts::FRAC_PI_2;
-
-        let h = (radius * radius - (chord_length / 2.0).powi(2)).sqrt();
-
-        let center = Pos2::new(mid.x + h * perp_angle.cos(), mid.y + h * perp_angle.sin());
-
-        let start_angle = (p1.y - center.y).atan2(p1.x - center.x);
-        let end_angle = (p2.y - center.y).atan2(p2.x - center.x);
-
-        let segments = 1000;
-        for i in 0..=segments {
-            let t = i as f32 / segments as f32;
-            let angle = start_angle + t * (end_angle - start_angle);
-            let x = center.x + radius * angle.cos();
-            let y = center.y + radius * angle.sin();
-            let pos = Pos2::new(x, y);
-            painter.circle_filled(pos, thickness, color);
-        }
+        // mniej wiecej jeden odcinek na 4 piksele luku
+        let steps = ((radius * sweep.abs()) / 4.0).ceil().clamp(8.0, 1000.0) as usize;
+        let points: Vec<Pos2> = sample_arc(center, radius, start_angle, start_angle + sweep, steps)
+            .iter()
+            .map(|p| egui::pos2(p.x, p.y))
+            .collect();
+        painter.add(egui::Shape::line(points, Stroke::new(thickness, color)));
     }
-    fn compute_arc_geometry(
-        start: Point,
-        end: Point,
-        tangent_start: Option<Point>, // punkt kierunku dla G1 start
-        tangent_end: Option<Point>,   // punkt kierunku dla G1 end
-        g1_start: bool,
-        g1_end: bool,
-    ) -> (Point, f32) {
-        let chord = end - start;
-        let chord_len = chord.length();
-        let mid = (start + end) * 0.5;
-
-        if !g1_start && !g1_end {
-            let chord = end - start;
-            let chord_len = chord.length();
-            let normal = Point::new(-chord.y, chord.x).normalized();
-            let radius = chord_len / 2.0;
-            let center = (start + end) * 0.5 ;
-            return (center, radius);
-        }
-
-
 
-        if g1_start {
-            if let Some(ts) = tangent_start {
-                let tangent_dir = (start - ts).normalized();
-                let normal_start = Point::new(-tangent_dir.y, tangent_dir.x);
-
-                let normal_chord = Point::new(-chord.y, chord.x).normalized();
-
-                let denom = normal_start.x * normal_chord.y - normal_start.y * normal_chord.x;
-                if denom.abs() < 1e-6 {
-                    let normal = Point::new(-chord.y, chord.x).normalized();
-                    let radius = chord_len / 2.0;
-                    let center = mid + normal * radius;
-                    return (center, radius);
-                }
-
-                let delta = mid - start;
-                let t = (delta.x * normal_chord.y - delta.y * normal_chord.x) / denom;
-                let center = start + normal_start * t;
-
-                let to_center = center - mid;
-                if to_center.dot(normal_chord) < 0.0 {
-                    let center = start - normal_start * t;
-                    let radius = (center - start).length();
-                    return (center, radius);
-                }
-
-                let radius = (center - start).length();
-                return (center, radius);
-            }
-        }
-
-        if g1_end {
-            if let Some(te) = tangent_end {
-                let tangent_dir = (te - end).normalized();
-                let normal_end = Point::new(-tangent_dir.y, tangent_dir.x);
-                let normal_chord = Point::new(-chord.y, chord.x).normalized();
-
-                let denom = normal_end.x * normal_chord.y - normal_end.y * normal_chord.x;
-                if denom.abs() < 1e-6 {
-                    let normal = Point::new(-chord.y, chord.x).normalized();
-                    let radius = chord_len / 2.0;
-                    let center = mid + normal * radius;
-                    return (center, radius);
-                }
-
-                let delta = mid - end;
-                let t = (delta.x * normal_chord.y - delta.y * normal_chord.x) / denom;
-                let center = end + normal_end * t;
-
-                let to_center = center - mid;
-                if to_center.dot(normal_chord) < 0.0 {
-                    let center = end - normal_end * t;
-                    let radius = (center - end).length();
-                    return (center, radius);
-                }
-
-                let radius = (center - end).length();
-                return (center, radius);
-            }
-        }
-
-        // --- fallback ---
-        let normal = Point::new(-chord.y, chord.x).normalized();
-        let radius = chord_len / 2.0;
-        let center = mid + normal * radius;
-        (center, radius)
-    }
-
-    fn draw_cubic_bezier(
-        &self,
-        painter: &egui::Painter,
-        p0: Point,
-        p1: Point,
-        p2: Point,
-        p3: Point,
-        stroke: egui::Stroke,
-    ) {
-        let steps = 64;
-        let mut prev = egui::pos2(p0.x, p0.y);
-        for i in 1..=steps {
-            let t = i as f32 / steps as f32;
-            let u = 1.0 - t;
-            let x = u*u*u*p0.x + 3.0*u*u*t*p1.x + 3.0*u*t*t*p2.x + t*t*t*p3.x;
-            let y = u*u*u*p0.y + 3.0*u*u*t*p1.y + 3.0*u*t*t*p2.y + t*t*t*p3.y;
-            let cur = egui::pos2(x, y);
-            painter.line_segment([prev, cur], stroke);
-            prev = cur;
-        }
+    fn draw_bezier_curve(&self, painter: &egui::Painter, points: &[Point], stroke: egui::Stroke) {
+        let points: Vec<Pos2> = flatten_bezier_curve(points, DEFAULT_FLATNESS)
+            .iter()
+            .map(|p| egui::pos2(p.x, p.y))
+            .collect();
+        painter.add(egui::Shape::line(points, stroke));
     }
 
     fn draw_dashed_polyline(&self, painter: &egui::Painter, pts: &[egui::Pos2], stroke: egui::Stroke) {
@@ -312,12 +172,17 @@ impl IPolygonDrawer for PolygonDrawer {
         }
     }
 
+    fn set_view(&mut self, view: Viewport) {
+        self.view = view;
+    }
+
     fn draw_continuity_label(&self, painter: &Painter, point: &Point) {
         use egui::Align2;
 
         let label = match point.continuity {
             Continuity::G1 => Some("G1"),
             Continuity::C1 => Some("C1"),
+            Continuity::G2 => Some("G2"),
             _ => None,
         };
 
@@ -2,6 +2,10 @@
 pub mod libPolygonDrawer;
 pub mod IPolygonDrawer;
 pub(crate) mod myPolygonDrawer;
+pub mod scanline;
+pub mod framebuffer;
+pub mod relations;
+pub mod viewport;
 
 pub use libPolygonDrawer::PolygonDrawer;
 pub use IPolygonDrawer::*;
@@ -1,30 +1,142 @@
 use crate::view::IPolygonDrawer::IPolygonDrawer;
+use crate::view::scanline;
 use egui::{Painter, Pos2};
 use crate::geometry::polygon::{Polygon, ConstraintType};
 use eframe::egui;
 use eframe::epaint::{Color32, Stroke};
 use crate::geometry::point;
 use crate::geometry::point::{Continuity, Point};
-use crate::view::PolygonDrawer;
-
-pub struct MyPolygonDrawer;
+use crate::view::framebuffer::Framebuffer;
+use crate::view::viewport::Viewport;
+use std::cell::RefCell;
+use crate::geometry::curves::{angle_in_sweep, flatten_bezier_curve, DEFAULT_FLATNESS};
+
+pub struct MyPolygonDrawer {
+    /// linie i łuki algorytmem Wu zamiast Bresenhama
+    pub antialiasing: bool,
+    /// grubość linii w pikselach
+    pub thickness: f32,
+    view: Viewport,
+    // piksele z jednej klatki - wysylane do egui jako jedna tekstura
+    framebuffer: RefCell<Framebuffer>,
+    texture: Option<egui::TextureHandle>,
+    image_slot: Option<egui::layers::ShapeIdx>,
+}
 
 impl MyPolygonDrawer {
     pub fn new() -> Self {
-        Self
+        Self {
+            antialiasing: false,
+            thickness: 2.0,
+            view: Viewport::default(),
+            framebuffer: RefCell::new(Framebuffer::new(0, 0)),
+            texture: None,
+            image_slot: None,
+        }
+    }
+
+    /// Rysuje wielokąty do bufora o podanym rozmiarze bez okna i GPU (do testów),
+    /// jeden piksel na jednostkę świata.
+    #[cfg(test)]
+    pub fn render_offscreen(&self, polygons: &[Polygon], width: usize, height: usize) -> Framebuffer {
+        self.framebuffer.borrow_mut().reset(width, height, (0, 0));
+        for polygon in polygons {
+            self.rasterize(polygon);
+        }
+        self.framebuffer.borrow().clone()
     }
 
-    fn draw_pixel(painter: &egui::Painter, x: i32, y: i32, color: egui::Color32) {
-        let size = 2.0;
-        painter.rect_filled(
-            egui::Rect::from_min_size(egui::pos2(x as f32, y as f32), egui::vec2(size, size)),
-            0.0,
-            color,
-        );
+    // gruby piksel to kwadrat wysrodkowany na (x, y), zeby linia nie uciekala w prawo i w dol
+    fn draw_pixel(&self, x: i32, y: i32, color: egui::Color32) {
+        let size = self.thickness.round().max(1.0) as i32;
+        let offset = (size - 1) / 2;
+        self.framebuffer.borrow_mut().fill_rect(x - offset, y - offset, size, size, color);
+    }
+
+    // piksel 1x1 z pokryciem z [0, 1] (dla Wu)
+    fn draw_pixel_coverage(&self, x: i32, y: i32, color: egui::Color32, coverage: f32) {
+        if coverage <= 0.0 {
+            return;
+        }
+        self.framebuffer.borrow_mut().blend_pixel(x, y, color, coverage);
+    }
+
+    /// Wszystko, co idzie piksel po pikselu do bufora: wypełnienie, odcinki i łuki.
+    /// Wielokąt jest we współrzędnych świata, piksele liczymy w widoku z `set_view`.
+    pub fn rasterize(&self, polygon: &Polygon) {
+        let n = polygon.vertices.len();
+        if n < 2 {
+            return;
+        }
+        let view = self.view;
+        let screen = |p: &Point| view.point_to_screen(*p);
+
+        // otwartej lamanej nie wypelniamy
+        if let (true, Some([r, g, b, a])) = (polygon.closed, polygon.fill) {
+            let spans = scanline::scanline_spans(&view.outlines_to_screen(polygon), polygon.fill_rule);
+            let color = Color32::from_rgba_unmultiplied(r, g, b, a);
+            let mut framebuffer = self.framebuffer.borrow_mut();
+            for span in spans {
+                framebuffer.fill_rect(span.x_start, span.y, span.x_end - span.x_start, 1, color);
+            }
+        }
+
+        for i in 0..polygon.edge_count() {
+            let start = &screen(&polygon.vertices[i]);
+            let end = &screen(&polygon.vertices[(i + 1) % n]); // wrap-around (w otwartej lamanej bez ostatniej)
+
+            match polygon.get_constraint(i) {
+                Some(ConstraintType::Arc { .. }) if polygon.arc_geometry(i).is_some() => {
+                    // widok tylko skaluje jednakowo i przesuwa, wiec katy luku sie nie zmieniaja
+                    let (center, radius, start_angle, sweep) = polygon.arc_geometry(i).unwrap();
+                    let (center, radius) = (screen(&center), radius * view.zoom);
+                    if self.antialiasing {
+                        self.wu_arc(center, radius, start_angle, sweep, Color32::WHITE);
+                    } else {
+                        self.midpoint_arc(center, radius, start_angle, sweep, Color32::WHITE);
+                    }
+                }
+
+                Some(constraint) if constraint.is_bezier() => {
+                    if let Some(points) = polygon.bezier_points(i) {
+                        let points: Vec<Point> = points.iter().map(screen).collect();
+                        self.rasterize_bezier(&points, egui::Color32::WHITE);
+                    }
+                }
+
+                _ => self.rasterize_segment(*start, *end, egui::Color32::WHITE),
+            }
+        }
+
+        for hole in &polygon.holes {
+            self.rasterize(hole);
+        }
+    }
+
+    fn rasterize_segment(&self, start: Point, end: Point, color: egui::Color32) {
+        if self.antialiasing {
+            self.wu_line(Pos2::new(start.x, start.y), Pos2::new(end.x, end.y), color);
+        } else {
+            let start_i32 = (start.x as i32, start.y as i32);
+            let end_i32 = (end.x as i32, end.y as i32);
+
+            self.bresenham_line(
+                start_i32,
+                end_i32,
+                color,
+            );
+        }
+    }
+
+    // krzywa splaszczona adaptacyjnie, kazdy kawalek przez zwykla rasteryzacje odcinka
+    fn rasterize_bezier(&self, points: &[Point], color: egui::Color32) {
+        for segment in flatten_bezier_curve(points, DEFAULT_FLATNESS).windows(2) {
+            self.rasterize_segment(segment[0], segment[1], color);
+        }
     }
 
     fn bresenham_line(
-        painter: &egui::Painter,
+        &self,
         start: (i32, i32),
         end: (i32, i32),
         color: egui::Color32,
@@ -39,7 +151,7 @@ impl MyPolygonDrawer {
         let mut err = dx - dy;
 
         loop {
-            Self::draw_pixel(painter, x0, y0, color);
+            self.draw_pixel(x0, y0, color);
 
             if x0 == x1 && y0 == y1 {
                 break;
@@ -57,109 +169,171 @@ impl MyPolygonDrawer {
         }
     }
 
-}
-
-impl IPolygonDrawer for MyPolygonDrawer {
-    fn draw(&self, painter: &egui::Painter, polygon: &mut Polygon) {
-        polygon.ensure_constraints_len();
-        let n = polygon.vertices.len();
-        if n < 2 {
-            return;
+    /// Linia Xiaolina Wu z grubością: w każdej kolumnie (dla linii stromej - wierszu)
+    /// zamalowujemy pas o szerokości `thickness` mierzonej prostopadle do linii,
+    /// a piksele brzegowe dostają częściowe pokrycie.
+    fn wu_line(&self, start: Pos2, end: Pos2, color: egui::Color32) {
+        let (mut x0, mut y0, mut x1, mut y1) = (start.x, start.y, end.x, end.y);
+        let steep = (y1 - y0).abs() > (x1 - x0).abs();
+        if steep {
+            std::mem::swap(&mut x0, &mut y0);
+            std::mem::swap(&mut x1, &mut y1);
+        }
+        if x0 > x1 {
+            std::mem::swap(&mut x0, &mut x1);
+            std::mem::swap(&mut y0, &mut y1);
         }
 
-        for i in 0..n {
-            let start = &polygon.vertices[i];
-            let end = &polygon.vertices[(i + 1) % n]; // wrap-around
-
-            match polygon.constraints[i] {
+        let dx = x1 - x0;
+        let gradient = if dx.abs() < f32::EPSILON { 0.0 } else { (y1 - y0) / dx };
+        // pionowy przekroj pasa o grubosci `thickness`
+        let half = self.thickness.max(1.0) * (1.0 + gradient * gradient).sqrt() / 2.0;
 
-                Some(ConstraintType::Arc {g1_start, g1_end}) => {
+        let plot = |x: i32, y: i32, coverage: f32| {
+            if steep {
+                self.draw_pixel_coverage(y, x, color, coverage);
+            } else {
+                self.draw_pixel_coverage(x, y, color, coverage);
+            }
+        };
 
-                    let prev = if i == 0 { None } else { Some(polygon.vertices[i - 1]) };
-                    let next = if i + 1 < n - 1 { Some(polygon.vertices[i + 2]) } else { None };
+        for x in (x0.floor() as i32)..=(x1.floor() as i32) {
+            // kolumny na koncach odcinka pokrywa tylko jego czesc
+            let span = ((x as f32 + 1.0).min(x1) - (x as f32).max(x0)).clamp(0.0, 1.0);
+            if span <= 0.0 {
+                continue;
+            }
+            let center = y0 + gradient * (x as f32 + 0.5 - x0);
+            let top = center - half;
+            let bottom = center + half;
+            for y in (top.floor() as i32)..=(bottom.floor() as i32) {
+                let coverage = (bottom.min(y as f32 + 1.0) - top.max(y as f32)).clamp(0.0, 1.0);
+                plot(x, y, coverage * span);
+            }
+        }
+    }
 
-                    let (center, radius) = PolygonDrawer::compute_arc_geometry(
-                        *start,
-                        *end,
-                        prev,
-                        next,
-                        g1_start,
-                        g1_end,
-                    );
+    /// Okrąg metodą punktu środkowego (same liczby całkowite) ograniczony do łuku
+    /// od `start_angle` o skierowanej rozwartości `sweep`.
+    fn midpoint_arc(&self, center: Point, radius: f32, start_angle: f32, sweep: f32, color: egui::Color32) {
+        let cx = center.x.round() as i32;
+        let cy = center.y.round() as i32;
+        let r = radius.round() as i32;
+        if r <= 0 {
+            self.draw_pixel(cx, cy, color);
+            return;
+        }
 
+        let sector = ArcSector::new(start_angle, sweep);
+        let mut x = 0;
+        let mut y = r;
+        let mut d = 1 - r;
+        while x <= y {
+            // najpierw cztery obroty o 90° (zawsze rozne), potem ich odbicia - na osiach (x == 0)
+            // i przekatnej (x == y) odbicia pokrywaja sie z obrotami
+            let offsets = [(x, y), (-y, x), (-x, -y), (y, -x), (y, x), (-x, y), (-y, -x), (x, -y)];
+            let count = if x == 0 || x == y { 4 } else { 8 };
+            for &(dx, dy) in &offsets[..count] {
+                if sector.contains(dx, dy) {
+                    self.draw_pixel(cx + dx, cy + dy, color);
+                }
+            }
 
-                    //if(g1_start || g1_end) { eprintln!("g1_start != g1_end");}
+            if d < 0 {
+                d += 2 * x + 3;
+            } else {
+                d += 2 * (x - y) + 5;
+                y -= 1;
+            }
+            x += 1;
+        }
+    }
 
-                    let start_angle = (*start - center).y.atan2((*start - center).x);
-                    let end_angle = (*end - center).y.atan2((*end - center).x);
-                    let mut arc_angle = end_angle - start_angle;
-                    if arc_angle < 0.0 {
-                        arc_angle = arc_angle * (-1.0);
-                    }
-                    self.draw_arc_between_points(
-                        painter,
-                        Pos2::new(start.x, start.y),
-                        Pos2::new(end.x, end.y),
-                        arc_angle,
-                        Color32::WHITE,
-                        1.0,
-                    );
+    /// Okrąg Wu ograniczony do łuku od `start_angle` o skierowanej rozwartości `sweep`.
+    /// Liczymy jeden oktant (j >= i) i odbijamy go symetrycznie na pozostałe siedem;
+    /// pokrycie piksela to część pasa [r - t/2, r + t/2] wpadająca w ten piksel.
+    fn wu_arc(&self, center: Point, radius: f32, start_angle: f32, sweep: f32, color: egui::Color32) {
+        let half = self.thickness.max(1.0) / 2.0;
+        let cx = center.x.round() as i32;
+        let cy = center.y.round() as i32;
+        let r_in = (radius - half).max(0.0);
+        let r_out = radius + half;
+
+        let mut i = 0;
+        while (i as f32) <= r_out / std::f32::consts::SQRT_2 + 1.0 {
+            let x = i as f32;
+            let y_out = (r_out * r_out - x * x).max(0.0).sqrt();
+            let y_in = if x < r_in { (r_in * r_in - x * x).sqrt() } else { 0.0 };
+
+            for j in ((y_in - 0.5).floor() as i32)..=((y_out + 0.5).floor() as i32) {
+                if j < i {
+                    continue;
                 }
-
-                Some(ConstraintType::Bezier {control1, control2, g1_start, g1_end, c1_start, c1_end }) => {
-                    self.draw_dashed_polyline(
-                        painter,
-                        &[
-                            egui::pos2(start.x, start.y),
-                            egui::pos2(control1.x, control1.y),
-                            egui::pos2(control2.x, control2.y),
-                            egui::pos2(end.x, end.y),
-                        ],
-                        egui::Stroke::new(1.0, egui::Color32::WHITE),
-                    );
-                    self.draw_cubic_bezier(
-                        painter,
-                        *start,
-                        control1,
-                        control2,
-                        *end,
-                        egui::Stroke::new(1.0, egui::Color32::WHITE),
-                    );
-                    painter.circle_filled(egui::pos2(control1.x, control1.y), 4.0, egui::Color32::GRAY);
-                    painter.circle_filled(egui::pos2(control2.x, control2.y), 4.0, egui::Color32::GRAY);
-
-                    if g1_start {
-                        painter.line_segment(
-                            [egui::pos2(start.x, start.y), egui::pos2(control1.x, control1.y)],
-                            egui::Stroke::new(1.0, egui::Color32::LIGHT_BLUE),
-                        );
+                let coverage = ((j as f32 + 0.5).min(y_out) - (j as f32 - 0.5).max(y_in)).clamp(0.0, 1.0);
+                if coverage <= 0.0 {
+                    continue;
+                }
+                // na osiach i przekatnej odbicia sie pokrywaja - po sortowaniu pomijamy powtorki
+                let mut offsets = [(i, j), (-i, j), (i, -j), (-i, -j), (j, i), (-j, i), (j, -i), (-j, -i)];
+                offsets.sort_unstable();
+                for (k, &(dx, dy)) in offsets.iter().enumerate() {
+                    if k > 0 && offsets[k - 1] == (dx, dy) {
+                        continue;
                     }
-
-                    if g1_end {
-                        painter.line_segment(
-                            [egui::pos2(end.x, end.y), egui::pos2(control2.x, control2.y)],
-                            egui::Stroke::new(1.0, egui::Color32::LIGHT_BLUE),
-                        );
+                    if angle_in_sweep((dy as f32).atan2(dx as f32), start_angle, sweep) {
+                        self.draw_pixel_coverage(cx + dx, cy + dy, color, coverage);
                     }
-
                 }
+            }
+            i += 1;
+        }
+    }
 
-                _ => {
-                    let start_i32 = (start.x as i32, start.y as i32);
-                    let end_i32 = (end.x as i32, end.y as i32);
+    // to, czego nie ma w buforze pikseli: wielobok kontrolny, etykiety ograniczen, wierzcholki
+    fn draw_overlay(&self, painter: &egui::Painter, polygon: &Polygon) {
+        let n = polygon.vertices.len();
+        if n < 2 {
+            return;
+        }
+        let view = self.view;
+        let screen = |p: &Point| view.point_to_screen(*p);
+
+        for i in 0..polygon.edge_count() {
+            let start = &screen(&polygon.vertices[i]);
+            let end = &screen(&polygon.vertices[(i + 1) % n]); // wrap-around (w otwartej lamanej bez ostatniej)
+
+            if let Some(points) = polygon.bezier_points(i) {
+                let points: Vec<Point> = points.iter().map(screen).collect();
+                // krzywa jest juz w buforze (rasterize), tu tylko wielobok kontrolny i uchwyty
+                let (g1_start, g1_end) = match &polygon.constraints[i] {
+                    Some(ConstraintType::Bezier { g1_start, g1_end, .. }) => (*g1_start, *g1_end),
+                    _ => (
+                        matches!(start.continuity, Continuity::G1 | Continuity::C1 | Continuity::G2),
+                        matches!(end.continuity, Continuity::G1 | Continuity::C1 | Continuity::G2),
+                    ),
+                };
+                let pts: Vec<egui::Pos2> = points.iter().map(|p| egui::pos2(p.x, p.y)).collect();
+                let last = pts.len() - 1;
+                self.draw_dashed_polyline(painter, &pts, egui::Stroke::new(1.0, egui::Color32::WHITE));
+                for control in &pts[1..last] {
+                    painter.circle_filled(*control, 4.0, egui::Color32::GRAY);
+                }
 
-                    Self::bresenham_line(
-                        painter,
-                        start_i32,
-                        end_i32,
-                        egui::Color32::WHITE,
+                if g1_start {
+                    painter.line_segment(
+                        [pts[0], pts[1]],
+                        egui::Stroke::new(1.0, egui::Color32::LIGHT_BLUE),
                     );
                 }
 
+                if g1_end {
+                    painter.line_segment(
+                        [pts[last], pts[last - 1]],
+                        egui::Stroke::new(1.0, egui::Color32::LIGHT_BLUE),
+                    );
+                }
             }
 
-
-
             let mid = egui::pos2((start.x + end.x) / 2.0, (start.y + end.y) / 2.0);
 
             if let Some(Some(constraint)) = polygon.constraints.get(i) {
@@ -167,7 +341,7 @@ impl IPolygonDrawer for MyPolygonDrawer {
                     ConstraintType::Horizontal => "H".to_string(),
                     ConstraintType::Vertical => "V".to_string(),
                     ConstraintType::Diagonal45 => "D".to_string(),
-                    ConstraintType::Arc { g1_start: _, g1_end: _ } => "A".to_string(),
+                    ConstraintType::Arc { .. } => "A".to_string(),
                     ConstraintType::Bezier { .. } => "B".to_string(),
                     ConstraintType::FixedLength(len) => format!("{:.1}", len),
                     _=> "".to_string(),
@@ -182,140 +356,93 @@ impl IPolygonDrawer for MyPolygonDrawer {
             }
         }
 
-        for v in &polygon.vertices { //TODO można zmieniac kolor jak jesteś nad nim
-            painter.circle_filled(egui::pos2(v.x, v.y), 5.0, egui::Color32::RED);
-            self.draw_continuity_label(painter, v);
+        for idx in 0..polygon.vertices.len() { //TODO można zmieniac kolor jak jesteś nad nim
+            let vertex = screen(&polygon.vertices[idx]);
+            self.draw_vertex(painter, egui::pos2(vertex.x, vertex.y), polygon.vertex_constraint(idx));
+            self.draw_continuity_label(painter, &vertex);
         }
     }
+}
 
+// wycinek kola dla `midpoint_arc`: kierunki poczatku i konca luku jako wektory calkowite,
+// przynaleznosc piksela sprawdzamy iloczynami wektorowymi zamiast atan2
+struct ArcSector {
+    from: (i64, i64),
+    to: (i64, i64),
+    full: bool,
+    // rozwartosc powyzej pi - sprawdzamy, czy piksel nie lezy w dopelnieniu
+    reflex: bool,
+}
 
+impl ArcSector {
+    const SCALE: f32 = 65536.0;
 
-    fn draw_arc_between_points(
-        &self,
-        painter: &Painter,
-        p1: Pos2,
-        p2: Pos2,
-        arc_angle: f32,
-        color: Color32,
-        thickness: f32,
-    ) {
-        let mid = Pos2::new((p1.x + p2.x) / 2.0, (p1.y + p2.y) / 2.0);
-
-        let dx = p2.x - p1.x;
-        let dy = p2.y - p1.y;
-        let chord_length = (dx * dx + dy * dy).sqrt();
-
-        let radius = chord_length / (2.0 * (arc_angle / 2.0).sin());
-
-        let chord_angle = dy.atan2(dx);
-
-        let perp_angle = chord_angle + std::f32::consts::FRAC_PI_2;
-
-        let h = (radius * radius - (chord_length / 2.0).powi(2)).sqrt();
-
-        let center = Pos2::new(mid.x + h * perp_angle.cos(), mid.y + h * perp_angle.sin());
-
-        let start_angle = (p1.y - center.y).atan2(p1.x - center.x);
-        let end_angle = (p2.y - center.y).atan2(p2.x - center.x);
+    fn new(start_angle: f32, sweep: f32) -> Self {
+        let dir = |angle: f32| ((angle.cos() * Self::SCALE).round() as i64, (angle.sin() * Self::SCALE).round() as i64);
+        // ujemna rozwartosc to ten sam wycinek przebiegany od konca
+        let (from, to) = if sweep >= 0.0 {
+            (dir(start_angle), dir(start_angle + sweep))
+        } else {
+            (dir(start_angle + sweep), dir(start_angle))
+        };
+        Self { from, to, full: sweep.abs() >= std::f32::consts::TAU, reflex: sweep.abs() > std::f32::consts::PI }
+    }
 
-        let segments = 1000;
-        for i in 0..=segments {
-            let t = i as f32 / segments as f32;
-            let angle = start_angle + t * (end_angle - start_angle);
-            let x = center.x + radius * angle.cos();
-            let y = center.y + radius * angle.sin();
-            let pos = Pos2::new(x, y);
-            painter.circle_filled(pos, thickness, color);
+    fn contains(&self, dx: i32, dy: i32) -> bool {
+        if self.full {
+            return true;
+        }
+        let cross = |a: (i64, i64), b: (i64, i64)| a.0 * b.1 - a.1 * b.0;
+        let p = (dx as i64, dy as i64);
+        if self.reflex {
+            !(cross(self.to, p) > 0 && cross(p, self.from) > 0)
+        } else {
+            cross(self.from, p) >= 0 && cross(p, self.to) >= 0
         }
     }
+}
 
-
-    fn compute_arc_geometry(
-        start: Point,
-        end: Point,
-        tangent_start: Option<Point>, // punkt kierunku dla G1 start
-        tangent_end: Option<Point>,   // punkt kierunku dla G1 end
-        g1_start: bool,
-        g1_end: bool,
-    ) -> (Point, f32) {
-        let chord = end - start;
-        let chord_len = chord.length();
-        let mid = (start + end) * 0.5;
-
-        if !g1_start && !g1_end {
-            let normal = Point::new(-chord.y, chord.x).normalized();
-            let center = mid + normal * (chord_len / 2.0);
-            return (center, (center - start).length());
+impl IPolygonDrawer for MyPolygonDrawer {
+    fn draw(&self, painter: &egui::Painter, polygon: &mut Polygon) {
+        polygon.ensure_constraints_len();
+        let n = polygon.vertices.len();
+        if n < 2 {
+            return;
         }
 
-        if g1_start {
-            if let Some(ts) = tangent_start {
-                let tangent_dir = (start - ts).normalized();
-                let normal_start = Point::new(-tangent_dir.y, tangent_dir.x);
-
-                let normal_chord = Point::new(-chord.y, chord.x).normalized();
+        // wypelnienie, odcinki i luki ida do bufora pikseli (razem z dziurami) w rozdzielczosci ekranu
+        self.rasterize(polygon);
 
-                let denom = normal_start.x * normal_chord.y - normal_start.y * normal_chord.x;
-                if denom.abs() < 1e-6 {
-                    let center = mid + normal_chord * (chord_len / 2.0);
-                    return (center, (center - start).length());
-                }
-
-                let delta = mid - start;
-                let t = (delta.x * normal_chord.y - delta.y * normal_chord.x) / denom;
-                let center = start + normal_start * t;
-                let radius = (center - start).length();
-                return (center, radius);
-            }
+        self.draw_overlay(painter, polygon);
+        for hole in &polygon.holes {
+            self.draw_overlay(painter, hole);
         }
+    }
 
-        if g1_end {
-            if let Some(te) = tangent_end {
-                let tangent_dir = (te - end).normalized();
-                let normal_end = Point::new(-tangent_dir.y, tangent_dir.x);
-                let normal_chord = Point::new(-chord.y, chord.x).normalized();
-
-                let denom = normal_end.x * normal_chord.y - normal_end.y * normal_chord.x;
-                if denom.abs() < 1e-6 {
-                    let center = mid + normal_chord * (chord_len / 2.0);
-                    return (center, (center - start).length());
-                }
-
-                let delta = mid - end;
-                let t = (delta.x * normal_chord.y - delta.y * normal_chord.x) / denom;
-                let center = end + normal_end * t;
-                let radius = (center - end).length();
-                return (center, radius);
-            }
-        }
 
-        let normal = Point::new(-chord.y, chord.x).normalized();
-        let center = mid + normal * (chord_len / 2.0);
-        (center, (center - start).length())
-    }
 
-    fn draw_cubic_bezier(
+    fn draw_arc(
         &self,
-        painter: &egui::Painter,
-        p0: Point,
-        p1: Point,
-        p2: Point,
-        p3: Point,
-        stroke: egui::Stroke,
+        _painter: &Painter,
+        center: Point,
+        radius: f32,
+        start_angle: f32,
+        sweep: f32,
+        color: Color32,
+        _thickness: f32,
     ) {
-        let steps = 64;
-        let mut prev = egui::pos2(p0.x, p0.y);
-        for i in 1..=steps {
-            let t = i as f32 / steps as f32;
-            let u = 1.0 - t;
-            let x = u*u*u*p0.x + 3.0*u*u*t*p1.x + 3.0*u*t*t*p2.x + t*t*t*p3.x;
-            let y = u*u*u*p0.y + 3.0*u*u*t*p1.y + 3.0*u*t*t*p2.y + t*t*t*p3.y;
-            let cur = egui::pos2(x, y);
-            painter.line_segment([prev, cur], stroke);
-            prev = cur;
+        // w tej implementacji luk trafia do bufora pikseli, nie przez painter
+        if self.antialiasing {
+            self.wu_arc(center, radius, start_angle, sweep, color);
+        } else {
+            self.midpoint_arc(center, radius, start_angle, sweep, color);
         }
     }
 
+    fn draw_bezier_curve(&self, _painter: &egui::Painter, points: &[Point], stroke: egui::Stroke) {
+        self.rasterize_bezier(points, stroke.color);
+    }
+
     fn draw_dashed_polyline(&self, painter: &egui::Painter, pts: &[egui::Pos2], stroke: egui::Stroke) {
         for w in pts.windows(2) {
             let a = w[0]; let b = w[1];
@@ -342,6 +469,7 @@ impl IPolygonDrawer for MyPolygonDrawer {
         let label = match point.continuity {
             Continuity::G1 => Some("G1"),
             Continuity::C1 => Some("C1"),
+            Continuity::G2 => Some("G2"),
             _ => None,
         };
 
@@ -356,5 +484,241 @@ impl IPolygonDrawer for MyPolygonDrawer {
             );
         }
     }
+
+    fn set_antialiasing(&mut self, enabled: bool) {
+        self.antialiasing = enabled;
+    }
+
+    fn set_line_thickness(&mut self, thickness: f32) {
+        self.thickness = thickness.max(1.0);
+    }
+
+    fn set_view(&mut self, view: Viewport) {
+        self.view = view;
+    }
+
+    fn begin_frame(&mut self, painter: &egui::Painter, rect: egui::Rect) {
+        let width = rect.width().ceil().max(0.0) as usize;
+        let height = rect.height().ceil().max(0.0) as usize;
+        let origin = (rect.min.x.floor() as i32, rect.min.y.floor() as i32);
+        self.framebuffer.get_mut().reset(width, height, origin);
+        // miejsce na teksture rezerwujemy przed reszta, zeby etykiety i uchwyty byly nad nia
+        self.image_slot = Some(painter.add(egui::Shape::Noop));
+    }
+
+    fn end_frame(&mut self, painter: &egui::Painter) {
+        let Some(slot) = self.image_slot.take() else {
+            return;
+        };
+        let framebuffer = self.framebuffer.get_mut();
+        if framebuffer.width == 0 || framebuffer.height == 0 {
+            return;
+        }
+        let image = framebuffer.to_color_image();
+        let texture = match &mut self.texture {
+            Some(texture) => {
+                texture.set(image, egui::TextureOptions::NEAREST);
+                texture
+            }
+            None => self.texture.insert(painter.ctx().load_texture(
+                "bresenham_framebuffer",
+                image,
+                egui::TextureOptions::NEAREST,
+            )),
+        };
+        let min = egui::pos2(framebuffer.origin.0 as f32, framebuffer.origin.1 as f32);
+        let rect = egui::Rect::from_min_size(min, egui::vec2(framebuffer.width as f32, framebuffer.height as f32));
+        let uv = egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0));
+        painter.set(slot, egui::Shape::image(texture.id(), rect, uv, Color32::WHITE));
+    }
+
+    fn framebuffer(&self) -> Option<Framebuffer> {
+        Some(self.framebuffer.borrow().clone())
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::polygon::FillRule;
+
+    fn lit(framebuffer: &Framebuffer) -> Vec<(i32, i32)> {
+        let mut result = Vec::new();
+        for y in 0..framebuffer.height as i32 {
+            for x in 0..framebuffer.width as i32 {
+                if framebuffer.pixel(x, y).is_some_and(|c| c.a() > 0) {
+                    result.push((x, y));
+                }
+            }
+        }
+        result
+    }
+
+    fn render_line(from: (f32, f32), to: (f32, f32)) -> Vec<(i32, i32)> {
+        let mut drawer = MyPolygonDrawer::new();
+        drawer.thickness = 1.0;
+        let line = Polygon::new_open(vec![Point::new(from.0, from.1), Point::new(to.0, to.1)]);
+        lit(&drawer.render_offscreen(&[line], 10, 10))
+    }
+
+    #[test]
+    fn horizontal_line() {
+        let expected: Vec<(i32, i32)> = (1..=6).map(|x| (x, 2)).collect();
+        assert_eq!(render_line((1.0, 2.0), (6.0, 2.0)), expected);
+    }
+
+    #[test]
+    fn vertical_line() {
+        let expected: Vec<(i32, i32)> = (2..=7).map(|y| (3, y)).collect();
+        assert_eq!(render_line((3.0, 7.0), (3.0, 2.0)), expected);
+    }
+
+    #[test]
+    fn diagonal_line() {
+        let expected: Vec<(i32, i32)> = (1..=5).map(|k| (k, k)).collect();
+        assert_eq!(render_line((1.0, 1.0), (5.0, 5.0)), expected);
+        let expected: Vec<(i32, i32)> = (1..=5).map(|k| (6 - k, k)).collect();
+        assert_eq!(render_line((5.0, 1.0), (1.0, 5.0)), expected);
+    }
+
+    #[test]
+    fn thick_line_is_centered() {
+        let mut drawer = MyPolygonDrawer::new();
+        drawer.thickness = 3.0;
+        let line = Polygon::new_open(vec![Point::new(3.0, 5.0), Point::new(6.0, 5.0)]);
+        let expected: Vec<(i32, i32)> = (4..=6).flat_map(|y| (2..=7).map(move |x| (x, y))).collect();
+        assert_eq!(lit(&drawer.render_offscreen(&[line], 10, 10)), expected);
+    }
+
+    fn render_arc(start_deg: f32, sweep_deg: f32) -> Vec<(i32, i32)> {
+        let mut drawer = MyPolygonDrawer::new();
+        drawer.thickness = 1.0;
+        drawer.framebuffer.borrow_mut().reset(41, 41, (0, 0));
+        drawer.midpoint_arc(Point::new(20.0, 20.0), 10.0, start_deg.to_radians(), sweep_deg.to_radians(), Color32::WHITE);
+        lit(&drawer.framebuffer.borrow()).into_iter().map(|(x, y)| (x - 20, y - 20)).collect()
+    }
+
+    #[test]
+    fn midpoint_arc_follows_sweep_direction() {
+        // katy rosna od +x w strone +y; dodatnia rozwartosc idzie w te strone, ujemna w przeciwna
+        let increasing = render_arc(0.0, 90.0);
+        assert!(increasing.contains(&(10, 0)) && increasing.contains(&(0, 10)));
+        assert!(increasing.iter().all(|&(dx, dy)| dx >= 0 && dy >= 0));
+
+        let decreasing = render_arc(0.0, -90.0);
+        assert!(decreasing.contains(&(10, 0)) && decreasing.contains(&(0, -10)));
+        assert!(decreasing.iter().all(|&(dx, dy)| dx >= 0 && dy <= 0));
+
+        // rozwartosc ponad pi: pusta zostaje tylko cwiartka dx > 0, dy < 0
+        let reflex = render_arc(0.0, 270.0);
+        assert!(reflex.contains(&(-10, 0)) && reflex.contains(&(0, -10)));
+        assert!(!reflex.iter().any(|&(dx, dy)| dx > 0 && dy < 0));
+
+        for &(dx, dy) in increasing.iter().chain(&decreasing).chain(&reflex) {
+            let r = ((dx * dx + dy * dy) as f32).sqrt();
+            assert!((r - 10.0).abs() <= 1.0);
+        }
+    }
+
+    #[test]
+    fn midpoint_arc_across_pi() {
+        // 135° -> 225° przechodzi przez ±180°; to samo od konca z ujemna rozwartoscia
+        for (start, sweep) in [(135.0, 90.0), (-135.0, -90.0)] {
+            let pixels = render_arc(start, sweep);
+            assert!(pixels.contains(&(-10, 0)));
+            assert!(pixels.iter().all(|&(dx, dy)| dx < 0 && dy.abs() <= -dx));
+        }
+    }
+
+    fn wu_drawer(size: usize) -> MyPolygonDrawer {
+        let mut drawer = MyPolygonDrawer::new();
+        drawer.antialiasing = true;
+        drawer.thickness = 1.0;
+        drawer.framebuffer.borrow_mut().reset(size, size, (0, 0));
+        drawer
+    }
+
+    // pokrycie kolumny x jako suma alf jej pikseli
+    fn column_coverage(framebuffer: &Framebuffer, x: i32) -> f32 {
+        (0..framebuffer.height as i32).filter_map(|y| framebuffer.pixel(x, y)).map(|c| c.a() as f32 / 255.0).sum()
+    }
+
+    #[test]
+    fn wu_line_splits_coverage_between_pixels() {
+        let drawer = wu_drawer(12);
+        drawer.wu_line(Pos2::new(2.0, 2.0), Pos2::new(8.0, 5.0), Color32::WHITE);
+        let framebuffer = drawer.framebuffer.borrow();
+        // nachylenie 1/2: pionowy przekroj pasa o grubosci 1 ma sqrt(1.25) ~ 1.118
+        for x in 2..8 {
+            assert!((column_coverage(&framebuffer, x) - 1.118).abs() < 0.02, "kolumna {}", x);
+        }
+        // w x = 2 os linii jest na y = 2.25: 0.31 pokrycia nad pikselem 2, reszta w nim
+        let alpha = |y: i32| framebuffer.pixel(2, y).unwrap().a() as i32;
+        assert!((alpha(1) - 79).abs() <= 2 && (alpha(2) - 206).abs() <= 2);
+        assert_eq!(alpha(3), 0);
+    }
+
+    #[test]
+    fn wu_line_clips_coverage_at_endpoints() {
+        let drawer = wu_drawer(12);
+        drawer.wu_line(Pos2::new(2.5, 4.0), Pos2::new(8.0, 4.0), Color32::WHITE);
+        let framebuffer = drawer.framebuffer.borrow();
+        assert_eq!(column_coverage(&framebuffer, 1), 0.0);
+        assert!((column_coverage(&framebuffer, 2) - 0.5).abs() < 0.02);
+        assert!((column_coverage(&framebuffer, 5) - 1.0).abs() < 0.02);
+        assert!((column_coverage(&framebuffer, 7) - 1.0).abs() < 0.02);
+        // odcinek konczy sie na krawedzi kolumny 8
+        assert_eq!(column_coverage(&framebuffer, 8), 0.0);
+    }
+
+    #[test]
+    fn wu_arc_coverage_stays_near_radius() {
+        let drawer = wu_drawer(41);
+        drawer.wu_arc(Point::new(20.0, 20.0), 10.0, 0.0, std::f32::consts::TAU, Color32::WHITE);
+        let framebuffer = drawer.framebuffer.borrow();
+        let mut total = 0.0;
+        for (x, y) in lit(&framebuffer) {
+            let r = (((x - 20) * (x - 20) + (y - 20) * (y - 20)) as f32).sqrt();
+            assert!((r - 10.0).abs() <= 1.0, "piksel ({}, {}) w odleglosci {}", x, y, r);
+            total += framebuffer.pixel(x, y).unwrap().a() as f32 / 255.0;
+        }
+        // suma pokrycia to pole pierscienia o grubosci 1: 2 pi r
+        assert!((total - 2.0 * std::f32::consts::PI * 10.0).abs() < 2.0, "pokrycie {}", total);
+    }
+
+    #[test]
+    fn pixels_outside_buffer_are_clipped() {
+        let framebuffer = MyPolygonDrawer::new().render_offscreen(&[], 4, 4);
+        assert_eq!(framebuffer.pixel(3, 3), Some(Color32::TRANSPARENT));
+        assert_eq!(framebuffer.pixel(4, 0), None);
+        assert_eq!(framebuffer.pixel(-1, 2), None);
+    }
+
+    #[test]
+    fn fill_rule_decides_star_center() {
+        // pentagram: srodek jest obiegany dwa razy, ramiona raz
+        let star: Vec<Point> = (0..5)
+            .map(|k| {
+                let angle = (k as f32 * 144.0 - 90.0).to_radians();
+                Point::new(20.0 + 18.0 * angle.cos(), 20.0 + 18.0 * angle.sin())
+            })
+            .collect();
+        let fill = Color32::from_rgb(255, 0, 0);
+        let render = |rule: FillRule| {
+            let mut polygon = Polygon::new(star.clone());
+            polygon.fill = Some([255, 0, 0, 255]);
+            polygon.fill_rule = rule;
+            MyPolygonDrawer::new().render_offscreen(&[polygon], 40, 40)
+        };
+        let (center, arm) = ((20, 20), (20, 11));
+
+        let even_odd = render(FillRule::EvenOdd);
+        assert_eq!(even_odd.pixel(center.0, center.1), Some(Color32::TRANSPARENT));
+        assert_eq!(even_odd.pixel(arm.0, arm.1), Some(fill));
+
+        let non_zero = render(FillRule::NonZero);
+        assert_eq!(non_zero.pixel(center.0, center.1), Some(fill));
+        assert_eq!(non_zero.pixel(arm.0, arm.1), Some(fill));
+        assert_eq!(non_zero.pixel(1, 1), Some(Color32::TRANSPARENT));
+    }
+}
@@ -0,0 +1,48 @@
+use eframe::egui;
+use crate::geometry::polygon::Polygon;
+use crate::geometry::relation::Relation;
+use crate::view::viewport::Viewport;
+
+const RELATION_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 170, 80);
+
+/// Znaczki relacji: przy obu krawędziach ten sam symbol z numerem relacji,
+/// żeby było widać, które krawędzie są ze sobą powiązane; kąt rysujemy przy wierzchołku.
+/// Odstępy znaczków od krawędzi są w pikselach ekranu, niezależnie od powiększenia.
+pub fn draw_relations(painter: &egui::Painter, view: &Viewport, polygons: &[Polygon], relations: &[Relation]) {
+    for (k, relation) in relations.iter().enumerate() {
+        if !relation.is_valid(polygons) {
+            continue;
+        }
+        match relation {
+            Relation::FixedAngle { at, .. } => {
+                let v = view.to_screen(polygons[at.polygon].vertices[at.vertex]);
+                painter.text(
+                    v + egui::vec2(8.0, 8.0),
+                    egui::Align2::LEFT_TOP,
+                    relation.glyph(),
+                    egui::FontId::monospace(12.0),
+                    RELATION_COLOR,
+                );
+            }
+            _ => {
+                let text = format!("{}{}", relation.glyph(), k + 1);
+                for e in relation.edges() {
+                    let polygon = &polygons[e.polygon];
+                    let n = polygon.vertices.len();
+                    let a = view.to_screen(polygon.vertices[e.edge]);
+                    let b = view.to_screen(polygon.vertices[(e.edge + 1) % n]);
+                    // odsuwamy znaczek od krawedzi, zeby nie zaslanial etykiety ograniczenia
+                    let normal = (b - a).rot90().normalized();
+                    let mid = a + (b - a) * 0.5 + normal * 14.0;
+                    painter.text(
+                        mid,
+                        egui::Align2::CENTER_CENTER,
+                        &text,
+                        egui::FontId::monospace(12.0),
+                        RELATION_COLOR,
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use eframe::egui;
+use crate::geometry::point::Point;
+use crate::geometry::polygon::{FillRule, Polygon};
+use crate::view::viewport::Viewport;
+
+/// Poziomy odcinek do zamalowania: piksele `x_start..x_end` w wierszu `y`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Span {
+    pub y: i32,
+    pub x_start: i32,
+    pub x_end: i32,
+}
+
+// krawedz w tablicy krawedzi (ET/AET)
+struct Edge {
+    y_start: i32, // pierwszy wiersz, ktory przecina (wlacznie)
+    y_end: i32,   // wiersz konca (wylacznie)
+    x: f32,       // x na srodku aktualnego wiersza
+    dx_dy: f32,
+    winding: i32,
+}
+
+/// Wypełnianie scanline z aktywną tablicą krawędzi. Wszystkie kontury trafiają do jednej
+/// tablicy, więc samoprzecięcia i dziury (`Polygon::outlines`) obsługuje sama reguła wypełniania.
+/// Piksel (x, y) jest zamalowany, jeśli jego środek (x + 0.5, y + 0.5) leży wewnątrz.
+pub fn scanline_spans(contours: &[Vec<Point>], rule: FillRule) -> Vec<Span> {
+    // ET: krawedzie posortowane wg pierwszego wiersza
+    let mut edge_table: Vec<Edge> = Vec::new();
+    for contour in contours {
+        let n = contour.len();
+        if n < 3 {
+            continue;
+        }
+        for i in 0..n {
+            let a = contour[i];
+            let b = contour[(i + 1) % n];
+            if a.y == b.y {
+                continue; // poziome krawedzie nie przecinaja scanlinii
+            }
+            let (top, bottom, winding) = if a.y < b.y { (a, b, 1) } else { (b, a, -1) };
+            let y_start = (top.y - 0.5).ceil() as i32;
+            let y_end = (bottom.y - 0.5).ceil() as i32;
+            if y_start >= y_end {
+                continue;
+            }
+            let dx_dy = (bottom.x - top.x) / (bottom.y - top.y);
+            let x = top.x + (y_start as f32 + 0.5 - top.y) * dx_dy;
+            edge_table.push(Edge { y_start, y_end, x, dx_dy, winding });
+        }
+    }
+    if edge_table.is_empty() {
+        return Vec::new();
+    }
+    edge_table.sort_by_key(|e| std::cmp::Reverse(e.y_start)); // zdejmujemy z konca
+
+    let mut spans = Vec::new();
+    let mut active: Vec<Edge> = Vec::new();
+    let mut y = edge_table.last().map(|e| e.y_start).unwrap_or(0);
+
+    while !edge_table.is_empty() || !active.is_empty() {
+        while edge_table.last().is_some_and(|e| e.y_start == y) {
+            active.push(edge_table.pop().unwrap());
+        }
+        active.retain(|e| e.y_end > y);
+        if active.is_empty() {
+            match edge_table.last() {
+                Some(e) => {
+                    y = e.y_start;
+                    continue;
+                }
+                None => break,
+            }
+        }
+        active.sort_by(|a, b| a.x.total_cmp(&b.x));
+
+        let mut winding = 0;
+        for pair in active.windows(2) {
+            let (left, right) = (&pair[0], &pair[1]);
+            winding += left.winding;
+            if !rule.contains(winding) {
+                continue;
+            }
+            let x_start = (left.x - 0.5).ceil() as i32;
+            let x_end = (right.x - 0.5).ceil() as i32;
+            if x_start < x_end {
+                // sklejamy z poprzednim odcinkiem, jesli sie stykaja (non-zero, przeciecia)
+                match spans.last_mut() {
+                    Some(Span { y: last_y, x_end: last_end, .. }) if *last_y == y && *last_end >= x_start => {
+                        *last_end = (*last_end).max(x_end);
+                    }
+                    _ => spans.push(Span { y, x_start, x_end }),
+                }
+            }
+        }
+
+        y += 1;
+        for e in &mut active {
+            e.x += e.dx_dy;
+        }
+    }
+    spans
+}
+
+/// Zamalowuje odcinki - jeden prostokąt o wysokości piksela na odcinek.
+pub fn paint_spans(painter: &egui::Painter, spans: &[Span], color: egui::Color32) {
+    for span in spans {
+        painter.rect_filled(
+            egui::Rect::from_min_max(
+                egui::pos2(span.x_start as f32, span.y as f32),
+                egui::pos2(span.x_end as f32, (span.y + 1) as f32),
+            ),
+            0.0,
+            color,
+        );
+    }
+}
+
+/// Wypełnia wielokąt (we współrzędnych świata) w pikselach ekranu widoku `view`.
+pub fn fill_polygon(painter: &egui::Painter, polygon: &Polygon, view: Viewport) {
+    if let (true, Some([r, g, b, a])) = (polygon.closed, polygon.fill) {
+        let spans = scanline_spans(&view.outlines_to_screen(polygon), polygon.fill_rule);
+        paint_spans(painter, &spans, egui::Color32::from_rgba_unmultiplied(r, g, b, a));
+    }
+}
@@ -0,0 +1,136 @@
+use eframe::egui;
+use crate::geometry::point::Point;
+use crate::geometry::curves::DEFAULT_FLATNESS;
+use crate::geometry::polygon::Polygon;
+
+pub const MIN_ZOOM: f32 = 0.05;
+pub const MAX_ZOOM: f32 = 40.0;
+// "dopasuj" zostawia tyle pikseli wolnego miejsca przy brzegu plotna
+const FIT_PADDING: f32 = 30.0;
+
+/// Przejście między współrzędnymi świata (w nich trzymamy geometrię) a pikselami ekranu:
+/// ekran = `pan` + świat · `zoom`. Domyślny widok to tożsamość, więc zapisane wcześniej
+/// sceny leżą na ekranie tam, gdzie przed wprowadzeniem powiększania.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Viewport {
+    pub pan: egui::Vec2,
+    pub zoom: f32,
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Self { pan: egui::Vec2::ZERO, zoom: 1.0 }
+    }
+}
+
+impl Viewport {
+    pub fn to_screen(self, p: Point) -> egui::Pos2 {
+        egui::Pos2::ZERO + self.pan + egui::vec2(p.x, p.y) * self.zoom
+    }
+
+    pub fn to_world(self, pos: egui::Pos2) -> Point {
+        let world = (pos.to_vec2() - self.pan) / self.zoom;
+        Point::new(world.x, world.y)
+    }
+
+    /// Jak `to_screen`, ale jako `Point` z zachowaną ciągłością - dla kodu rysującego, który
+    /// liczy na punktach (krzywe, łuki, etykiety G1/C1/G2).
+    pub fn point_to_screen(self, p: Point) -> Point {
+        let s = self.to_screen(p);
+        Point { x: s.x, y: s.y, ..p }
+    }
+
+    /// Kontury wielokąta (z dziurami) w pikselach ekranu, do wypełnienia. Krzywe spłaszczamy
+    /// z tolerancją przeliczoną na świat, żeby przy powiększeniu nie robiły się kanciaste.
+    pub fn outlines_to_screen(self, polygon: &Polygon) -> Vec<Vec<Point>> {
+        polygon
+            .outlines_within(DEFAULT_FLATNESS / self.zoom)
+            .into_iter()
+            .map(|ring| ring.into_iter().map(|p| self.point_to_screen(p)).collect())
+            .collect()
+    }
+
+    /// Odległość w pikselach ekranu przeliczona na jednostki świata - promienie trafienia
+    /// i uchwyty mają mieć stały rozmiar na ekranie niezależnie od powiększenia.
+    pub fn world_len(&self, pixels: f32) -> f32 {
+        pixels / self.zoom
+    }
+
+    /// Przesunięcie myszy na ekranie jako przesunięcie w świecie.
+    pub fn world_delta(&self, delta: egui::Vec2) -> (f32, f32) {
+        (delta.x / self.zoom, delta.y / self.zoom)
+    }
+
+    pub fn pan_by(&mut self, delta: egui::Vec2) {
+        self.pan += delta;
+    }
+
+    /// Powiększenie o `factor` tak, żeby punkt świata pod kursorem `anchor` został na miejscu.
+    pub fn zoom_at(&mut self, anchor: egui::Pos2, factor: f32) {
+        let under_cursor = self.to_world(anchor);
+        self.zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+        self.pan += anchor - self.to_screen(under_cursor);
+    }
+
+    /// Dobiera powiększenie i przesunięcie tak, żeby wszystkie wielokąty zmieściły się w `canvas`.
+    pub fn fit(&mut self, polygons: &[Polygon], canvas: egui::Rect) {
+        let points: Vec<Point> = polygons.iter().flat_map(|p| p.outlines()).flatten().collect();
+        let Some(first) = points.first() else {
+            return;
+        };
+        let (min, max) = points.iter().fold((*first, *first), |(min, max), p| {
+            (Point::new(min.x.min(p.x), min.y.min(p.y)), Point::new(max.x.max(p.x), max.y.max(p.y)))
+        });
+        let size = egui::vec2((max.x - min.x).max(1.0), (max.y - min.y).max(1.0));
+        let room = (canvas.size() - egui::vec2(2.0 * FIT_PADDING, 2.0 * FIT_PADDING)).max(egui::vec2(1.0, 1.0));
+        self.zoom = (room.x / size.x).min(room.y / size.y).clamp(MIN_ZOOM, MAX_ZOOM);
+        let center = (min + max) * 0.5;
+        self.pan = canvas.center().to_vec2() - egui::vec2(center.x, center.y) * self.zoom;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn screen_and_world_round_trip() {
+        let view = Viewport { pan: egui::vec2(30.0, -12.0), zoom: 2.5 };
+        let p = Point::new(17.0, -4.0);
+        let screen = view.to_screen(p);
+        assert_eq!(screen, egui::pos2(72.5, -22.0));
+        let back = view.to_world(screen);
+        assert!(back.distance(&p) < 1e-4);
+        // domyslny widok to tozsamosc
+        assert_eq!(Viewport::default().to_screen(p), egui::pos2(17.0, -4.0));
+    }
+
+    #[test]
+    fn zoom_keeps_point_under_cursor() {
+        let mut view = Viewport { pan: egui::vec2(10.0, 20.0), zoom: 1.0 };
+        let cursor = egui::pos2(200.0, 150.0);
+        let under = view.to_world(cursor);
+        for factor in [1.5, 0.2, 1000.0] {
+            view.zoom_at(cursor, factor);
+            assert!(view.to_world(cursor).distance(&under) < 1e-3);
+        }
+        // powiekszenie zatrzymuje sie na granicy
+        assert_eq!(view.zoom, MAX_ZOOM);
+    }
+
+    #[test]
+    fn fit_centers_polygons_in_canvas() {
+        let polygon = Polygon::new(vec![Point::new(0.0, 0.0), Point::new(100.0, 0.0), Point::new(100.0, 50.0), Point::new(0.0, 50.0)]);
+        let canvas = egui::Rect::from_min_size(egui::pos2(0.0, 0.0), egui::vec2(460.0, 460.0));
+        let mut view = Viewport::default();
+        view.fit(&[polygon], canvas);
+        // szerokosc 100 wypelnia 460 - 2 * FIT_PADDING
+        assert!((view.zoom - 4.0).abs() < 1e-4);
+        assert!((view.to_screen(Point::new(50.0, 25.0)) - canvas.center()).length() < 1e-3);
+
+        // pusta scena zostawia widok bez zmian
+        let before = view;
+        view.fit(&[], canvas);
+        assert_eq!(view, before);
+    }
+}